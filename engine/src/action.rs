@@ -1,5 +1,105 @@
+use crate::assets::{handles::MeshHandle, mesh::Vertex, mesh_resource::MeshResource};
+
 #[allow(dead_code)]
 pub trait Action {
     fn execute(&mut self);
     fn undo(&mut self);
 }
+
+/// Re-winds a stored mesh with `Mesh::orient_normals_consistent`, keeping the previous
+/// indices and vertices around so the repair can be undone. Both steps mark the mesh as
+/// modified, so the renderer uploads it again.
+#[allow(dead_code)]
+pub struct OrientMeshNormalsAction {
+    meshes: MeshResource,
+    mesh: MeshHandle,
+    previous: Option<(Vec<u32>, Vec<Vertex>)>,
+}
+
+#[allow(dead_code)]
+impl OrientMeshNormalsAction {
+    pub fn new(meshes: MeshResource, mesh: MeshHandle) -> Self {
+        Self {
+            meshes,
+            mesh,
+            previous: None,
+        }
+    }
+}
+
+impl Action for OrientMeshNormalsAction {
+    fn execute(&mut self) {
+        let mut storage = self.meshes.write();
+        let Some(mesh) = storage.get_mesh_mut(self.mesh) else {
+            log::error!("Mesh {:?} not found, cannot orient normals", self.mesh);
+            return;
+        };
+        self.previous = Some((mesh.indices.clone(), mesh.vertices.clone()));
+        mesh.orient_normals_consistent();
+        storage.mark_modified(self.mesh);
+    }
+
+    fn undo(&mut self) {
+        let Some((indices, vertices)) = self.previous.take() else {
+            return;
+        };
+        let mut storage = self.meshes.write();
+        if let Some(mesh) = storage.get_mesh_mut(self.mesh) {
+            mesh.indices = indices;
+            mesh.vertices = vertices;
+            if mesh.bvh.is_some() {
+                mesh.build_bvh(8);
+            }
+            storage.mark_modified(self.mesh);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action_manager::ActionManager;
+    use crate::assets::mesh::Mesh;
+
+    #[test]
+    fn orient_normals_action_undo_restores_winding() {
+        let mut mesh = Mesh {
+            vertices: [
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [1.0, 1.0, 0.0],
+            ]
+            .iter()
+            .map(|p| Vertex {
+                position: *p,
+                ..Default::default()
+            })
+            .collect(),
+            // Second triangle wound the other way round
+            indices: vec![0, 1, 2, 1, 2, 3],
+            ..Default::default()
+        };
+        mesh.recompute_normals();
+        let original = mesh.indices.clone();
+
+        let meshes = MeshResource::default();
+        let handle = meshes.write().add_mesh(mesh);
+
+        let mut manager = ActionManager::new();
+        manager.execute(Box::new(OrientMeshNormalsAction::new(
+            meshes.clone(),
+            handle,
+        )));
+        assert_eq!(
+            meshes.read().get_mesh(handle).unwrap().indices,
+            vec![0, 1, 2, 1, 3, 2]
+        );
+        assert_eq!(meshes.read().modified, vec![handle]);
+
+        meshes.write().modified.clear();
+        manager.undo();
+        assert_eq!(meshes.read().get_mesh(handle).unwrap().indices, original);
+        assert_eq!(meshes.read().modified, vec![handle]);
+    }
+}
@@ -10,6 +10,11 @@ pub struct MaterialStorage {
     pub materials: SlotMap<MaterialHandle, Material>,
 }
 
+/// The material given to loaded models that define none of their own. When unset, each such
+/// model gets its own plain white material.
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct DefaultMaterial(pub Option<MaterialHandle>);
+
 #[derive(Resource, Default, Clone)]
 pub struct MaterialResource(pub Arc<RwLock<MaterialStorage>>);
 impl MaterialResource {
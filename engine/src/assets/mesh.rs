@@ -3,12 +3,13 @@
 use approx::relative_eq;
 use bytemuck::{Pod, Zeroable};
 use glam::Vec3;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::{Hash, Hasher};
 
 use crate::components::collider_component::{BVHNode, Triangle};
 
 #[repr(C)]
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
 pub struct Vertex {
     pub position: [f32; 3],
     pub normal: [f32; 3],
@@ -16,6 +17,18 @@ pub struct Vertex {
     pub uv_albedo: [f32; 2],
     pub uv_normal: [f32; 2],
     pub tangent: [f32; 4],
+    /// Linear RGB multiplied into the material's base color. White for meshes without
+    /// vertex colors.
+    pub color: [f32; 3],
+}
+
+impl Default for Vertex {
+    fn default() -> Self {
+        Self {
+            color: Vertex::WHITE,
+            ..Vertex::zeroed()
+        }
+    }
 }
 
 impl PartialEq for Vertex {
@@ -35,6 +48,7 @@ unsafe impl Zeroable for Vertex {
             uv_albedo: [0.0, 0.0],
             uv_normal: [0.0, 0.0],
             tangent: [0.0, 0.0, 0.0, 0.0],
+            color: [0.0, 0.0, 0.0],
         }
     }
 }
@@ -51,6 +65,8 @@ impl Hash for Vertex {
 }
 
 impl Vertex {
+    pub const WHITE: [f32; 3] = [1.0, 1.0, 1.0];
+
     pub(crate) fn stride() -> i32 {
         std::mem::size_of::<Vertex>() as i32
     }
@@ -104,6 +120,35 @@ impl Aabb {
         tmax >= tmin.max(0.0)
     }
 
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    /// Whether the segment from `start` to `end` passes through this box.
+    pub fn intersects_segment(&self, start: Vec3, end: Vec3) -> bool {
+        let dir = end - start;
+        let mut t_min = 0.0_f32;
+        let mut t_max = 1.0_f32;
+        for axis in 0..3 {
+            if dir[axis].abs() <= f32::EPSILON {
+                // Parallel to this slab: the segment must already be inside it
+                if start[axis] < self.min[axis] || start[axis] > self.max[axis] {
+                    return false;
+                }
+                continue;
+            }
+            let inv = 1.0 / dir[axis];
+            let t1 = (self.min[axis] - start[axis]) * inv;
+            let t2 = (self.max[axis] - start[axis]) * inv;
+            t_min = t_min.max(t1.min(t2));
+            t_max = t_max.min(t1.max(t2));
+            if t_min > t_max {
+                return false;
+            }
+        }
+        true
+    }
+
     pub(crate) fn from_vertices(vertices: &[Vertex]) -> Self {
         // Initialize min and max with the first vertex
         let mut min = vertices[0];
@@ -157,10 +202,47 @@ impl Aabb {
     }
 }
 
+/// Which triangle winding, seen from outside the mesh, faces the viewer. Formats disagree:
+/// glTF mandates counter-clockwise, while OBJ and STL exporters vary.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrontFace {
+    #[default]
+    Ccw,
+    Cw,
+    /// Picks `Ccw` or `Cw` from the sign of the mesh volume; only meaningful for closed meshes.
+    Auto,
+}
+
+impl FrontFace {
+    /// Guesses the winding of a closed mesh: outward counter-clockwise triangles enclose a
+    /// positive signed volume.
+    pub fn detect(mesh: &Mesh) -> FrontFace {
+        if mesh.signed_volume() < 0.0 {
+            FrontFace::Cw
+        } else {
+            FrontFace::Ccw
+        }
+    }
+}
+
+/// Geometry summary of a mesh, see [`Mesh::compute_stats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MeshStats {
+    /// Enclosed volume; only meaningful when `boundary_edge_count` is zero.
+    pub volume: f32,
+    pub surface_area: f32,
+    pub triangle_count: usize,
+    /// Edges used by exactly one triangle. Zero suggests a watertight mesh.
+    pub boundary_edge_count: usize,
+}
+
 #[derive(Default, Clone)]
 pub struct Mesh {
     pub vertices: Vec<Vertex>,
     pub indices: Vec<u32>,
+    /// Winding of front-facing triangles, used for normal computation and face culling.
+    /// Never `Auto`; see `set_front_face`.
+    pub front_face: FrontFace,
 
     // Bounds
     pub aabb: Aabb,
@@ -193,7 +275,18 @@ impl Mesh {
         self.bvh = Some(BVHNode::build(triangles, max_leaf_size));
     }
 
-    fn triangles_from_indices(&self) -> Vec<Triangle> {
+    /// Builds a collision BVH from a decimated copy of the triangles, keeping roughly `ratio`
+    /// of them. The mesh itself, including its own BVH, is left untouched.
+    pub fn simplified_bvh(&self, max_leaf_size: usize, ratio: f32) -> Option<BVHNode> {
+        let triangles = decimate_triangles(&self.triangles_from_indices(), ratio);
+        if triangles.is_empty() {
+            return None;
+        }
+
+        Some(BVHNode::build(triangles, max_leaf_size))
+    }
+
+    pub(crate) fn triangles_from_indices(&self) -> Vec<Triangle> {
         let mut tris = Vec::with_capacity(self.indices.len() / 3);
         for indices in self.indices.chunks(3) {
             if indices.len() < 3 {
@@ -213,6 +306,204 @@ impl Mesh {
         tris
     }
 
+    /// Re-winds triangles so that every connected patch of the mesh shares one orientation.
+    /// Closed patches are additionally turned outward, as judged by `front_face`. Vertex normals are recomputed afterwards.
+    /// Returns the number of triangles that were flipped.
+    pub fn orient_normals_consistent(&mut self) -> usize {
+        let tri_count = self.indices.len() / 3;
+        if tri_count == 0 {
+            return 0;
+        }
+
+        // Vertices are often split per face, so weld by position to find shared edges.
+        let mut welded_ids: HashMap<[u32; 3], u32> = HashMap::with_capacity(self.vertices.len());
+        let welded: Vec<u32> = self
+            .vertices
+            .iter()
+            .map(|v| {
+                let next_id = welded_ids.len() as u32;
+                *welded_ids.entry(v.position_bits()).or_insert(next_id)
+            })
+            .collect();
+
+        let corner = |t: usize, c: usize| welded[self.indices[t * 3 + c] as usize];
+
+        // Undirected edge -> (triangle, whether the triangle walks the edge from low to high)
+        let mut edges: HashMap<(u32, u32), Vec<(usize, bool)>> = HashMap::new();
+        for t in 0..tri_count {
+            for c in 0..3 {
+                let a = corner(t, c);
+                let b = corner(t, (c + 1) % 3);
+                if a == b {
+                    continue;
+                }
+                edges
+                    .entry((a.min(b), a.max(b)))
+                    .or_default()
+                    .push((t, a < b));
+            }
+        }
+
+        let mut flip: Vec<Option<bool>> = vec![None; tri_count];
+        let mut queue = VecDeque::new();
+        let mut component = Vec::new();
+
+        for seed in 0..tri_count {
+            if flip[seed].is_some() {
+                continue;
+            }
+            flip[seed] = Some(false);
+            queue.push_back(seed);
+            component.clear();
+            let mut closed = true;
+
+            while let Some(t) = queue.pop_front() {
+                component.push(t);
+                let t_flip = flip[t].unwrap_or(false);
+                for c in 0..3 {
+                    let a = corner(t, c);
+                    let b = corner(t, (c + 1) % 3);
+                    if a == b {
+                        continue;
+                    }
+                    let Some(shared) = edges.get(&(a.min(b), a.max(b))) else {
+                        continue;
+                    };
+                    if shared.len() != 2 {
+                        closed = false;
+                    }
+                    let t_forward = (a < b) ^ t_flip;
+                    for &(n, n_dir) in shared {
+                        if n == t || flip[n].is_some() {
+                            continue;
+                        }
+                        // Neighbours must walk a shared edge in opposite directions.
+                        flip[n] = Some(n_dir == t_forward);
+                        queue.push_back(n);
+                    }
+                }
+            }
+
+            if closed {
+                let mut signed_volume = 0.0;
+                for &t in &component {
+                    let p0 = Vec3::from(self.vertices[self.indices[t * 3] as usize].position);
+                    let mut p1 =
+                        Vec3::from(self.vertices[self.indices[t * 3 + 1] as usize].position);
+                    let mut p2 =
+                        Vec3::from(self.vertices[self.indices[t * 3 + 2] as usize].position);
+                    if flip[t] == Some(true) {
+                        std::mem::swap(&mut p1, &mut p2);
+                    }
+                    signed_volume += p0.dot(p1.cross(p2));
+                }
+                // Outward clockwise triangles enclose a negative volume
+                if (signed_volume < 0.0) != (self.front_face == FrontFace::Cw) {
+                    for &t in &component {
+                        flip[t] = flip[t].map(|f| !f);
+                    }
+                }
+            }
+        }
+
+        let mut flipped = 0;
+        for (t, f) in flip.iter().enumerate() {
+            if *f == Some(true) {
+                self.indices.swap(t * 3 + 1, t * 3 + 2);
+                flipped += 1;
+            }
+        }
+
+        if flipped > 0 {
+            self.recompute_normals();
+            if self.bvh.is_some() {
+                self.build_bvh(8);
+            }
+        }
+        flipped
+    }
+
+    /// Sets the front-face winding, resolving `FrontFace::Auto` against the current triangles.
+    /// Normals are left untouched; call `recompute_normals` if they were derived from winding.
+    pub fn set_front_face(&mut self, front_face: FrontFace) {
+        self.front_face = match front_face {
+            FrontFace::Auto => FrontFace::detect(self),
+            other => other,
+        };
+    }
+
+    /// Volume enclosed by the triangles, positive when they wind counter-clockwise seen from
+    /// outside.
+    pub fn signed_volume(&self) -> f32 {
+        self.indices
+            .chunks_exact(3)
+            .filter(|tri| tri.iter().all(|i| (*i as usize) < self.vertices.len()))
+            .map(|tri| {
+                let p0 = Vec3::from(self.vertices[tri[0] as usize].position);
+                let p1 = Vec3::from(self.vertices[tri[1] as usize].position);
+                let p2 = Vec3::from(self.vertices[tri[2] as usize].position);
+                p0.dot(p1.cross(p2))
+            })
+            .sum::<f32>()
+            / 6.0
+    }
+
+    /// Volume, surface area, triangle count and boundary edges. Edges are matched by vertex
+    /// position, so meshes with split vertices (e.g. for flat shading) still count as closed.
+    pub fn compute_stats(&self) -> MeshStats {
+        let mut stats = MeshStats {
+            volume: self.signed_volume().abs(),
+            ..Default::default()
+        };
+        let mut edge_uses: HashMap<([u32; 3], [u32; 3]), usize> = HashMap::new();
+        for tri in self.indices.chunks_exact(3) {
+            let Some(vertices) = tri
+                .iter()
+                .map(|i| self.vertices.get(*i as usize))
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+            let [p0, p1, p2] = [0, 1, 2].map(|k| Vec3::from(vertices[k].position));
+            stats.surface_area += (p1 - p0).cross(p2 - p0).length() * 0.5;
+            stats.triangle_count += 1;
+
+            for (a, b) in [(0, 1), (1, 2), (2, 0)] {
+                let (a, b) = (vertices[a].position_bits(), vertices[b].position_bits());
+                *edge_uses.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+        stats.boundary_edge_count = edge_uses.values().filter(|uses| **uses == 1).count();
+        stats
+    }
+
+    /// Rebuilds vertex normals from the triangle winding, weighted by triangle area.
+    /// Normals point towards the side `front_face` says is the front.
+    pub fn recompute_normals(&mut self) {
+        let winding = if self.front_face == FrontFace::Cw {
+            -1.0
+        } else {
+            1.0
+        };
+        let mut normals = vec![Vec3::ZERO; self.vertices.len()];
+        for tri in self.indices.chunks_exact(3) {
+            let [i0, i1, i2] = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+            if i0 >= normals.len() || i1 >= normals.len() || i2 >= normals.len() {
+                continue;
+            }
+            let p0 = Vec3::from(self.vertices[i0].position);
+            let p1 = Vec3::from(self.vertices[i1].position);
+            let p2 = Vec3::from(self.vertices[i2].position);
+            let face = (p1 - p0).cross(p2 - p0) * winding;
+            normals[i0] += face;
+            normals[i1] += face;
+            normals[i2] += face;
+        }
+        for (vertex, normal) in self.vertices.iter_mut().zip(normals) {
+            vertex.normal = normal.normalize_or_zero().into();
+        }
+    }
+
     pub fn compute_bounding_sphere(&mut self) {
         // Center = AABB center
         self.sphere_center = (self.aabb.min + self.aabb.max) * 0.5;
@@ -300,6 +591,84 @@ impl Mesh {
     }
 }
 
+/// Vertex-clustering decimation. Snaps corners to a uniform grid, merges each cell into the
+/// average of its corners and drops triangles that collapse. The grid resolution is searched for
+/// the finest one that keeps at most `ratio` of the input triangles.
+pub(crate) fn decimate_triangles(triangles: &[Triangle], ratio: f32) -> Vec<Triangle> {
+    if triangles.is_empty() || ratio.is_nan() || ratio >= 1.0 {
+        return triangles.to_vec();
+    }
+    let target = ((triangles.len() as f32 * ratio.max(0.0)).ceil() as usize).max(1);
+
+    let mut min = triangles[0].v0;
+    let mut max = triangles[0].v0;
+    for tri in triangles {
+        min = min.min(tri.v0).min(tri.v1).min(tri.v2);
+        max = max.max(tri.v0).max(tri.v1).max(tri.v2);
+    }
+    let extent = (max - min).max_element();
+    if extent <= f32::EPSILON {
+        return triangles.to_vec();
+    }
+
+    let mut best = None;
+    let (mut lo, mut hi) = (1_u32, 1024_u32);
+    while lo <= hi {
+        let resolution = lo + (hi - lo) / 2;
+        let clustered = cluster_triangles(triangles, min, extent / resolution as f32);
+        if clustered.len() > target {
+            hi = resolution - 1;
+        } else {
+            if !clustered.is_empty() {
+                best = Some(clustered);
+            }
+            lo = resolution + 1;
+        }
+    }
+
+    best.unwrap_or_else(|| triangles.to_vec())
+}
+
+fn cluster_triangles(triangles: &[Triangle], origin: Vec3, cell_size: f32) -> Vec<Triangle> {
+    let cell_of = |p: Vec3| {
+        let c = ((p - origin) / cell_size).floor();
+        [c.x as i32, c.y as i32, c.z as i32]
+    };
+
+    let mut sums: HashMap<[i32; 3], (Vec3, u32)> = HashMap::new();
+    for tri in triangles {
+        for p in [tri.v0, tri.v1, tri.v2] {
+            let entry = sums.entry(cell_of(p)).or_insert((Vec3::ZERO, 0));
+            entry.0 += p;
+            entry.1 += 1;
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut out = Vec::new();
+    for tri in triangles {
+        let cells = [cell_of(tri.v0), cell_of(tri.v1), cell_of(tri.v2)];
+        if cells[0] == cells[1] || cells[1] == cells[2] || cells[0] == cells[2] {
+            continue;
+        }
+        let mut key = cells;
+        key.sort_unstable();
+        if !seen.insert(key) {
+            continue;
+        }
+
+        let [v0, v1, v2] = cells.map(|cell| {
+            let (sum, count) = sums[&cell];
+            sum / count as f32
+        });
+        if (v1 - v0).cross(v2 - v0).length_squared() <= f32::EPSILON {
+            continue;
+        }
+        out.push(Triangle { v0, v1, v2 });
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -334,6 +703,57 @@ mod tests {
         assert_eq!(aabb.max, Vec3::new(7.0, 8.0, 9.0));
     }
 
+    fn tetrahedron() -> Mesh {
+        let positions = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ];
+        let mut mesh = Mesh {
+            vertices: positions
+                .iter()
+                .map(|p| Vertex {
+                    position: *p,
+                    ..Vertex::zeroed()
+                })
+                .collect(),
+            // Outward winding
+            indices: vec![0, 2, 1, 0, 1, 3, 0, 3, 2, 1, 2, 3],
+            ..Default::default()
+        };
+        mesh.recompute_normals();
+        mesh
+    }
+
+    #[test]
+    fn orient_normals_consistent_fixes_flipped_triangle() {
+        let reference = tetrahedron();
+        let mut mesh = tetrahedron();
+        mesh.indices.swap(4, 5);
+
+        let flipped = mesh.orient_normals_consistent();
+
+        assert_eq!(flipped, 1);
+        assert_eq!(mesh.indices, reference.indices);
+        for (a, b) in mesh.vertices.iter().zip(reference.vertices.iter()) {
+            assert!((Vec3::from(a.normal) - Vec3::from(b.normal)).length() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn orient_normals_consistent_turns_inside_out_mesh_outward() {
+        let reference = tetrahedron();
+        let mut mesh = tetrahedron();
+        for tri in mesh.indices.chunks_exact_mut(3) {
+            tri.swap(1, 2);
+        }
+
+        assert_eq!(mesh.orient_normals_consistent(), 4);
+        assert_eq!(mesh.indices, reference.indices);
+        assert_eq!(mesh.orient_normals_consistent(), 0);
+    }
+
     #[test]
     fn compute_bounding_sphere() {
         let mut mesh = Mesh::default();
@@ -354,4 +774,70 @@ mod tests {
         assert_eq!(mesh.sphere_center, Vec3::new(1.0, 0.0, 0.0));
         assert!((mesh.sphere_radius - 1.0).abs() < 1e-6);
     }
+
+    fn unit_cube() -> Mesh {
+        let positions = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [1.0, 0.0, 1.0],
+            [1.0, 1.0, 1.0],
+            [0.0, 1.0, 1.0],
+        ];
+        Mesh {
+            vertices: positions
+                .iter()
+                .map(|p| Vertex {
+                    position: *p,
+                    ..Vertex::zeroed()
+                })
+                .collect(),
+            // Counter-clockwise seen from outside
+            indices: vec![
+                0, 2, 1, 0, 3, 2, // -Z
+                4, 5, 6, 4, 6, 7, // +Z
+                0, 1, 5, 0, 5, 4, // -Y
+                3, 6, 2, 3, 7, 6, // +Y
+                0, 4, 7, 0, 7, 3, // -X
+                1, 2, 6, 1, 6, 5, // +X
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn auto_front_face_detects_ccw_for_an_outward_wound_cube() {
+        let mut cube = unit_cube();
+        assert!((cube.signed_volume() - 1.0).abs() < 1e-6);
+
+        cube.set_front_face(FrontFace::Auto);
+        assert_eq!(cube.front_face, FrontFace::Ccw);
+
+        for tri in cube.indices.chunks_exact_mut(3) {
+            tri.swap(1, 2);
+        }
+        cube.set_front_face(FrontFace::Auto);
+        assert_eq!(cube.front_face, FrontFace::Cw);
+
+        // Clockwise winding still yields outward normals
+        cube.recompute_normals();
+        let normal = Vec3::from(cube.vertices[6].normal);
+        assert!(normal.dot(Vec3::ONE) > 0.0);
+    }
+
+    #[test]
+    fn unit_cube_stats_report_unit_volume_and_six_faces_of_area() {
+        let mut cube = unit_cube();
+        let stats = cube.compute_stats();
+        assert!((stats.volume - 1.0).abs() < 1e-6);
+        assert!((stats.surface_area - 6.0).abs() < 1e-5);
+        assert_eq!(stats.triangle_count, 12);
+        assert_eq!(stats.boundary_edge_count, 0);
+
+        // Removing a triangle opens a hole bounded by its three edges
+        cube.indices.truncate(33);
+        assert_eq!(cube.compute_stats().boundary_edge_count, 3);
+    }
 }
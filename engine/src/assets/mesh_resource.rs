@@ -1,17 +1,40 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    thread::JoinHandle,
+};
 
 use slotmap::SlotMap;
 
 use bevy_ecs::prelude::*;
 
 use crate::{
-    assets::{handles::MeshHandle, mesh::Mesh},
-    render::renderer::Renderer,
+    assets::{handles::MeshHandle, mesh::Mesh, ref_counts::RefCounts},
+    components::collider_component::{BVHNode, BvhBuildControl, BvhSplit},
 };
 
+/// Meshes with at least this many triangles get their collision BVH built on a background
+/// thread by `add_mesh_with_bvh`, so importing them doesn't block.
+pub const BACKGROUND_BVH_TRIANGLES: usize = 200_000;
+
 #[derive(Default)]
 pub struct MeshStorage {
     pub meshes: SlotMap<MeshHandle, Mesh>,
+    /// Unloaded meshes whose GPU buffers the renderer still has to delete.
+    pub(crate) unloaded: Vec<MeshHandle>,
+    /// Meshes edited in place whose GPU buffers the renderer has to upload again.
+    pub(crate) modified: Vec<MeshHandle>,
+    /// References held by entities through their render bodies.
+    pub(crate) refs: RefCounts<MeshHandle>,
+    /// Collision BVHs still being built in the background, by mesh.
+    pending_bvhs: HashMap<MeshHandle, PendingBvh>,
+}
+
+/// A collision BVH being built on a background thread.
+struct PendingBvh {
+    control: Arc<BvhBuildControl>,
+    triangle_count: usize,
+    build: JoinHandle<Option<BVHNode>>,
 }
 
 #[derive(Resource, Default, Clone)]
@@ -36,12 +59,120 @@ impl MeshResource {
             }
         }
     }
+
+    /// Frees the mesh behind `handle`. Its GPU buffers are deleted by the renderer on the next
+    /// frame, and the handle stays invalid, so later lookups return `None`.
+    /// While an entity's render body still uses the mesh, the unload is deferred until the
+    /// last such entity is despawned or loses its `RenderBodyComponent`.
+    /// Returns `false` if the handle was already unloaded.
+    pub fn unload(&self, handle: MeshHandle) -> bool {
+        self.write().unload_mesh(handle)
+    }
+
+    /// Installs collision BVHs whose background build has finished. Runs once per frame.
+    pub fn install_finished_bvh_builds(meshes: Res<MeshResource>) {
+        if meshes.read().pending_bvhs.is_empty() {
+            return;
+        }
+        meshes.write().install_finished_bvhs();
+    }
 }
 impl MeshStorage {
     pub fn add_mesh(&mut self, mesh: Mesh) -> MeshHandle {
         self.meshes.insert(mesh)
     }
 
+    /// Adds `mesh` and builds its collision BVH, on a background thread if the mesh has at
+    /// least `BACKGROUND_BVH_TRIANGLES` triangles. Until that build finishes, the mesh has no
+    /// BVH, so mesh colliders skip it, and `bvh_build_progress` reports it as processing.
+    pub fn add_mesh_with_bvh(&mut self, mut mesh: Mesh, max_leaf_size: usize) -> MeshHandle {
+        if mesh.indices.len() / 3 < BACKGROUND_BVH_TRIANGLES {
+            mesh.build_bvh(max_leaf_size);
+            return self.add_mesh(mesh);
+        }
+        let mesh_id = self.add_mesh(mesh);
+        self.build_bvh_in_background(mesh_id, max_leaf_size);
+        mesh_id
+    }
+
+    /// Starts rebuilding the collision BVH of `mesh_id` on a background thread, cancelling any
+    /// build already running for it. The mesh keeps its current BVH until the new one is
+    /// installed by `install_finished_bvhs`. Returns `false` if the mesh isn't loaded.
+    pub fn build_bvh_in_background(&mut self, mesh_id: MeshHandle, max_leaf_size: usize) -> bool {
+        let Some(mesh) = self.meshes.get(mesh_id) else {
+            return false;
+        };
+        let triangles = mesh.triangles_from_indices();
+        self.cancel_bvh_build(mesh_id);
+        if triangles.is_empty() {
+            self.meshes[mesh_id].bvh = None;
+            return true;
+        }
+
+        let control = Arc::new(BvhBuildControl::default());
+        let triangle_count = triangles.len();
+        let build = {
+            let control = control.clone();
+            std::thread::spawn(move || {
+                BVHNode::build_cancelable(triangles, max_leaf_size, BvhSplit::Median, &control)
+            })
+        };
+        self.pending_bvhs.insert(
+            mesh_id,
+            PendingBvh {
+                control,
+                triangle_count,
+                build,
+            },
+        );
+        true
+    }
+
+    /// Share of the triangles placed so far, from 0 to 1, while a background BVH build runs
+    /// for `mesh_id`. `None` when no build is running.
+    pub fn bvh_build_progress(&self, mesh_id: MeshHandle) -> Option<f32> {
+        let pending = self.pending_bvhs.get(&mesh_id)?;
+        Some(pending.control.placed_triangles() as f32 / pending.triangle_count.max(1) as f32)
+    }
+
+    /// Cancels the background BVH build of `mesh_id`, leaving its current BVH in place.
+    /// Returns `false` if no build was running.
+    pub fn cancel_bvh_build(&mut self, mesh_id: MeshHandle) -> bool {
+        let Some(pending) = self.pending_bvhs.remove(&mesh_id) else {
+            return false;
+        };
+        pending.control.cancel();
+        true
+    }
+
+    /// Moves every finished background BVH onto its mesh. Returns how many were installed.
+    pub fn install_finished_bvhs(&mut self) -> usize {
+        let finished: Vec<MeshHandle> = self
+            .pending_bvhs
+            .iter()
+            .filter(|(_, pending)| pending.build.is_finished())
+            .map(|(mesh_id, _)| *mesh_id)
+            .collect();
+
+        let mut installed = 0;
+        for mesh_id in finished {
+            let Some(pending) = self.pending_bvhs.remove(&mesh_id) else {
+                continue;
+            };
+            match pending.build.join() {
+                Ok(Some(bvh)) => {
+                    if let Some(mesh) = self.meshes.get_mut(mesh_id) {
+                        mesh.bvh = Some(bvh);
+                        installed += 1;
+                    }
+                }
+                Ok(None) => {}
+                Err(_) => log::error!("Collision BVH build for {mesh_id:?} panicked"),
+            }
+        }
+        installed
+    }
+
     #[allow(dead_code)]
     pub fn get_mesh(&self, mesh_id: MeshHandle) -> Option<&Mesh> {
         self.meshes.get(mesh_id)
@@ -51,10 +182,108 @@ impl MeshStorage {
         self.meshes.get_mut(mesh_id)
     }
 
-    #[allow(dead_code)]
-    pub fn remove_mesh(&mut self, mesh_id: MeshHandle, renderer: &mut Renderer) {
+    pub fn unload_mesh(&mut self, mesh_id: MeshHandle) -> bool {
+        if !self.meshes.contains_key(mesh_id) {
+            return false;
+        }
+        if self.refs.is_referenced(mesh_id) {
+            self.refs.defer_unload(mesh_id);
+        } else {
+            self.free_mesh(mesh_id);
+        }
+        true
+    }
+
+    /// Flags a mesh whose vertices or indices were edited in place, so the renderer drops its
+    /// GPU buffers on the next frame and uploads the new data the next time it is drawn.
+    pub fn mark_modified(&mut self, mesh_id: MeshHandle) {
+        if self.meshes.contains_key(mesh_id) && !self.modified.contains(&mesh_id) {
+            self.modified.push(mesh_id);
+        }
+    }
+
+    pub(crate) fn acquire_mesh(&mut self, mesh_id: MeshHandle) {
+        self.refs.acquire(mesh_id);
+    }
+
+    pub(crate) fn release_mesh(&mut self, mesh_id: MeshHandle) {
+        if self.refs.release(mesh_id) {
+            self.free_mesh(mesh_id);
+        }
+    }
+
+    fn free_mesh(&mut self, mesh_id: MeshHandle) {
+        self.cancel_bvh_build(mesh_id);
         if self.meshes.remove(mesh_id).is_some() {
-            renderer.delete_mesh_gpu(mesh_id);
+            self.unloaded.push(mesh_id);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unloaded_mesh_is_gone_and_reloading_gets_a_fresh_handle() {
+        let resource = MeshResource::default();
+        let handle = resource.write().add_mesh(Mesh::default());
+        assert!(resource.read().get_mesh(handle).is_some());
+
+        assert!(resource.unload(handle));
+        assert!(resource.read().get_mesh(handle).is_none());
+        assert!(!resource.unload(handle));
+        assert_eq!(resource.read().unloaded, vec![handle]);
+
+        // The slot is reused, but the old handle must not alias the new mesh
+        let reloaded = resource.write().add_mesh(Mesh::default());
+        assert_ne!(reloaded, handle);
+        assert!(resource.read().get_mesh(reloaded).is_some());
+        assert!(resource.read().get_mesh(handle).is_none());
+    }
+
+    fn strip_mesh(triangles: u32) -> Mesh {
+        let mut mesh = Mesh::default();
+        for i in 0..triangles + 2 {
+            mesh.vertices.push(crate::assets::mesh::Vertex {
+                position: [(i / 2) as f32, (i % 2) as f32, 0.0],
+                ..Default::default()
+            });
+        }
+        mesh.indices = (0..triangles).flat_map(|i| [i, i + 1, i + 2]).collect();
+        mesh
+    }
+
+    #[test]
+    fn background_bvh_is_installed_once_finished() {
+        let mut storage = MeshStorage::default();
+        let mesh_id = storage.add_mesh(strip_mesh(4096));
+
+        assert!(storage.build_bvh_in_background(mesh_id, 8));
+        assert!(storage.bvh_build_progress(mesh_id).is_some());
+
+        let start = std::time::Instant::now();
+        while storage.install_finished_bvhs() == 0 {
+            assert!(
+                start.elapsed().as_secs() < 10,
+                "background BVH build never finished"
+            );
+            std::thread::yield_now();
+        }
+        assert_eq!(storage.bvh_build_progress(mesh_id), None);
+        let bvh = storage.get_mesh(mesh_id).unwrap().bvh.as_ref().unwrap();
+        assert_eq!(bvh.triangle_count(), 4096);
+    }
+
+    #[test]
+    fn cancelled_background_bvh_is_never_installed() {
+        let mut storage = MeshStorage::default();
+        let mesh_id = storage.add_mesh(strip_mesh(4096));
+
+        assert!(storage.build_bvh_in_background(mesh_id, 8));
+        assert!(storage.cancel_bvh_build(mesh_id));
+        assert_eq!(storage.bvh_build_progress(mesh_id), None);
+        assert_eq!(storage.install_finished_bvhs(), 0);
+        assert!(storage.get_mesh(mesh_id).unwrap().bvh.is_none());
+    }
+}
@@ -4,6 +4,8 @@ pub mod material_resource;
 pub mod mesh;
 pub mod mesh_resource;
 pub mod model_loader;
+pub mod ply;
+pub(crate) mod ref_counts;
 pub mod shader;
 pub mod shader_resource;
 pub mod sound;
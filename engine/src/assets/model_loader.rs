@@ -4,11 +4,12 @@ use std::{collections::HashMap, ffi::OsStr};
 use crate::{
     Engine,
     assets::{
-        handles::{MaterialHandle, RenderBodyHandle, ShaderHandle, TextureHandle},
+        handles::{MaterialHandle, MeshHandle, RenderBodyHandle, ShaderHandle, TextureHandle},
         material::{Material, MaterialDesc},
-        material_resource::{MaterialResource, MaterialStorage},
-        mesh::{Aabb, GltfPrimitiveMesh, Mesh, Vertex},
+        material_resource::{DefaultMaterial, MaterialResource, MaterialStorage},
+        mesh::{Aabb, FrontFace, GltfPrimitiveMesh, Mesh, Vertex},
         mesh_resource::MeshResource,
+        ply::PlyProcessor,
         shader::UniformValue,
         shader_resource::ShaderResource,
         texture_resource::TextureResource,
@@ -19,17 +20,17 @@ use crate::{
     },
 };
 
-const DEFAULT_MATERIAL_CAPACITY: usize = 32;
-impl Engine {
-    fn rgba_from_rgb(rgb: [f32; 3]) -> [u8; 4] {
-        [
-            (rgb[0].clamp(0.0, 1.0) * 255.0) as u8,
-            (rgb[1].clamp(0.0, 1.0) * 255.0) as u8,
-            (rgb[2].clamp(0.0, 1.0) * 255.0) as u8,
-            255,
-        ]
-    }
+/// Material parameters read from a glTF document or OBJ material library, before any GPU
+/// resources other than textures are created. Texture slots are `None` when the material has no
+/// texture for them.
+struct ModelMaterialInputs {
+    albedo: Option<TextureHandle>,
+    normal: Option<TextureHandle>,
+    base_color_factor: [f32; 4],
+    roughness: f32,
+}
 
+impl Engine {
     fn rgba_from_rgba_f32(rgba: [f32; 4]) -> [u8; 4] {
         [
             (rgba[0].clamp(0.0, 1.0) * 255.0) as u8,
@@ -44,10 +45,15 @@ impl Engine {
         shader_handle: ShaderHandle,
         albedo_handle: TextureHandle,
         normal_handle: TextureHandle,
+        base_color_factor: glam::Vec3,
         roughness: f32,
     ) -> MaterialHandle {
         let params = vec![
             ("u_roughness".to_string(), UniformValue::Float(roughness)),
+            (
+                "u_base_color_factor".to_string(),
+                UniformValue::Vec3(base_color_factor),
+            ),
             ("u_base_reflectance".to_string(), UniformValue::Float(0.04)),
             (
                 "u_albedo".to_string(),
@@ -70,11 +76,58 @@ impl Engine {
 
     /// Loads a model from the specified file path. Supports different model formats based on file extension.
     /// Returns a `RenderBodyHandle` if the model is successfully loaded, or `None` if the format is unsupported.
+    /// Loading a path that is already loaded returns the existing handle without re-reading
+    /// the file or uploading its meshes again; use `reload_model` to force a fresh load.
     ///
-    /// Currently supported formats: glTF (.gltf)
+    /// Currently supported formats: glTF (.gltf, .glb), OBJ (.obj) and PLY (.ply)
     ///
     /// FBX (.fbx) loading is not yet implemented.
     pub fn load_model(&mut self, model_path: &str) -> Option<RenderBodyHandle> {
+        self.load_model_cached(model_path, false, FrontFace::Ccw)
+    }
+
+    /// Like `load_model`, for models whose front faces wind `front_face` rather than
+    /// counter-clockwise. `FrontFace::Auto` detects the winding per mesh from its signed volume.
+    pub fn load_model_with_front_face(
+        &mut self,
+        model_path: &str,
+        front_face: FrontFace,
+    ) -> Option<RenderBodyHandle> {
+        self.load_model_cached(model_path, false, front_face)
+    }
+
+    /// Like `load_model`, but always reads the file again and replaces the cached handle.
+    /// Render bodies from earlier loads stay valid until they are unloaded.
+    pub fn reload_model(&mut self, model_path: &str) -> Option<RenderBodyHandle> {
+        self.load_model_cached(model_path, true, FrontFace::Ccw)
+    }
+
+    fn load_model_cached(
+        &mut self,
+        model_path: &str,
+        force_reload: bool,
+        front_face: FrontFace,
+    ) -> Option<RenderBodyHandle> {
+        let bodies = self
+            .scene
+            .world
+            .get_resource::<RenderBodyResource>()
+            .expect("RenderBodyResource not found")
+            .clone();
+        load_through_cache(
+            &bodies,
+            std::path::Path::new(model_path),
+            front_face,
+            force_reload,
+            || self.load_model_uncached(model_path, front_face),
+        )
+    }
+
+    fn load_model_uncached(
+        &mut self,
+        model_path: &str,
+        front_face: FrontFace,
+    ) -> Option<RenderBodyHandle> {
         let extension = std::path::Path::new(model_path)
             .extension()
             .and_then(|ext| ext.to_str())
@@ -82,9 +135,10 @@ impl Engine {
             .to_lowercase();
 
         match extension.as_str() {
-            "gltf" | "glb" => Some(self.load_gltf(model_path)),
+            "gltf" | "glb" => Some(self.load_gltf(model_path, front_face)),
             "fbx" => Some(self.load_fbx(model_path)),
-            "obj" => Some(self.load_obj(model_path)),
+            "obj" => Some(self.load_obj(model_path, front_face)),
+            "ply" => self.load_ply(model_path, front_face),
             _ => {
                 warn!("Unsupported model format: {}", extension);
                 None
@@ -93,7 +147,7 @@ impl Engine {
     }
 
     /// Loads an OBJ model from the specified file path and returns a `RenderBodyHandle`.
-    fn load_obj(&mut self, obj_path: &str) -> RenderBodyHandle {
+    fn load_obj(&mut self, obj_path: &str, front_face: FrontFace) -> RenderBodyHandle {
         let gl = &self.gl;
         let obj_path = std::path::Path::new(obj_path);
         let base_dir = obj_path
@@ -122,94 +176,30 @@ impl Engine {
             )
         };
 
-        let mut material_inputs: Vec<(TextureHandle, TextureHandle, f32)> =
-            Vec::with_capacity(DEFAULT_MATERIAL_CAPACITY);
-        {
-            let texture_resource = self
-                .scene
-                .world
-                .get_resource_mut::<TextureResource>()
-                .expect("TextureResource not found");
-
-            if let Ok(obj_materials) = obj_materials.as_ref() {
-                for material in obj_materials {
-                    let albedo_handle = if let Some(tex) = material.diffuse_texture.as_ref() {
-                        if !tex.is_empty() {
-                            let tex_path = base_dir.join(tex);
-                            texture_resource
-                                .write()
-                                .load_from_file(gl, tex_path.as_os_str())
-                        } else {
-                            let diffuse = material.diffuse.unwrap_or([1.0, 1.0, 1.0]);
-                            let rgba = Self::rgba_from_rgb(diffuse);
-                            texture_resource.write().create_solid_rgba(gl, rgba)
-                        }
-                    } else {
-                        let diffuse = material.diffuse.unwrap_or([1.0, 1.0, 1.0]);
-                        let rgba = Self::rgba_from_rgb(diffuse);
-                        texture_resource.write().create_solid_rgba(gl, rgba)
-                    };
-
-                    let normal_handle = if let Some(tex) = material.normal_texture.as_ref() {
-                        if !tex.is_empty() {
-                            let tex_path = base_dir.join(tex);
-                            texture_resource
-                                .write()
-                                .load_from_file(gl, tex_path.as_os_str())
-                        } else {
-                            texture_resource
-                                .write()
-                                .create_solid_rgba(gl, [128, 128, 255, 255])
-                        }
-                    } else {
-                        texture_resource
-                            .write()
-                            .create_solid_rgba(gl, [128, 128, 255, 255])
-                    };
-
-                    let roughness = if let Some(shininess) = material.shininess {
-                        if shininess > 0.0 {
-                            (1.0_f32 - (shininess / 1000.0)).clamp(0.0, 1.0)
-                        } else {
-                            1.0
-                        }
-                    } else {
-                        1.0
-                    };
-
-                    material_inputs.push((albedo_handle, normal_handle, roughness));
-                }
-            }
-
-            if material_inputs.is_empty() {
-                let albedo = texture_resource
-                    .write()
-                    .create_solid_rgba(gl, [255, 255, 255, 255]);
-                let default_normal = texture_resource
-                    .write()
-                    .create_solid_rgba(gl, [128, 128, 255, 255]);
-                material_inputs.push((albedo, default_normal, 1.0));
+        let material_inputs = match obj_materials.as_ref() {
+            Ok(obj_materials) => {
+                let texture_resource = self
+                    .scene
+                    .world
+                    .get_resource::<TextureResource>()
+                    .expect("TextureResource not found")
+                    .clone();
+                Self::obj_material_inputs(obj_materials, base_dir, |tex_path| {
+                    texture_resource
+                        .write()
+                        .load_from_file(gl, tex_path.as_os_str())
+                })
             }
-        }
-
-        let mut material_handles: Vec<MaterialHandle> = Vec::with_capacity(material_inputs.len());
-        {
-            let material_resource = self
-                .scene
-                .world
-                .get_resource_mut::<MaterialResource>()
-                .expect("MaterialResource not found");
-            for (albedo_handle, normal_handle, roughness) in material_inputs {
-                let handle = Self::create_pbr_material(
-                    &mut material_resource.write(),
-                    shader_handle,
-                    albedo_handle,
-                    normal_handle,
-                    roughness,
+            Err(err) => {
+                warn!(
+                    "No usable OBJ materials for {}: {}",
+                    obj_path.display(),
+                    err
                 );
-                material_handles.push(handle);
+                Vec::new()
             }
-        }
+        };
+        let material_handles = self.create_materials_from_inputs(shader_handle, material_inputs);
 
         let default_material = material_handles[0];
 
@@ -253,6 +243,16 @@ impl Engine {
                 uvs.resize(vertex_count, [0.0, 0.0]);
             }
 
+            // `v x y z r g b` lines give per-vertex colors
+            let colors: Vec<[f32; 3]> = if mesh.vertex_color.len() == vertex_count * 3 {
+                mesh.vertex_color
+                    .chunks_exact(3)
+                    .map(|rgb| [rgb[0], rgb[1], rgb[2]])
+                    .collect()
+            } else {
+                vec![Vertex::WHITE; vertex_count]
+            };
+
             let indices: Vec<u32> = mesh.indices.to_vec();
 
             let tangents = if has_uvs && has_normals {
@@ -270,13 +270,17 @@ impl Engine {
                     uv_albedo: uvs[i],
                     uv_normal: uvs[i],
                     tangent: tangents[i],
+                    color: colors[i],
                 });
             }
             built_mesh.indices.extend(indices.iter().copied());
+            built_mesh.set_front_face(front_face);
+            if !has_normals {
+                built_mesh.recompute_normals();
+            }
 
             built_mesh.aabb = Aabb::from_vertices(&built_mesh.vertices);
             built_mesh.compute_bounding_sphere();
-            built_mesh.build_bvh(8);
 
             let material_handle = mesh
                 .material_id
@@ -294,7 +298,7 @@ impl Engine {
                 .get_resource_mut::<MeshResource>()
                 .expect("MeshResource not found");
             for (built_mesh, material_handle) in built_parts {
-                let mesh_handle = mesh_resource.write().add_mesh(built_mesh);
+                let mesh_handle = mesh_resource.write().add_mesh_with_bvh(built_mesh, 8);
                 parts.push(RenderBodyPart {
                     mesh_id: mesh_handle,
                     material_id: material_handle,
@@ -312,16 +316,65 @@ impl Engine {
             .add_render_body(render_body)
     }
 
+    /// Loads a PLY scan as a single-part render body. Its vertex colors show through the
+    /// default material.
+    fn load_ply(&mut self, ply_path: &str, front_face: FrontFace) -> Option<RenderBodyHandle> {
+        let mut mesh = match PlyProcessor::load(std::path::Path::new(ply_path), front_face) {
+            Ok(mesh) => mesh,
+            Err(err) => {
+                warn!("Failed to load PLY {}: {}", ply_path, err);
+                return None;
+            }
+        };
+
+        let shader_handle = self
+            .scene
+            .world
+            .get_resource::<ShaderResource>()
+            .expect("ShaderResource not found")
+            .write()
+            .get_or_load(
+                &self.gl,
+                OsStr::new("resources/shaders/pbr.vert"),
+                OsStr::new("resources/shaders/pbr.frag"),
+            );
+        let material_id = self.create_materials_from_inputs(shader_handle, Vec::new())[0];
+
+        let mesh_id = self
+            .scene
+            .world
+            .get_resource::<MeshResource>()
+            .expect("MeshResource not found")
+            .write()
+            .add_mesh_with_bvh(mesh, 8);
+        let render_body = RenderBody::new(vec![RenderBodyPart {
+            mesh_id,
+            material_id,
+            local_transform: glam::Mat4::IDENTITY,
+        }]);
+        Some(
+            self.scene
+                .world
+                .get_resource::<RenderBodyResource>()
+                .expect("RenderBodyResource not found")
+                .write()
+                .add_render_body(render_body),
+        )
+    }
+
     /// Loads an FBX model from the specified file path and returns a `RenderBodyHandle`.
     fn load_fbx(&mut self, _fbx_path: &str) -> RenderBodyHandle {
         unimplemented!("FBX loading is not yet implemented");
     }
 
     /// Loads a glTF model from the specified file path and returns a `RenderBodyHandle`.
-    fn load_gltf(&mut self, gltf_path: &str) -> RenderBodyHandle {
+    fn load_gltf(&mut self, gltf_path: &str, front_face: FrontFace) -> RenderBodyHandle {
         let os_path = OsStr::new(gltf_path);
 
-        let mesh_primitives = Self::mesh_primatives_from_gltf(os_path).unwrap();
+        let mut mesh_primitives = Self::mesh_primatives_from_gltf(os_path).unwrap();
+        for primitive in &mut mesh_primitives {
+            primitive.mesh.set_front_face(front_face);
+        }
 
         let material_handles = self
             .load_materials_from_gltf(
@@ -331,33 +384,15 @@ impl Engine {
             )
             .expect("Failed to load glTF materials");
 
-        let default_material = *material_handles
-            .first()
-            .expect("No materials found in glTF");
-
-        let mut parts = Vec::with_capacity(mesh_primitives.len());
-        {
-            let mesh_resource = self
-                .scene
-                .world
-                .get_resource_mut::<MeshResource>()
-                .expect("MeshResource not found");
-
-            for prim in mesh_primitives {
-                let mesh_handle = mesh_resource.write().add_mesh(prim.mesh);
-
-                let material_handle = prim
-                    .material_index
-                    .and_then(|idx| material_handles.get(idx).copied())
-                    .unwrap_or(default_material);
-
-                parts.push(RenderBodyPart {
-                    mesh_id: mesh_handle,
-                    material_id: material_handle,
-                    local_transform: glam::Mat4::IDENTITY,
-                });
-            }
-        }
+        let mesh_resource = self
+            .scene
+            .world
+            .get_resource::<MeshResource>()
+            .expect("MeshResource not found")
+            .clone();
+        let parts = Self::gltf_render_body_parts(mesh_primitives, &material_handles, |mesh| {
+            mesh_resource.write().add_mesh_with_bvh(mesh, 8)
+        });
 
         let render_body = RenderBody::new(parts);
         self.scene
@@ -368,6 +403,30 @@ impl Engine {
             .add_render_body(render_body)
     }
 
+    /// Builds one render body part per glTF primitive, each with its own mesh and the material
+    /// its primitive names. Primitives without a valid material fall back to the first one.
+    fn gltf_render_body_parts(
+        primitives: Vec<GltfPrimitiveMesh>,
+        material_handles: &[MaterialHandle],
+        mut add_mesh: impl FnMut(Mesh) -> MeshHandle,
+    ) -> Vec<RenderBodyPart> {
+        let default_material = *material_handles
+            .first()
+            .expect("No materials found in glTF");
+
+        primitives
+            .into_iter()
+            .map(|prim| RenderBodyPart {
+                material_id: prim
+                    .material_index
+                    .and_then(|idx| material_handles.get(idx).copied())
+                    .unwrap_or(default_material),
+                mesh_id: add_mesh(prim.mesh),
+                local_transform: glam::Mat4::IDENTITY,
+            })
+            .collect()
+    }
+
     fn load_materials_from_gltf(
         &mut self,
         gltf_path: &OsStr,
@@ -389,74 +448,156 @@ impl Engine {
                 .get_or_load(gl, vertex_shader, fragment_shader)
         };
 
-        let texture_map = {
-            let mut texture_resource = self
-                .scene
-                .world
-                .get_resource_mut::<TextureResource>()
-                .expect("TextureResource not found");
-            Self::load_textures_from_gltf_data(&mut texture_resource, gl, &gltf, &images)?
-        };
+        let texture_resource = self
+            .scene
+            .world
+            .get_resource::<TextureResource>()
+            .expect("TextureResource not found")
+            .clone();
+        let texture_map = Self::load_textures_from_gltf_data(&gltf, &images, |w, h, rgba| {
+            texture_resource
+                .write()
+                .create_from_rgba_with_key(gl, w, h, rgba)
+        })?;
 
-        let mut material_inputs: Vec<(TextureHandle, TextureHandle, f32)> =
-            Vec::with_capacity(DEFAULT_MATERIAL_CAPACITY);
+        let material_inputs = Self::gltf_material_inputs(&gltf, &texture_map);
+        Ok(self.create_materials_from_inputs(shader_handle, material_inputs))
+    }
 
+    /// Creates one PBR material per input. A textured base color is tinted by the factor in the
+    /// shader; without a texture the factor becomes a solid color texture instead. Inputs without
+    /// a normal map get a flat one.
+    fn create_materials_from_inputs(
+        &self,
+        shader_handle: ShaderHandle,
+        mut material_inputs: Vec<ModelMaterialInputs>,
+    ) -> Vec<MaterialHandle> {
+        let gl = &self.gl;
         let texture_resource = self
             .scene
             .world
-            .get_resource_mut::<TextureResource>()
-            .expect("TextureResource not found");
+            .get_resource::<TextureResource>()
+            .expect("TextureResource not found")
+            .clone();
+        let material_resource = self
+            .scene
+            .world
+            .get_resource::<MaterialResource>()
+            .expect("MaterialResource not found")
+            .clone();
+
+        // Models without materials still need one for their meshes: the configured default, or
+        // plain white as in glTF.
+        if material_inputs.is_empty() {
+            if let Some(DefaultMaterial(Some(material_id))) =
+                self.scene.world.get_resource::<DefaultMaterial>().copied()
+            {
+                return vec![material_id];
+            }
+            material_inputs.push(ModelMaterialInputs {
+                albedo: None,
+                normal: None,
+                base_color_factor: [1.0, 1.0, 1.0, 1.0],
+                roughness: 1.0,
+            });
+        }
+
         let default_normal = texture_resource
             .write()
             .create_solid_rgba(gl, [128, 128, 255, 255]);
 
-        for material in gltf.materials() {
-            let pbr = material.pbr_metallic_roughness();
-            let roughness = pbr.roughness_factor();
-
-            let albedo_handle = pbr
-                .base_color_texture()
-                .and_then(|info| texture_map.get(&info.texture().index()).copied())
-                .unwrap_or_else(|| {
-                    let base_color = pbr.base_color_factor();
-                    let rgba = Self::rgba_from_rgba_f32(base_color);
-                    texture_resource.write().create_solid_rgba(gl, rgba)
-                });
-
-            let normal_handle = material
-                .normal_texture()
-                .and_then(|info| texture_map.get(&info.texture().index()).copied())
-                .unwrap_or(default_normal);
+        material_inputs
+            .into_iter()
+            .map(|inputs| {
+                let (albedo_handle, tint) = match inputs.albedo {
+                    Some(albedo) => (
+                        albedo,
+                        glam::Vec4::from(inputs.base_color_factor).truncate(),
+                    ),
+                    None => {
+                        let rgba = Self::rgba_from_rgba_f32(inputs.base_color_factor);
+                        (
+                            texture_resource.write().create_solid_rgba(gl, rgba),
+                            glam::Vec3::ONE,
+                        )
+                    }
+                };
+                Self::create_pbr_material(
+                    &mut material_resource.write(),
+                    shader_handle,
+                    albedo_handle,
+                    inputs.normal.unwrap_or(default_normal),
+                    tint,
+                    inputs.roughness,
+                )
+            })
+            .collect()
+    }
 
-            material_inputs.push((albedo_handle, normal_handle, roughness));
-        }
+    /// Reads each OBJ material's diffuse color and texture, normal texture and roughness (derived
+    /// from the specular exponent). Texture paths are resolved against `base_dir`, the OBJ's
+    /// directory, and handed to `load_texture`.
+    fn obj_material_inputs(
+        obj_materials: &[tobj::Material],
+        base_dir: &std::path::Path,
+        mut load_texture: impl FnMut(&std::path::Path) -> TextureHandle,
+    ) -> Vec<ModelMaterialInputs> {
+        let mut texture = |name: &Option<String>| {
+            name.as_deref()
+                .filter(|name| !name.is_empty())
+                .map(|name| load_texture(&base_dir.join(name)))
+        };
 
-        let mut material_handles = Vec::with_capacity(material_inputs.len());
-        let material_resource = self
-            .scene
-            .world
-            .get_resource_mut::<MaterialResource>()
-            .expect("MaterialResource not found");
-
-        for (albedo_handle, normal_handle, roughness) in material_inputs {
-            let handle = Self::create_pbr_material(
-                &mut material_resource.write(),
-                shader_handle,
-                albedo_handle,
-                normal_handle,
-                roughness,
-            );
-            material_handles.push(handle);
-        }
+        obj_materials
+            .iter()
+            .map(|material| {
+                let [r, g, b] = material.diffuse.unwrap_or([1.0, 1.0, 1.0]);
+                let roughness = match material.shininess {
+                    Some(shininess) if shininess > 0.0 => {
+                        (1.0_f32 - (shininess / 1000.0)).clamp(0.0, 1.0)
+                    }
+                    _ => 1.0,
+                };
+                ModelMaterialInputs {
+                    albedo: texture(&material.diffuse_texture),
+                    normal: texture(&material.normal_texture),
+                    base_color_factor: [r, g, b, 1.0],
+                    roughness,
+                }
+            })
+            .collect()
+    }
 
-        Ok(material_handles)
+    /// Reads each glTF material's base color, normal texture and roughness, resolving texture
+    /// indices through `texture_map`.
+    fn gltf_material_inputs(
+        gltf: &gltf::Document,
+        texture_map: &HashMap<usize, TextureHandle>,
+    ) -> Vec<ModelMaterialInputs> {
+        gltf.materials()
+            .map(|material| {
+                let pbr = material.pbr_metallic_roughness();
+                ModelMaterialInputs {
+                    albedo: pbr
+                        .base_color_texture()
+                        .and_then(|info| texture_map.get(&info.texture().index()).copied()),
+                    normal: material
+                        .normal_texture()
+                        .and_then(|info| texture_map.get(&info.texture().index()).copied()),
+                    base_color_factor: pbr.base_color_factor(),
+                    roughness: pbr.roughness_factor(),
+                }
+            })
+            .collect()
     }
 
+    /// Converts every glTF texture to RGBA and hands it to `upload`, returning the created
+    /// handles by glTF texture index. `gltf::import` has already resolved both embedded
+    /// (data URI or GLB buffer) and external image references into `images`.
     fn load_textures_from_gltf_data(
-        texture_resource: &mut TextureResource,
-        gl: &glow::Context,
         gltf: &gltf::Document,
         images: &[gltf::image::Data],
+        mut upload: impl FnMut(u32, u32, &[u8]) -> TextureHandle,
     ) -> Result<HashMap<usize, TextureHandle>, Box<dyn std::error::Error>> {
         let mut texture_map = HashMap::new();
 
@@ -469,12 +610,7 @@ impl Engine {
             let rgba = Self::gltf_image_to_rgba(image)
                 .map_err(|message| std::io::Error::new(std::io::ErrorKind::InvalidData, message))?;
 
-            let handle = texture_resource.write().create_from_rgba_with_key(
-                gl,
-                image.width,
-                image.height,
-                &rgba,
-            );
+            let handle = upload(image.width, image.height, &rgba);
             texture_map.insert(texture_index, handle);
         }
 
@@ -514,10 +650,10 @@ impl Engine {
         let mut meshes = Vec::with_capacity(gltf.meshes().len());
 
         for gltf_mesh in gltf.meshes() {
-            println!("Mesh #{}", gltf_mesh.index());
+            log::debug!(target: "assets", "Mesh #{}", gltf_mesh.index());
 
             for primitive in gltf_mesh.primitives() {
-                println!("- Primitive #{}", primitive.index());
+                log::debug!(target: "assets", "- Primitive #{}", primitive.index());
 
                 let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
 
@@ -546,6 +682,11 @@ impl Engine {
                     .into_u32()
                     .collect();
 
+                let colors: Vec<[f32; 3]> = match reader.read_colors(0) {
+                    Some(colors) => colors.into_rgb_f32().collect(),
+                    None => vec![Vertex::WHITE; positions.len()],
+                };
+
                 // Some meshes might not have tangents
                 // If so, we need to compute them
 
@@ -560,6 +701,7 @@ impl Engine {
                 assert_eq!(positions.len(), normals.len());
                 assert_eq!(positions.len(), uvs.len());
                 assert_eq!(positions.len(), tangents.len());
+                assert_eq!(positions.len(), colors.len());
 
                 let mut mesh = Mesh::default();
                 mesh.vertices.reserve(positions.len());
@@ -578,13 +720,13 @@ impl Engine {
                             tangents[i][2],
                             tangents[i][3],
                         ],
+                        color: colors[i],
                     });
                 }
 
                 mesh.indices.extend(indices);
                 mesh.aabb = Aabb::from_vertices(&mesh.vertices);
                 mesh.compute_bounding_sphere();
-                mesh.build_bvh(8);
 
                 meshes.push(GltfPrimitiveMesh {
                     mesh,
@@ -597,9 +739,33 @@ impl Engine {
     }
 }
 
+/// Returns the cached render body for `path`, or runs `load` and caches its result.
+/// The cache lock is not held while `load` runs, since loading adds the new render body.
+/// Paths are canonicalized first, so different spellings of the same file share an entry.
+fn load_through_cache(
+    bodies: &RenderBodyResource,
+    path: &std::path::Path,
+    front_face: FrontFace,
+    force_reload: bool,
+    load: impl FnOnce() -> Option<RenderBodyHandle>,
+) -> Option<RenderBodyHandle> {
+    let key = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if !force_reload && let Some(handle) = bodies.read().cached_model(&key, front_face) {
+        return Some(handle);
+    }
+    let handle = load()?;
+    bodies.write().cache_model(key, front_face, handle);
+    Some(handle)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use slotmap::SlotMap;
+
+    use crate::assets::{
+        mesh_resource::MeshStorage, texture::Texture, texture_resource::TextureStorage,
+    };
 
     fn make_image_data(format: gltf::image::Format, pixels: Vec<u8>) -> gltf::image::Data {
         gltf::image::Data {
@@ -644,4 +810,329 @@ mod tests {
         let result = Engine::gltf_image_to_rgba(&image);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn gltf_base_color_texture_becomes_a_loaded_material_texture() {
+        let dir = tempfile::tempdir().unwrap();
+        image::RgbaImage::from_pixel(2, 2, image::Rgba([200, 100, 50, 255]))
+            .save(dir.path().join("albedo.png"))
+            .unwrap();
+        let gltf_path = dir.path().join("textured.gltf");
+        std::fs::write(
+            &gltf_path,
+            r#"{
+                "asset": { "version": "2.0" },
+                "images": [{ "uri": "albedo.png" }],
+                "textures": [{ "source": 0 }],
+                "materials": [
+                    {
+                        "pbrMetallicRoughness": {
+                            "baseColorTexture": { "index": 0 },
+                            "baseColorFactor": [0.5, 1.0, 1.0, 1.0],
+                            "roughnessFactor": 0.3
+                        }
+                    },
+                    { "pbrMetallicRoughness": { "baseColorFactor": [0.0, 1.0, 0.0, 1.0] } }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let (gltf, _, images) = gltf::import(&gltf_path).unwrap();
+        let mut textures = TextureStorage::default();
+        let texture_map = Engine::load_textures_from_gltf_data(&gltf, &images, |w, h, _| {
+            textures.add_texture(Texture::new(w, h))
+        })
+        .unwrap();
+        let inputs = Engine::gltf_material_inputs(&gltf, &texture_map);
+
+        assert_eq!(inputs.len(), 2);
+        let albedo = inputs[0]
+            .albedo
+            .expect("base color texture should be imported");
+        let texture = textures
+            .get_texture(albedo)
+            .expect("handle should be valid");
+        assert_eq!((texture.width, texture.height), (2, 2));
+        assert_eq!(inputs[0].base_color_factor, [0.5, 1.0, 1.0, 1.0]);
+        assert_eq!(inputs[0].roughness, 0.3);
+
+        assert!(inputs[1].albedo.is_none());
+        assert_eq!(inputs[1].base_color_factor, [0.0, 1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn gltf_mesh_primitives_become_separate_parts_with_their_own_materials() {
+        let dir = tempfile::tempdir().unwrap();
+        // One triangle, shared by both primitives: positions, normals, UVs, then u32 indices
+        let mut bin: Vec<u8> = Vec::new();
+        for value in [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0] {
+            bin.extend(value.to_le_bytes());
+        }
+        for value in [0.0f32, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0] {
+            bin.extend(value.to_le_bytes());
+        }
+        for value in [0.0f32, 0.0, 1.0, 0.0, 0.0, 1.0] {
+            bin.extend(value.to_le_bytes());
+        }
+        for index in [0u32, 1, 2] {
+            bin.extend(index.to_le_bytes());
+        }
+        std::fs::write(dir.path().join("triangle.bin"), &bin).unwrap();
+
+        let gltf_path = dir.path().join("two_primitives.gltf");
+        std::fs::write(
+            &gltf_path,
+            r#"{
+                "asset": { "version": "2.0" },
+                "buffers": [{ "uri": "triangle.bin", "byteLength": 108 }],
+                "bufferViews": [
+                    { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+                    { "buffer": 0, "byteOffset": 36, "byteLength": 36 },
+                    { "buffer": 0, "byteOffset": 72, "byteLength": 24 },
+                    { "buffer": 0, "byteOffset": 96, "byteLength": 12 }
+                ],
+                "accessors": [
+                    { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                      "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0] },
+                    { "bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC3" },
+                    { "bufferView": 2, "componentType": 5126, "count": 3, "type": "VEC2" },
+                    { "bufferView": 3, "componentType": 5125, "count": 3, "type": "SCALAR" }
+                ],
+                "materials": [
+                    { "pbrMetallicRoughness": { "baseColorFactor": [1.0, 0.0, 0.0, 1.0] } },
+                    { "pbrMetallicRoughness": { "baseColorFactor": [0.0, 0.0, 1.0, 1.0] } }
+                ],
+                "meshes": [{
+                    "primitives": [
+                        { "attributes": { "POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2 },
+                          "indices": 3, "material": 0 },
+                        { "attributes": { "POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2 },
+                          "indices": 3, "material": 1 }
+                    ]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let primitives = Engine::mesh_primatives_from_gltf(gltf_path.as_os_str()).unwrap();
+        let mut materials: SlotMap<MaterialHandle, ()> = SlotMap::with_key();
+        let material_handles = vec![materials.insert(()), materials.insert(())];
+        let mut meshes = MeshStorage::default();
+        let parts = Engine::gltf_render_body_parts(primitives, &material_handles, |mesh| {
+            meshes.add_mesh(mesh)
+        });
+
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].material_id, material_handles[0]);
+        assert_eq!(parts[1].material_id, material_handles[1]);
+        assert_ne!(parts[0].mesh_id, parts[1].mesh_id);
+        for part in &parts {
+            assert_eq!(
+                meshes.get_mesh(part.mesh_id).unwrap().indices,
+                vec![0, 1, 2]
+            );
+        }
+    }
+
+    #[test]
+    fn loading_the_same_path_twice_reuses_the_render_body() {
+        let bodies = RenderBodyResource::default();
+        let mut meshes = MeshStorage::default();
+        let mut uploads = 0;
+        let mut load = || {
+            uploads += 1;
+            let mesh_id = meshes.add_mesh(Mesh::default());
+            let mut materials: SlotMap<MaterialHandle, ()> = SlotMap::with_key();
+            Some(
+                bodies
+                    .write()
+                    .add_render_body(RenderBody::new(vec![RenderBodyPart {
+                        mesh_id,
+                        material_id: materials.insert(()),
+                        local_transform: glam::Mat4::IDENTITY,
+                    }])),
+            )
+        };
+        let path = std::path::Path::new("resources/models/cube/Cube.gltf");
+
+        let ccw = FrontFace::Ccw;
+
+        let first = load_through_cache(&bodies, path, ccw, false, &mut load).unwrap();
+        let second = load_through_cache(&bodies, path, ccw, false, &mut load).unwrap();
+        assert_eq!(first, second);
+
+        let reloaded = load_through_cache(&bodies, path, ccw, true, &mut load).unwrap();
+        assert_ne!(reloaded, first);
+        assert_eq!(
+            load_through_cache(&bodies, path, ccw, false, &mut load),
+            Some(reloaded)
+        );
+
+        // An unloaded body is loaded again rather than returned stale
+        assert!(bodies.unload(reloaded));
+        let after_unload = load_through_cache(&bodies, path, ccw, false, &mut load).unwrap();
+        assert_ne!(after_unload, reloaded);
+
+        // Another winding is a separate load, and doesn't replace the counter-clockwise one
+        let clockwise = load_through_cache(&bodies, path, FrontFace::Cw, false, &mut load).unwrap();
+        assert_ne!(clockwise, after_unload);
+        assert_eq!(
+            load_through_cache(&bodies, path, ccw, false, &mut load),
+            Some(after_unload)
+        );
+
+        let _ = load;
+        assert_eq!(uploads, 4);
+        assert_eq!(meshes.meshes.len(), 4);
+    }
+
+    #[test]
+    fn different_spellings_of_a_path_share_the_cache_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("nested")).unwrap();
+        let path = dir.path().join("model.obj");
+        std::fs::write(&path, "").unwrap();
+        let roundabout = dir
+            .path()
+            .join("nested")
+            .join("..")
+            .join(".")
+            .join("model.obj");
+
+        let bodies = RenderBodyResource::default();
+        let mut uploads = 0;
+        let mut load = || {
+            uploads += 1;
+            Some(bodies.write().add_render_body(RenderBody::new(Vec::new())))
+        };
+
+        let first = load_through_cache(&bodies, &path, FrontFace::Ccw, false, &mut load);
+        let second = load_through_cache(&bodies, &roundabout, FrontFace::Ccw, false, &mut load);
+        assert_eq!(first, second);
+        let _ = load;
+        assert_eq!(uploads, 1);
+    }
+
+    #[test]
+    fn obj_mtl_diffuse_color_and_texture_become_material_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("tinted.mtl"),
+            "newmtl red\nKd 0.8 0.2 0.1\nNs 250\n\nnewmtl textured\nKd 0.5 0.5 0.5\nmap_Kd albedo.png\n",
+        )
+        .unwrap();
+        let obj_path = dir.path().join("tinted.obj");
+        std::fs::write(
+            &obj_path,
+            "mtllib tinted.mtl\n\
+             v 0 0 0\nv 1 0 0\nv 0 1 0\n\
+             usemtl red\nf 1 2 3\n",
+        )
+        .unwrap();
+
+        let (models, materials) = tobj::load_obj(
+            &obj_path,
+            &tobj::LoadOptions {
+                single_index: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let materials = materials.unwrap();
+
+        let mut textures = TextureStorage::default();
+        let mut loaded_paths = Vec::new();
+        let inputs = Engine::obj_material_inputs(&materials, dir.path(), |path| {
+            loaded_paths.push(path.to_path_buf());
+            textures.add_texture(Texture::new(1, 1))
+        });
+
+        let red = &inputs[models[0].mesh.material_id.unwrap()];
+        assert!(red.albedo.is_none());
+        assert_eq!(red.base_color_factor, [0.8, 0.2, 0.1, 1.0]);
+        assert_eq!(red.roughness, 0.75);
+
+        let textured = &inputs[1];
+        assert!(textured.albedo.is_some());
+        assert_eq!(textured.base_color_factor, [0.5, 0.5, 0.5, 1.0]);
+        assert_eq!(loaded_paths, vec![dir.path().join("albedo.png")]);
+    }
+
+    #[test]
+    fn gltf_vertex_colors_are_stored_on_the_mesh() {
+        let dir = tempfile::tempdir().unwrap();
+        // One triangle: positions, normals, UVs, colors, then u32 indices
+        let mut bin: Vec<u8> = Vec::new();
+        for value in [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0] {
+            bin.extend(value.to_le_bytes());
+        }
+        for value in [0.0f32, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0] {
+            bin.extend(value.to_le_bytes());
+        }
+        for value in [0.0f32, 0.0, 1.0, 0.0, 0.0, 1.0] {
+            bin.extend(value.to_le_bytes());
+        }
+        for value in [1.0f32, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0] {
+            bin.extend(value.to_le_bytes());
+        }
+        for index in [0u32, 1, 2] {
+            bin.extend(index.to_le_bytes());
+        }
+        std::fs::write(dir.path().join("triangle.bin"), &bin).unwrap();
+
+        let gltf_path = dir.path().join("colored.gltf");
+        std::fs::write(
+            &gltf_path,
+            r#"{
+                "asset": { "version": "2.0" },
+                "buffers": [{ "uri": "triangle.bin", "byteLength": 144 }],
+                "bufferViews": [
+                    { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+                    { "buffer": 0, "byteOffset": 36, "byteLength": 36 },
+                    { "buffer": 0, "byteOffset": 72, "byteLength": 24 },
+                    { "buffer": 0, "byteOffset": 96, "byteLength": 36 },
+                    { "buffer": 0, "byteOffset": 132, "byteLength": 12 }
+                ],
+                "accessors": [
+                    { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                      "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0] },
+                    { "bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC3" },
+                    { "bufferView": 2, "componentType": 5126, "count": 3, "type": "VEC2" },
+                    { "bufferView": 3, "componentType": 5126, "count": 3, "type": "VEC3" },
+                    { "bufferView": 4, "componentType": 5125, "count": 3, "type": "SCALAR" }
+                ],
+                "meshes": [{
+                    "primitives": [
+                        { "attributes": { "POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2,
+                                          "COLOR_0": 3 },
+                          "indices": 4 },
+                        { "attributes": { "POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2 },
+                          "indices": 4 }
+                    ]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let primitives = Engine::mesh_primatives_from_gltf(gltf_path.as_os_str()).unwrap();
+
+        let colors: Vec<[f32; 3]> = primitives[0]
+            .mesh
+            .vertices
+            .iter()
+            .map(|v| v.color)
+            .collect();
+        assert_eq!(
+            colors,
+            vec![[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+        );
+        assert!(
+            primitives[1]
+                .mesh
+                .vertices
+                .iter()
+                .all(|v| v.color == Vertex::WHITE)
+        );
+    }
 }
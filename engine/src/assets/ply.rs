@@ -0,0 +1,461 @@
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::assets::mesh::{Aabb, FrontFace, Mesh, Vertex};
+
+#[derive(Debug, Error)]
+pub enum PlyError {
+    #[error("IO Error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid PLY header: {0}")]
+    Header(String),
+
+    #[error("Invalid PLY data: {0}")]
+    Data(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl ScalarType {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => ScalarType::I8,
+            "uchar" | "uint8" => ScalarType::U8,
+            "short" | "int16" => ScalarType::I16,
+            "ushort" | "uint16" => ScalarType::U16,
+            "int" | "int32" => ScalarType::I32,
+            "uint" | "uint32" => ScalarType::U32,
+            "float" | "float32" => ScalarType::F32,
+            "double" | "float64" => ScalarType::F64,
+            _ => return None,
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            ScalarType::I8 | ScalarType::U8 => 1,
+            ScalarType::I16 | ScalarType::U16 => 2,
+            ScalarType::I32 | ScalarType::U32 | ScalarType::F32 => 4,
+            ScalarType::F64 => 8,
+        }
+    }
+
+    /// Maps a color channel of this type to 0..=1. Integer channels use their full range.
+    fn normalize_color(self, value: f64) -> f32 {
+        match self {
+            ScalarType::F32 | ScalarType::F64 => value as f32,
+            ScalarType::U16 => (value / u16::MAX as f64) as f32,
+            _ => (value / u8::MAX as f64) as f32,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum PropertyKind {
+    Scalar(ScalarType),
+    List { count: ScalarType, item: ScalarType },
+}
+
+#[derive(Debug)]
+struct Property {
+    name: String,
+    kind: PropertyKind,
+}
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+#[derive(Debug)]
+struct Header {
+    format: Format,
+    elements: Vec<Element>,
+}
+
+/// Reads property values from the body of a PLY file in either encoding.
+enum Values<'a> {
+    Ascii(std::str::SplitAsciiWhitespace<'a>),
+    Binary { data: &'a [u8], big_endian: bool },
+}
+
+impl Values<'_> {
+    fn read(&mut self, ty: ScalarType) -> Result<f64, PlyError> {
+        match self {
+            Values::Ascii(tokens) => {
+                let token = tokens
+                    .next()
+                    .ok_or_else(|| PlyError::Data("unexpected end of data".to_string()))?;
+                token
+                    .parse::<f64>()
+                    .map_err(|_| PlyError::Data(format!("invalid number '{token}'")))
+            }
+            Values::Binary { data, big_endian } => {
+                let size = ty.size();
+                if data.len() < size {
+                    return Err(PlyError::Data("unexpected end of data".to_string()));
+                }
+                let (bytes, rest) = data.split_at(size);
+                *data = rest;
+                let mut buf = [0u8; 8];
+                buf[..size].copy_from_slice(bytes);
+                if *big_endian {
+                    buf[..size].reverse();
+                }
+                Ok(match ty {
+                    ScalarType::I8 => buf[0] as i8 as f64,
+                    ScalarType::U8 => buf[0] as f64,
+                    ScalarType::I16 => i16::from_le_bytes([buf[0], buf[1]]) as f64,
+                    ScalarType::U16 => u16::from_le_bytes([buf[0], buf[1]]) as f64,
+                    ScalarType::I32 => i32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64,
+                    ScalarType::U32 => u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64,
+                    ScalarType::F32 => f32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64,
+                    ScalarType::F64 => f64::from_le_bytes(buf),
+                })
+            }
+        }
+    }
+
+    fn read_index(&mut self, ty: ScalarType) -> Result<usize, PlyError> {
+        let value = self.read(ty)?;
+        if value < 0.0 || value.fract() != 0.0 {
+            return Err(PlyError::Data(format!("invalid index {value}")));
+        }
+        Ok(value as usize)
+    }
+}
+
+/// Imports PLY (Stanford polygon) meshes, as written by most 3D scanners, in ASCII or binary
+/// encoding. Vertex colors are kept, polygons are fan-triangulated, and normals are computed
+/// from the faces when the file has none.
+pub struct PlyProcessor;
+
+impl PlyProcessor {
+    pub fn load(path: &Path, front_face: FrontFace) -> Result<Mesh, PlyError> {
+        Self::parse(&std::fs::read(path)?, front_face)
+    }
+
+    /// Parses a PLY file. `front_face` gives the winding of its faces, which computed normals
+    /// follow.
+    pub fn parse(bytes: &[u8], front_face: FrontFace) -> Result<Mesh, PlyError> {
+        let (header, body) = Self::parse_header(bytes)?;
+        let mut values = match header.format {
+            Format::Ascii => Values::Ascii(
+                std::str::from_utf8(body)
+                    .map_err(|_| PlyError::Data("ASCII data is not valid UTF-8".to_string()))?
+                    .split_ascii_whitespace(),
+            ),
+            Format::BinaryLittleEndian => Values::Binary {
+                data: body,
+                big_endian: false,
+            },
+            Format::BinaryBigEndian => Values::Binary {
+                data: body,
+                big_endian: true,
+            },
+        };
+
+        let mut mesh = Mesh::default();
+        let mut has_normals = false;
+        for element in &header.elements {
+            match element.name.as_str() {
+                "vertex" => {
+                    // Each vertex takes at least a byte, so a bogus count can't reserve more
+                    // than the file could hold
+                    mesh.vertices.reserve(element.count.min(body.len()));
+                    for _ in 0..element.count {
+                        let mut vertex = Vertex {
+                            tangent: [1.0, 0.0, 0.0, 1.0],
+                            ..Vertex::default()
+                        };
+                        for property in &element.properties {
+                            let PropertyKind::Scalar(ty) = property.kind else {
+                                Self::skip(&mut values, &property.kind)?;
+                                continue;
+                            };
+                            let value = values.read(ty)?;
+                            match property.name.as_str() {
+                                "x" => vertex.position[0] = value as f32,
+                                "y" => vertex.position[1] = value as f32,
+                                "z" => vertex.position[2] = value as f32,
+                                "nx" => vertex.normal[0] = value as f32,
+                                "ny" => vertex.normal[1] = value as f32,
+                                "nz" => vertex.normal[2] = value as f32,
+                                "red" | "diffuse_red" => {
+                                    vertex.color[0] = ty.normalize_color(value)
+                                }
+                                "green" | "diffuse_green" => {
+                                    vertex.color[1] = ty.normalize_color(value)
+                                }
+                                "blue" | "diffuse_blue" => {
+                                    vertex.color[2] = ty.normalize_color(value)
+                                }
+                                _ => {}
+                            }
+                        }
+                        mesh.vertices.push(vertex);
+                    }
+                    has_normals = element.properties.iter().any(|p| p.name == "nx");
+                }
+                "face" => {
+                    for _ in 0..element.count {
+                        for property in &element.properties {
+                            match property.kind {
+                                PropertyKind::List { count, item }
+                                    if property.name == "vertex_indices"
+                                        || property.name == "vertex_index" =>
+                                {
+                                    let len = values.read_index(count)?;
+                                    let polygon = (0..len)
+                                        .map(|_| values.read_index(item))
+                                        .collect::<Result<Vec<_>, _>>()?;
+                                    // Fan triangulation; scanners only write convex polygons
+                                    for i in 1..polygon.len().saturating_sub(1) {
+                                        for index in [polygon[0], polygon[i], polygon[i + 1]] {
+                                            mesh.indices.push(index as u32);
+                                        }
+                                    }
+                                }
+                                _ => Self::skip(&mut values, &property.kind)?,
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    for _ in 0..element.count {
+                        for property in &element.properties {
+                            Self::skip(&mut values, &property.kind)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        let vertex_count = mesh.vertices.len();
+        if vertex_count == 0 {
+            return Err(PlyError::Data("mesh has no vertices".to_string()));
+        }
+        if let Some(index) = mesh.indices.iter().find(|i| **i as usize >= vertex_count) {
+            return Err(PlyError::Data(format!(
+                "face index {index} out of range for {vertex_count} vertices"
+            )));
+        }
+
+        mesh.set_front_face(front_face);
+        if !has_normals {
+            mesh.recompute_normals();
+        }
+        mesh.aabb = Aabb::from_vertices(&mesh.vertices);
+        mesh.compute_bounding_sphere();
+        Ok(mesh)
+    }
+
+    fn skip(values: &mut Values<'_>, kind: &PropertyKind) -> Result<(), PlyError> {
+        match *kind {
+            PropertyKind::Scalar(ty) => {
+                values.read(ty)?;
+            }
+            PropertyKind::List { count, item } => {
+                for _ in 0..values.read_index(count)? {
+                    values.read(item)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Splits the file into its parsed header and the raw body that follows `end_header`.
+    fn parse_header(bytes: &[u8]) -> Result<(Header, &[u8]), PlyError> {
+        const END: &[u8] = b"end_header";
+        let end = bytes
+            .windows(END.len())
+            .position(|window| window == END)
+            .ok_or_else(|| PlyError::Header("missing end_header".to_string()))?;
+        let body_start = bytes[end..]
+            .iter()
+            .position(|b| *b == b'\n')
+            .map_or(bytes.len(), |newline| end + newline + 1);
+        let text = std::str::from_utf8(&bytes[..end])
+            .map_err(|_| PlyError::Header("header is not valid UTF-8".to_string()))?;
+
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        if lines.next() != Some("ply") {
+            return Err(PlyError::Header("missing 'ply' magic".to_string()));
+        }
+
+        let mut format = None;
+        let mut elements: Vec<Element> = Vec::new();
+        for line in lines {
+            let words: Vec<&str> = line.split_ascii_whitespace().collect();
+            match words.as_slice() {
+                ["format", name, _version] => {
+                    format = Some(match *name {
+                        "ascii" => Format::Ascii,
+                        "binary_little_endian" => Format::BinaryLittleEndian,
+                        "binary_big_endian" => Format::BinaryBigEndian,
+                        _ => return Err(PlyError::Header(format!("unknown format '{name}'"))),
+                    });
+                }
+                ["comment", ..] | ["obj_info", ..] => {}
+                ["element", name, count] => elements.push(Element {
+                    name: name.to_string(),
+                    count: count
+                        .parse()
+                        .map_err(|_| PlyError::Header(format!("invalid count in '{line}'")))?,
+                    properties: Vec::new(),
+                }),
+                ["property", "list", count, item, name] => {
+                    let kind = match (ScalarType::parse(count), ScalarType::parse(item)) {
+                        (Some(count), Some(item)) => PropertyKind::List { count, item },
+                        _ => return Err(PlyError::Header(format!("unknown type in '{line}'"))),
+                    };
+                    Self::push_property(&mut elements, name, kind, line)?;
+                }
+                ["property", ty, name] => {
+                    let ty = ScalarType::parse(ty)
+                        .ok_or_else(|| PlyError::Header(format!("unknown type in '{line}'")))?;
+                    Self::push_property(&mut elements, name, PropertyKind::Scalar(ty), line)?;
+                }
+                _ => return Err(PlyError::Header(format!("unexpected line '{line}'"))),
+            }
+        }
+
+        let format = format.ok_or_else(|| PlyError::Header("missing format".to_string()))?;
+        Ok((Header { format, elements }, &bytes[body_start..]))
+    }
+
+    fn push_property(
+        elements: &mut [Element],
+        name: &str,
+        kind: PropertyKind,
+        line: &str,
+    ) -> Result<(), PlyError> {
+        let element = elements
+            .last_mut()
+            .ok_or_else(|| PlyError::Header(format!("property before any element: '{line}'")))?;
+        element.properties.push(Property {
+            name: name.to_string(),
+            kind,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn colored_quad_header(format: &str) -> String {
+        format!(
+            "ply\nformat {format} 1.0\ncomment scanned\nelement vertex 4\n\
+             property float x\nproperty float y\nproperty float z\n\
+             property uchar red\nproperty uchar green\nproperty uchar blue\n\
+             element face 1\nproperty list uchar int vertex_indices\nend_header\n"
+        )
+    }
+
+    const QUAD: [([f32; 3], [u8; 3]); 4] = [
+        ([0.0, 0.0, 0.0], [255, 0, 0]),
+        ([1.0, 0.0, 0.0], [0, 255, 0]),
+        ([1.0, 1.0, 0.0], [0, 0, 255]),
+        ([0.0, 1.0, 0.0], [255, 255, 255]),
+    ];
+
+    fn assert_colored_quad(mesh: &Mesh) {
+        assert_eq!(mesh.vertices.len(), 4);
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(mesh.vertices[0].color, [1.0, 0.0, 0.0]);
+        assert_eq!(mesh.vertices[1].color, [0.0, 1.0, 0.0]);
+        assert_eq!(mesh.vertices[2].color, [0.0, 0.0, 1.0]);
+        assert_eq!(mesh.vertices[3].color, Vertex::WHITE);
+        assert_eq!(mesh.vertices[2].position, [1.0, 1.0, 0.0]);
+        // Counter-clockwise seen from +Z
+        assert_eq!(mesh.vertices[0].normal, [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn binary_ply_vertices_faces_and_colors_are_imported() {
+        let mut bytes = colored_quad_header("binary_little_endian").into_bytes();
+        for (position, color) in QUAD {
+            for value in position {
+                bytes.extend(value.to_le_bytes());
+            }
+            bytes.extend(color);
+        }
+        bytes.push(4);
+        for index in [0i32, 1, 2, 3] {
+            bytes.extend(index.to_le_bytes());
+        }
+
+        assert_colored_quad(&PlyProcessor::parse(&bytes, FrontFace::Ccw).unwrap());
+    }
+
+    #[test]
+    fn ascii_ply_matches_binary() {
+        let mut text = colored_quad_header("ascii");
+        for (p, c) in QUAD {
+            text += &format!("{} {} {} {} {} {}\n", p[0], p[1], p[2], c[0], c[1], c[2]);
+        }
+        text += "4 0 1 2 3\n";
+
+        assert_colored_quad(&PlyProcessor::parse(text.as_bytes(), FrontFace::Ccw).unwrap());
+    }
+
+    #[test]
+    fn out_of_range_face_index_is_an_error() {
+        let text = "ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\n\
+                    property float y\nproperty float z\nelement face 1\n\
+                    property list uchar int vertex_indices\nend_header\n0 0 0\n3 0 1 2\n";
+
+        assert!(matches!(
+            PlyProcessor::parse(text.as_bytes(), FrontFace::Ccw),
+            Err(PlyError::Data(_))
+        ));
+    }
+
+    #[test]
+    fn mesh_without_vertices_is_an_error() {
+        let text = "ply\nformat ascii 1.0\nelement vertex 0\nproperty float x\n\
+                    property float y\nproperty float z\nend_header\n";
+
+        assert!(matches!(
+            PlyProcessor::parse(text.as_bytes(), FrontFace::Ccw),
+            Err(PlyError::Data(_))
+        ));
+    }
+
+    #[test]
+    fn vertex_count_beyond_the_data_is_an_error() {
+        let mut bytes = "ply\nformat binary_little_endian 1.0\nelement vertex 4000000000000\n\
+                         property float x\nproperty float y\nproperty float z\nend_header\n"
+            .to_string()
+            .into_bytes();
+        bytes.extend([0u8; 12]);
+
+        assert!(matches!(
+            PlyProcessor::parse(&bytes, FrontFace::Ccw),
+            Err(PlyError::Data(_))
+        ));
+    }
+}
@@ -0,0 +1,47 @@
+use slotmap::{Key, SecondaryMap};
+
+/// Live entity references per asset handle, plus the handles whose unload is waiting for
+/// those references to go away.
+pub(crate) struct RefCounts<K: Key> {
+    counts: SecondaryMap<K, usize>,
+    pending_unload: SecondaryMap<K, ()>,
+}
+
+impl<K: Key> Default for RefCounts<K> {
+    fn default() -> Self {
+        Self {
+            counts: SecondaryMap::new(),
+            pending_unload: SecondaryMap::new(),
+        }
+    }
+}
+
+impl<K: Key> RefCounts<K> {
+    pub(crate) fn acquire(&mut self, handle: K) {
+        if let Some(count) = self.counts.entry(handle) {
+            *count.or_insert(0) += 1;
+        }
+    }
+
+    /// Drops one reference. Returns `true` when this was the last one and an unload was
+    /// waiting on it, meaning the asset should be freed now.
+    pub(crate) fn release(&mut self, handle: K) -> bool {
+        let Some(count) = self.counts.get_mut(handle) else {
+            return false;
+        };
+        *count = count.saturating_sub(1);
+        if *count > 0 {
+            return false;
+        }
+        self.counts.remove(handle);
+        self.pending_unload.remove(handle).is_some()
+    }
+
+    pub(crate) fn is_referenced(&self, handle: K) -> bool {
+        self.counts.get(handle).is_some_and(|count| *count > 0)
+    }
+
+    pub(crate) fn defer_unload(&mut self, handle: K) {
+        self.pending_unload.insert(handle, ());
+    }
+}
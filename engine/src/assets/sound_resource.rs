@@ -6,12 +6,14 @@ use std::{
 use bevy_ecs::prelude::*;
 use slotmap::SlotMap;
 
-use crate::assets::{handles::SoundHandle, sound::Sound};
+use crate::assets::{handles::SoundHandle, ref_counts::RefCounts, sound::Sound};
 
 #[derive(Default)]
 pub struct SoundStorage {
     pub sounds: SlotMap<SoundHandle, Sound>,
     pub name_map: HashMap<String, SoundHandle>,
+    /// References held by `AudioSourceComponent`s.
+    pub(crate) refs: RefCounts<SoundHandle>,
 }
 
 #[derive(Resource, Default, Clone)]
@@ -36,6 +38,14 @@ impl SoundResource {
             }
         }
     }
+
+    /// Frees the sound behind `handle` and its name lookup. Voices that are already playing
+    /// keep their own reference to the samples and finish normally. While an
+    /// `AudioSourceComponent` still uses the sound, the unload is deferred until it is removed.
+    /// Returns `false` if the handle was already unloaded.
+    pub fn unload(&self, handle: SoundHandle) -> bool {
+        self.write().unload_sound(handle)
+    }
 }
 impl SoundStorage {
     pub fn add_sound(&mut self, sound: Sound, name: String) -> SoundHandle {
@@ -53,15 +63,33 @@ impl SoundStorage {
         self.sounds.get_mut(sound_id)
     }
 
-    #[allow(dead_code)]
-    pub fn remove_sound(&mut self, sound_id: SoundHandle) {
-        if self.sounds.remove(sound_id).is_some() {
-            println!("Removed sound with ID: {:?}", sound_id);
+    pub fn unload_sound(&mut self, sound_id: SoundHandle) -> bool {
+        if !self.sounds.contains_key(sound_id) {
+            return false;
+        }
+        if self.refs.is_referenced(sound_id) {
+            self.refs.defer_unload(sound_id);
         } else {
-            println!("Sound with ID {:?} not found for removal", sound_id);
+            self.free_sound(sound_id);
+        }
+        true
+    }
+
+    pub(crate) fn acquire_sound(&mut self, sound_id: SoundHandle) {
+        self.refs.acquire(sound_id);
+    }
+
+    pub(crate) fn release_sound(&mut self, sound_id: SoundHandle) {
+        if self.refs.release(sound_id) {
+            self.free_sound(sound_id);
         }
     }
 
+    fn free_sound(&mut self, sound_id: SoundHandle) {
+        self.sounds.remove(sound_id);
+        self.name_map.retain(|_, handle| *handle != sound_id);
+    }
+
     pub fn get_by_name(&self, name: &str) -> Option<SoundHandle> {
         self.name_map.get(name).copied()
     }
@@ -11,6 +11,8 @@ use crate::render::renderer;
 #[derive(Default)]
 pub struct TextureStorage {
     pub textures: SlotMap<TextureHandle, Texture>,
+    /// GL textures of unloaded entries, deleted by the renderer on the next frame.
+    pub(crate) unloaded: Vec<glow::Texture>,
 }
 
 #[derive(Resource, Default, Clone)]
@@ -35,6 +37,12 @@ impl TextureResource {
             }
         }
     }
+
+    /// Frees the texture behind `handle`. The GL texture is deleted by the renderer on the
+    /// next frame. Returns `false` if the handle was already unloaded.
+    pub fn unload(&self, handle: TextureHandle) -> bool {
+        self.write().unload_texture(handle)
+    }
 }
 
 impl TextureStorage {
@@ -42,6 +50,12 @@ impl TextureStorage {
         self.textures.insert(texture)
     }
 
+    /// Registers a `width` x `height` texture without GPU storage, to be rendered into by
+    /// `Engine::render_camera_into`. Zero dimensions are raised to one.
+    pub fn create_render_target(&mut self, width: u32, height: u32) -> TextureHandle {
+        self.add_texture(Texture::new(width.max(1), height.max(1)))
+    }
+
     pub fn load_from_file(&mut self, gl: &Context, path: &OsStr) -> TextureHandle {
         // Load image with the `image` crate
         let img = image::open(path)
@@ -75,4 +89,31 @@ impl TextureStorage {
     pub fn get_texture(&self, id: TextureHandle) -> Option<&Texture> {
         self.textures.get(id)
     }
+
+    pub fn unload_texture(&mut self, id: TextureHandle) -> bool {
+        let Some(texture) = self.textures.remove(id) else {
+            return false;
+        };
+        self.unloaded.extend(texture.gl_tex);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_target_is_registered_with_the_requested_size() {
+        let resource = TextureResource::default();
+        let handle = resource.write().create_render_target(256, 128);
+
+        let storage = resource.read();
+        let texture = storage
+            .get_texture(handle)
+            .expect("render target should be registered");
+        assert_eq!((texture.width, texture.height), (256, 128));
+        // GPU storage is allocated on the first render
+        assert!(texture.gl_tex.is_none());
+    }
 }
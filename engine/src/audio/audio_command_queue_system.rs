@@ -16,7 +16,28 @@ impl AudioCommandQueueSystem {
         mut audio: ResMut<AudioControl>,
     ) {
         for (entity, source, _) in query.iter() {
-            audio.spawn_spatial_emitter(0, source.sound, source.volume, source.looping, entity);
+            audio.spawn_spatial_emitter(
+                0,
+                source.sound,
+                source.volume,
+                source.pitch,
+                source.looping,
+                entity,
+            );
+        }
+    }
+
+    /// Forwards volume and pitch edits on existing sources to their playing voices.
+    pub fn update_changed_sources(
+        query: Query<(Entity, Ref<AudioSourceComponent>), Changed<AudioSourceComponent>>,
+        mut audio: ResMut<AudioControl>,
+    ) {
+        for (entity, source) in query.iter() {
+            // Newly added sources are spawned with their initial values
+            if source.is_added() {
+                continue;
+            }
+            audio.set_voice_params(entity, source.volume, source.pitch);
         }
     }
 
@@ -24,3 +45,52 @@ impl AudioCommandQueueSystem {
         audio.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SoundHandle, audio::audio_control::AudioCommand};
+
+    #[test]
+    fn changing_source_volume_emits_voice_params() {
+        let mut world = World::new();
+        world.insert_resource(AudioControl::default());
+        let source = world
+            .spawn(AudioSourceComponent {
+                sound: SoundHandle::default(),
+                volume: 1.0,
+                pitch: 1.0,
+                looping: true,
+                occludable: false,
+            })
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                AudioCommandQueueSystem::build_command_queue,
+                AudioCommandQueueSystem::update_changed_sources,
+            )
+                .chain(),
+        );
+        schedule.run(&mut world);
+        let queue = world.resource::<AudioControl>().queue();
+        assert!(matches!(
+            queue,
+            [AudioCommand::SpawnSpatialEmitter { volume, .. }] if *volume == 1.0
+        ));
+
+        world.resource_mut::<AudioControl>().clear();
+        world
+            .get_mut::<AudioSourceComponent>(source)
+            .unwrap()
+            .volume = 0.4;
+        schedule.run(&mut world);
+        let queue = world.resource::<AudioControl>().queue();
+        assert!(matches!(
+            queue,
+            [AudioCommand::SetVoiceParams { voice, volume, pitch }]
+                if *voice == source && *volume == 0.4 && *pitch == 1.0
+        ));
+    }
+}
@@ -3,7 +3,10 @@ use glam::{Quat, Vec3};
 
 use crate::{
     SoundHandle,
-    audio::audio_mixer::{ListenerInfo, SourceInfo},
+    audio::{
+        audio_mixer::{ListenerInfo, SourceInfo},
+        limiter::LimiterConfig,
+    },
 };
 
 #[derive(Debug)]
@@ -12,6 +15,7 @@ pub(crate) enum AudioCommand {
         track: u8,
         sound: SoundHandle,
         volume: f32,
+        pitch: f32,
         looping: bool,
         source: Entity,
     },
@@ -46,6 +50,18 @@ pub(crate) enum AudioCommand {
     RemoveSourceInfo {
         entity: Entity,
     },
+    SetLimiter {
+        config: LimiterConfig,
+    },
+    SetVoiceParams {
+        voice: Entity,
+        volume: f32,
+        pitch: f32,
+    },
+    SetVoiceOcclusion {
+        voice: Entity,
+        occluded: bool,
+    },
 }
 
 #[derive(Resource, Default)]
@@ -80,6 +96,7 @@ impl AudioControl {
         track: u8,
         sound: SoundHandle,
         volume: f32,
+        pitch: f32,
         looping: bool,
         source: Entity,
     ) {
@@ -87,11 +104,24 @@ impl AudioControl {
             track,
             sound,
             volume,
+            pitch,
             looping,
             source,
         });
     }
 
+    pub(crate) fn set_voice_params(&mut self, voice: Entity, volume: f32, pitch: f32) {
+        self.push(AudioCommand::SetVoiceParams {
+            voice,
+            volume,
+            pitch,
+        });
+    }
+
+    pub(crate) fn set_voice_occlusion(&mut self, voice: Entity, occluded: bool) {
+        self.push(AudioCommand::SetVoiceOcclusion { voice, occluded });
+    }
+
     pub(crate) fn remove_spatial_emitter(&mut self, source: Entity) {
         self.push(AudioCommand::RemoveSourceInfo { entity: source });
     }
@@ -143,6 +173,11 @@ impl AudioControl {
         self.push(AudioCommand::UnmuteMix);
     }
 
+    /// Changes the threshold and release of the master bus limiter.
+    pub fn set_limiter(&mut self, config: LimiterConfig) {
+        self.push(AudioCommand::SetLimiter { config });
+    }
+
     pub(crate) fn queue(&self) -> &[AudioCommand] {
         &self.queue
     }
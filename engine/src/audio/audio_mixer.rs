@@ -5,16 +5,24 @@ use cpal::{
 };
 use glam::{Quat, Vec3};
 use rtrb::{Consumer, Producer, RingBuffer};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc};
 
 use crate::{
     assets::sound_resource::SoundStorage,
-    audio::{audio_control::AudioCommand, track::Track, voice::Voice},
+    audio::{
+        audio_control::AudioCommand,
+        limiter::{Limiter, LimiterConfig},
+        mix_capture::{CaptureTap, MixCapture},
+        track::Track,
+        voice::Voice,
+    },
 };
 pub struct AudioMixer {
     stream: Option<Stream>,
     pub sample_rate: cpal::SampleRate,
+    channels: u16,
     producer: Producer<MixerCommand>,
+    capture: Option<MixCapture>,
 }
 
 pub(crate) type ListenerInfo = (Vec3, Quat); // position, rotation
@@ -26,6 +34,7 @@ enum MixerCommand {
         samples: Arc<[f32]>,
         sample_rate: f32,
         volume: f32,
+        pitch: f32,
         looping: bool,
         source_channels: u16,
         source: Option<Entity>,
@@ -51,6 +60,21 @@ enum MixerCommand {
     RemoveSourceInfo {
         entity: Entity,
     },
+    SetLimiter {
+        config: LimiterConfig,
+    },
+    SetVoiceParams {
+        source: Entity,
+        volume: f32,
+        pitch: f32,
+    },
+    SetVoiceOcclusion {
+        source: Entity,
+        occluded: bool,
+    },
+    SetCaptureTap {
+        tap: CaptureTap,
+    },
 }
 
 impl Default for AudioMixer {
@@ -76,6 +100,7 @@ impl Default for AudioMixer {
         });
 
         let active_tracks = Vec::with_capacity(32);
+        let limiter = Limiter::new(LimiterConfig::default(), sample_rate as f32);
         let paused = false;
         let muted = false;
         let (producer, consumer) = RingBuffer::<MixerCommand>::new(4096);
@@ -83,6 +108,8 @@ impl Default for AudioMixer {
             stream: None,
             producer,
             sample_rate,
+            channels,
+            capture: None,
         };
 
         let listener_info = None; // position, rotation
@@ -100,6 +127,7 @@ impl Default for AudioMixer {
             listener_info,
             source_map,
             active_tracks,
+            limiter,
         ));
         s
     }
@@ -118,8 +146,10 @@ impl AudioMixer {
         mut listener_info: Option<ListenerInfo>,
         mut source_map: HashMap<Entity, Vec3>,
         mut active_tracks: Vec<usize>,
+        mut limiter: Limiter,
     ) -> Stream {
         let channels = config.channels() as usize;
+        let mut capture_tap: Option<CaptureTap> = None;
         let stream = device
             .build_output_stream(
                 &config.into(),
@@ -132,6 +162,8 @@ impl AudioMixer {
                         &mut listener_info,
                         output.len(),
                         &mut source_map,
+                        &mut limiter,
+                        &mut capture_tap,
                     );
                     if paused {
                         for frame_out in output.chunks_mut(channels) {
@@ -158,27 +190,18 @@ impl AudioMixer {
                         active_tracks.push(index);
                     }
 
-                    for frame in 0..required_frames {
-                        for ch in 0..channels {
-                            let out_index = frame * channels + ch;
-
-                            for &track_index in &active_tracks {
-                                let track = &tracks[track_index];
-                                let src_ch = if track.channels == 1 { 0 } else { ch };
-
-                                output[out_index] +=
-                                    track.buffer[frame * track.channels as usize + src_ch];
-                            }
-                        }
-                    }
-
-                    let mute_gain = if muted { 0.0 } else { 1.0 };
+                    Self::mix_tracks(
+                        output,
+                        &tracks,
+                        &active_tracks,
+                        channels,
+                        muted,
+                        &mut limiter,
+                    );
 
-                    for sample in output.iter_mut() {
-                        *sample = (*sample * mute_gain).clamp(-1.0, 1.0);
-                    }
+                    Self::tee_capture(&mut capture_tap, output);
                 },
-                move |err| eprintln!("Stream error: {}", err),
+                move |err| log::error!(target: "audio", "Stream error: {err}"),
                 None, // None=blocking, Some(Duration)=timeout
             )
             .expect("failed to build output stream");
@@ -186,6 +209,79 @@ impl AudioMixer {
         stream
     }
 
+    /// Sums the buffers of `active_tracks` into `output`, runs the master limiter, then
+    /// applies the master mute and clamps to full scale.
+    fn mix_tracks(
+        output: &mut [f32],
+        tracks: &[Track],
+        active_tracks: &[usize],
+        channels: usize,
+        muted: bool,
+        limiter: &mut Limiter,
+    ) {
+        let required_frames = output.len() / channels;
+        for frame in 0..required_frames {
+            for ch in 0..channels {
+                let out_index = frame * channels + ch;
+
+                for &track_index in active_tracks {
+                    let track = &tracks[track_index];
+                    let src_ch = if track.channels == 1 { 0 } else { ch };
+
+                    output[out_index] += track.buffer[frame * track.channels as usize + src_ch];
+                }
+            }
+        }
+
+        limiter.process(output, channels);
+
+        let mute_gain = if muted { 0.0 } else { 1.0 };
+
+        for sample in output.iter_mut() {
+            *sample = (*sample * mute_gain).clamp(-1.0, 1.0);
+        }
+    }
+
+    /// Queues a mixed block on the capture tap, if any, and drops a tap whose capture has
+    /// finished. Runs in the output callback, so it never touches the file itself.
+    fn tee_capture(capture_tap: &mut Option<CaptureTap>, output: &[f32]) {
+        if capture_tap.as_ref().is_some_and(CaptureTap::is_closed) {
+            *capture_tap = None;
+        }
+        if let Some(tap) = capture_tap {
+            tap.write(output);
+        }
+    }
+
+    /// Starts writing the mixed output to a WAV file at `path`, replacing any capture
+    /// already in progress. Intended for debugging spatialization offline.
+    pub fn start_capture(&mut self, path: &Path) -> hound::Result<()> {
+        let (capture, tap) = MixCapture::create(path, self.channels, self.sample_rate)?;
+        if self
+            .producer
+            .push(MixerCommand::SetCaptureTap { tap })
+            .is_err()
+        {
+            capture.finish()?;
+            return Err(hound::Error::IoError(std::io::Error::other(
+                "audio mixer command queue is full",
+            )));
+        }
+        if let Some(previous) = self.capture.replace(capture) {
+            previous.finish()?;
+        }
+        Ok(())
+    }
+
+    /// Stops the current capture, if any, and finalizes the WAV file.
+    pub fn stop_capture(&mut self) -> hound::Result<()> {
+        match self.capture.take() {
+            Some(capture) => capture.finish(),
+            None => Ok(()),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn process_mixer_commands(
         consumer: &mut Consumer<MixerCommand>,
         tracks: &mut [Track],
@@ -194,6 +290,8 @@ impl AudioMixer {
         listener_info: &mut Option<ListenerInfo>,
         required_buffer_size_for_voices: usize,
         source_map: &mut HashMap<Entity, Vec3>,
+        limiter: &mut Limiter,
+        capture_tap: &mut Option<CaptureTap>,
     ) {
         while let Ok(command) = consumer.pop() {
             match command {
@@ -202,13 +300,14 @@ impl AudioMixer {
                     samples,
                     sample_rate,
                     volume,
+                    pitch,
                     looping,
                     source_channels,
                     source,
                     location,
                 } => {
                     if let Some(track) = tracks.get_mut(track as usize) {
-                        track.voices.push(Voice::new(
+                        let mut voice = Voice::new(
                             samples,
                             sample_rate,
                             volume,
@@ -217,7 +316,9 @@ impl AudioMixer {
                             location,
                             source_channels,
                             required_buffer_size_for_voices,
-                        ));
+                        );
+                        voice.set_params(volume, pitch);
+                        track.voices.push(voice);
                         if let Some(source) = source {
                             // This is going to lead to a 1 frame lag in position... Should fix
                             source_map.insert(source, Vec3::ZERO); // Default location
@@ -259,6 +360,34 @@ impl AudioMixer {
                 MixerCommand::RemoveSourceInfo { entity } => {
                     source_map.remove(&entity);
                 }
+                MixerCommand::SetLimiter { config } => {
+                    limiter.set_config(config);
+                }
+                MixerCommand::SetVoiceParams {
+                    source,
+                    volume,
+                    pitch,
+                } => {
+                    for voice in tracks
+                        .iter_mut()
+                        .flat_map(|track| track.voices.iter_mut())
+                        .filter(|voice| voice.source() == Some(source))
+                    {
+                        voice.set_params(volume, pitch);
+                    }
+                }
+                MixerCommand::SetVoiceOcclusion { source, occluded } => {
+                    for voice in tracks
+                        .iter_mut()
+                        .flat_map(|track| track.voices.iter_mut())
+                        .filter(|voice| voice.source() == Some(source))
+                    {
+                        voice.set_occluded(occluded);
+                    }
+                }
+                MixerCommand::SetCaptureTap { tap } => {
+                    *capture_tap = Some(tap);
+                }
             }
         }
     }
@@ -275,6 +404,7 @@ impl AudioMixer {
                     track,
                     sound,
                     volume,
+                    pitch,
                     looping,
                     source,
                 } => {
@@ -285,6 +415,7 @@ impl AudioMixer {
                                 samples: sound.data.clone(),
                                 sample_rate: sound.sample_rate as f32,
                                 volume: *volume,
+                                pitch: *pitch,
                                 looping: *looping,
                                 source_channels: sound.channels,
                                 source: Some(*source),
@@ -292,7 +423,7 @@ impl AudioMixer {
                             })
                             .expect(MIXER_FULL_ERROR_MESSAGE);
                     } else {
-                        eprintln!("Sound ID {:?} not found", sound);
+                        log::warn!(target: "audio", "Sound ID {sound:?} not found");
                     }
                 }
                 AudioCommand::PlayOneShotAtLocation {
@@ -308,6 +439,7 @@ impl AudioMixer {
                                 samples: sound.data.clone(), // Cloning an Arc
                                 sample_rate: sound.sample_rate as f32,
                                 volume: *volume,
+                                pitch: 1.0,
                                 looping: false,
                                 source_channels: sound.channels,
                                 source: None,
@@ -315,7 +447,7 @@ impl AudioMixer {
                             })
                             .expect(MIXER_FULL_ERROR_MESSAGE);
                     } else {
-                        eprintln!("Sound ID {:?} not found", sound);
+                        log::warn!(target: "audio", "Sound ID {sound:?} not found");
                     }
                 }
                 AudioCommand::PlayOneShot {
@@ -330,6 +462,7 @@ impl AudioMixer {
                                 samples: sound.data.clone(), // Cloning an Arc
                                 sample_rate: sound.sample_rate as f32,
                                 volume: *volume,
+                                pitch: 1.0,
                                 looping: false,
                                 source_channels: sound.channels,
                                 source: None,
@@ -337,7 +470,7 @@ impl AudioMixer {
                             })
                             .expect(MIXER_FULL_ERROR_MESSAGE);
                     } else {
-                        eprintln!("Sound ID {:?} not found", sound);
+                        log::warn!(target: "audio", "Sound ID {sound:?} not found");
                     }
                 }
                 AudioCommand::PauseMix => {
@@ -395,7 +528,102 @@ impl AudioMixer {
                         .push(MixerCommand::RemoveSourceInfo { entity: *entity })
                         .expect(MIXER_FULL_ERROR_MESSAGE);
                 }
+                AudioCommand::SetLimiter { config } => {
+                    self.producer
+                        .push(MixerCommand::SetLimiter { config: *config })
+                        .expect(MIXER_FULL_ERROR_MESSAGE);
+                }
+                AudioCommand::SetVoiceParams {
+                    voice,
+                    volume,
+                    pitch,
+                } => {
+                    self.producer
+                        .push(MixerCommand::SetVoiceParams {
+                            source: *voice,
+                            volume: *volume,
+                            pitch: *pitch,
+                        })
+                        .expect(MIXER_FULL_ERROR_MESSAGE);
+                }
+                AudioCommand::SetVoiceOcclusion { voice, occluded } => {
+                    self.producer
+                        .push(MixerCommand::SetVoiceOcclusion {
+                            source: *voice,
+                            occluded: *occluded,
+                        })
+                        .expect(MIXER_FULL_ERROR_MESSAGE);
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track_with_buffer(channels: u16, buffer: Vec<f32>) -> Track {
+        Track {
+            volume: 1.0,
+            playing: true,
+            voices: Vec::new(),
+            buffer,
+            channels,
+            finished_indices_buffer: Vec::new(),
+            muted: false,
+            has_active_voices: true,
+        }
+    }
+
+    #[test]
+    fn capture_writes_the_mixed_buffer_to_wav() {
+        let frames = 256;
+        let tone: Vec<f32> = (0..frames).map(|i| (i as f32 * 0.05).sin() * 0.5).collect();
+        let stereo: Vec<f32> = (0..frames * 2)
+            .map(|i| if i % 2 == 0 { 0.25 } else { -0.25 })
+            .collect();
+        let mut tracks = [track_with_buffer(1, tone), track_with_buffer(2, stereo)];
+        let mut limiter = Limiter::new(LimiterConfig::default(), 48_000.0);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mix.wav");
+        let (capture, tap) = MixCapture::create(&path, 2, 48_000).unwrap();
+
+        // Hand the tap over the command queue and tee blocks the way the output callback does
+        let (mut producer, mut consumer) = RingBuffer::<MixerCommand>::new(4);
+        assert!(producer.push(MixerCommand::SetCaptureTap { tap }).is_ok());
+        let mut capture_tap = None;
+        AudioMixer::process_mixer_commands(
+            &mut consumer,
+            &mut tracks,
+            &mut false,
+            &mut false,
+            &mut None,
+            frames * 2,
+            &mut HashMap::new(),
+            &mut limiter,
+            &mut capture_tap,
+        );
+        assert!(capture_tap.is_some());
+
+        let mut captured = Vec::new();
+        for _ in 0..3 {
+            let mut output = vec![0.0; frames * 2];
+            AudioMixer::mix_tracks(&mut output, &tracks, &[0, 1], 2, false, &mut limiter);
+            AudioMixer::tee_capture(&mut capture_tap, &output);
+            captured.extend(output);
+        }
+        capture.finish().unwrap();
+
+        let mut reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        assert_eq!(reader.spec().sample_rate, 48_000);
+        let samples: Vec<f32> = reader.samples::<f32>().map(Result::unwrap).collect();
+        assert_eq!(samples, captured);
+
+        // The next block after the capture finished releases the tap
+        AudioMixer::tee_capture(&mut capture_tap, &captured[..frames * 2]);
+        assert!(capture_tap.is_none());
+    }
+}
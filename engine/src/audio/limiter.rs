@@ -0,0 +1,125 @@
+/// Settings for the master bus limiter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LimiterConfig {
+    /// Level where the soft knee starts. Peaks above it are squeezed towards full scale.
+    pub threshold: f32,
+    /// Time for the gain to recover after a peak, in seconds.
+    pub release_seconds: f32,
+}
+
+impl Default for LimiterConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.8,
+            release_seconds: 0.1,
+        }
+    }
+}
+
+/// Soft-knee peak limiter with instant attack and exponential release.
+#[derive(Debug)]
+pub(crate) struct Limiter {
+    config: LimiterConfig,
+    sample_rate: f32,
+    release_coeff: f32,
+    gain: f32,
+}
+
+impl Limiter {
+    pub(crate) fn new(config: LimiterConfig, sample_rate: f32) -> Self {
+        let mut limiter = Self {
+            config,
+            sample_rate,
+            release_coeff: 0.0,
+            gain: 1.0,
+        };
+        limiter.set_config(config);
+        limiter
+    }
+
+    pub(crate) fn set_config(&mut self, config: LimiterConfig) {
+        self.config = LimiterConfig {
+            threshold: config.threshold.clamp(0.0, 1.0),
+            release_seconds: config.release_seconds.max(0.0),
+        };
+        let release_samples = self.config.release_seconds * self.sample_rate;
+        self.release_coeff = if release_samples > 0.0 {
+            1.0 - (-1.0 / release_samples).exp()
+        } else {
+            1.0
+        };
+    }
+
+    /// Level a peak of `peak` is mapped to. Identity below the threshold; above it the curve
+    /// bends smoothly towards, but never reaches, full scale.
+    fn knee(&self, peak: f32) -> f32 {
+        let threshold = self.config.threshold;
+        if peak <= threshold {
+            return peak;
+        }
+        let headroom = 1.0 - threshold;
+        if headroom <= f32::EPSILON {
+            return threshold;
+        }
+        threshold + headroom * ((peak - threshold) / headroom).tanh()
+    }
+
+    /// Limits interleaved `samples` in place, one gain per frame across all channels.
+    pub(crate) fn process(&mut self, samples: &mut [f32], channels: usize) {
+        for frame in samples.chunks_mut(channels) {
+            let peak = frame.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+            let target = if peak > self.config.threshold {
+                self.knee(peak) / peak
+            } else {
+                1.0
+            };
+            if target < self.gain {
+                self.gain = target;
+            } else {
+                self.gain += (target - self.gain) * self.release_coeff;
+            }
+            if self.gain < 1.0 {
+                for sample in frame.iter_mut() {
+                    *sample *= self.gain;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(amplitude: f32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .flat_map(|i| {
+                let s = (i as f32 * 0.03).sin() * amplitude;
+                [s, s]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn loud_input_is_limited_below_full_scale() {
+        let mut limiter = Limiter::new(LimiterConfig::default(), 48_000.0);
+        let mut samples = sine(2.0, 4096);
+
+        limiter.process(&mut samples, 2);
+
+        let peak = samples.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+        assert!(peak < 1.0, "peak {peak} should stay below full scale");
+        assert!(peak > LimiterConfig::default().threshold);
+    }
+
+    #[test]
+    fn quiet_input_passes_unchanged() {
+        let mut limiter = Limiter::new(LimiterConfig::default(), 48_000.0);
+        let original = sine(0.5, 4096);
+        let mut samples = original.clone();
+
+        limiter.process(&mut samples, 2);
+
+        assert_eq!(samples, original);
+    }
+}
@@ -0,0 +1,124 @@
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use hound::{SampleFormat, WavSpec, WavWriter};
+use rtrb::{Consumer, Producer, RingBuffer};
+
+/// How often the writer thread drains the ring buffer.
+const DRAIN_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Seconds of audio the ring buffer holds before the output callback starts dropping blocks.
+const BUFFER_SECONDS: usize = 2;
+
+/// Tees the mixed master output to a 32-bit float WAV file for offline inspection.
+///
+/// The output callback only pushes samples into a ring buffer through the `CaptureTap`
+/// returned by `create`; a separate thread drains it and does the file I/O.
+pub(crate) struct MixCapture {
+    stop: Arc<AtomicBool>,
+    writer_thread: JoinHandle<hound::Result<()>>,
+}
+
+/// The output callback's end of a capture.
+pub(crate) struct CaptureTap {
+    producer: Producer<f32>,
+}
+
+impl MixCapture {
+    pub(crate) fn create(
+        path: &Path,
+        channels: u16,
+        sample_rate: u32,
+    ) -> hound::Result<(Self, CaptureTap)> {
+        let spec = WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 32,
+            sample_format: SampleFormat::Float,
+        };
+        let writer = WavWriter::create(path, spec)?;
+        let capacity = (sample_rate as usize * channels as usize * BUFFER_SECONDS).max(1);
+        let (producer, consumer) = RingBuffer::new(capacity);
+        let stop = Arc::new(AtomicBool::new(false));
+        let writer_thread = {
+            let stop = stop.clone();
+            thread::Builder::new()
+                .name("audio-capture".to_string())
+                .spawn(move || Self::run_writer(writer, consumer, &stop))?
+        };
+        Ok((
+            Self {
+                stop,
+                writer_thread,
+            },
+            CaptureTap { producer },
+        ))
+    }
+
+    fn run_writer(
+        mut writer: WavWriter<BufWriter<File>>,
+        mut consumer: Consumer<f32>,
+        stop: &AtomicBool,
+    ) -> hound::Result<()> {
+        loop {
+            // Checked before draining, so everything pushed before the stop is written
+            let stopping = stop.load(Ordering::Acquire);
+            Self::drain(&mut writer, &mut consumer)?;
+            if stopping {
+                break;
+            }
+            thread::sleep(DRAIN_INTERVAL);
+        }
+        writer.finalize()
+    }
+
+    fn drain(
+        writer: &mut WavWriter<BufWriter<File>>,
+        consumer: &mut Consumer<f32>,
+    ) -> hound::Result<()> {
+        let Ok(chunk) = consumer.read_chunk(consumer.slots()) else {
+            return Ok(());
+        };
+        let (first, second) = chunk.as_slices();
+        for &sample in first.iter().chain(second) {
+            writer.write_sample(sample)?;
+        }
+        chunk.commit_all();
+        Ok(())
+    }
+
+    /// Stops the writer thread once it has written everything pushed so far, then flushes the
+    /// header and remaining samples to disk.
+    pub(crate) fn finish(self) -> hound::Result<()> {
+        self.stop.store(true, Ordering::Release);
+        self.writer_thread.join().unwrap_or_else(|_| {
+            Err(hound::Error::IoError(std::io::Error::other(
+                "audio capture thread panicked",
+            )))
+        })
+    }
+}
+
+impl CaptureTap {
+    /// Queues interleaved samples exactly as they were sent to the output device. Never
+    /// blocks: if the writer thread has fallen behind, the whole block is dropped.
+    pub(crate) fn write(&mut self, samples: &[f32]) {
+        if let Ok(chunk) = self.producer.write_chunk_uninit(samples.len()) {
+            chunk.fill_from_iter(samples.iter().copied());
+        }
+    }
+
+    /// Whether the capture has finished, or its writer thread gave up after an error.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.producer.is_abandoned()
+    }
+}
@@ -1,6 +1,8 @@
 pub(crate) mod audio_command_queue_system;
 pub mod audio_control;
 pub(crate) mod audio_mixer;
+pub mod limiter;
+pub(crate) mod mix_capture;
 pub(crate) mod simple_phys_audio_system;
 pub(crate) mod spatial_audio_system;
 pub(crate) mod track;
@@ -1,33 +1,108 @@
-use bevy_ecs::prelude::*;
+use std::collections::HashSet;
+
+use bevy_ecs::{prelude::*, system::SystemParam};
+use glam::Vec3;
 
 use crate::{
     TransformComponent,
+    assets::mesh_resource::MeshResource,
     audio::audio_control::AudioControl,
     components::{
         audio_source_component::AudioSourceComponent,
-        single_audio_listener_component::SingleAudioListenerComponent,
+        collider_component::{ConvexCollider, MeshCollider},
+        physics_component::{PhysicsComponent, PhysicsType},
+        single_audio_listener_component::{ActiveListener, SingleAudioListenerComponent},
+    },
+    physics::{
+        collision_system::{segment_intersects_convex, segment_intersects_mesh},
+        physics_resource::PhysicsResource,
     },
+    render::render_body_resource::RenderBodyResource,
 };
 
+/// Static geometry that can block the line between a source and the listener.
+#[derive(SystemParam)]
+pub struct OcclusionGeometry<'w, 's> {
+    physics: Res<'w, PhysicsResource>,
+    bodies: Query<
+        'w,
+        's,
+        (
+            &'static PhysicsComponent,
+            &'static TransformComponent,
+            Option<&'static ConvexCollider>,
+            Option<&'static MeshCollider>,
+        ),
+    >,
+    render_bodies: Res<'w, RenderBodyResource>,
+    meshes: Res<'w, MeshResource>,
+}
+
+impl OcclusionGeometry<'_, '_> {
+    /// Whether a static collider other than `ignore` crosses the segment from `start` to
+    /// `end`. Broadphase hits are confirmed against the collider's actual shape.
+    fn blocks(&self, start: Vec3, end: Vec3, ignore: [Entity; 2]) -> bool {
+        self.physics
+            .query_segment(start, end)
+            .into_iter()
+            .filter(|hit| !ignore.contains(hit))
+            .any(|hit| {
+                let Ok((body, transform, convex, mesh)) = self.bodies.get(hit) else {
+                    return false;
+                };
+                if !matches!(body.physics_type, PhysicsType::Static) {
+                    return false;
+                }
+                if let Some(convex) = convex {
+                    segment_intersects_convex(start, end, convex, transform)
+                } else if let Some(mesh) = mesh {
+                    segment_intersects_mesh(
+                        start,
+                        end,
+                        mesh,
+                        transform,
+                        &self.render_bodies,
+                        &self.meshes.read(),
+                    )
+                } else {
+                    false
+                }
+            })
+    }
+}
+
 pub struct SpatialAudioSystem;
 
 /// This system is responsible for updating the position and rotation of the audio listener
 /// and the position of audio source entities in the world.
 impl SpatialAudioSystem {
-    /// This currently only supports having a single listener
+    /// Sends the listener transform to the mixer when it moves or when the active listener
+    /// changes. Uses `ActiveListener` if set, otherwise the single `SingleAudioListenerComponent`.
     pub fn update_listener_position(
-        query: Query<
-            (Entity, &TransformComponent, &SingleAudioListenerComponent),
-            Changed<TransformComponent>,
-        >,
+        active_listener: Res<ActiveListener>,
+        transforms: Query<Ref<TransformComponent>>,
+        single_listeners: Query<Entity, With<SingleAudioListenerComponent>>,
         mut audio_command_queue: ResMut<AudioControl>,
+        mut last_listener: Local<Option<Entity>>,
     ) {
-        if query.iter().count() > 1 {
-            log::error!(
-                "Multiple entities with SingleAudioListenerComponent found. Only the first one will be used as the audio listener."
-            );
-        }
-        if let Some((_, transform, _)) = query.iter().nth(0) {
+        let listener = match active_listener.get() {
+            Some(entity) => Some(entity),
+            None => {
+                if single_listeners.iter().count() > 1 {
+                    log::error!(
+                        "Multiple entities with SingleAudioListenerComponent found. Only the first one will be used as the audio listener."
+                    );
+                }
+                single_listeners.iter().next()
+            }
+        };
+        let switched = *last_listener != listener;
+        *last_listener = listener;
+
+        let Some(transform) = listener.and_then(|entity| transforms.get(entity).ok()) else {
+            return;
+        };
+        if switched || transform.is_changed() {
             audio_command_queue.update_listener_info(transform.position, transform.rotation);
         }
     }
@@ -44,6 +119,47 @@ impl SpatialAudioSystem {
         }
     }
 
+    /// Muffles occludable sources whose line to the listener crosses a static collider.
+    /// Only changes in occlusion are sent to the mixer, except for newly added sources, whose
+    /// voices start out unoccluded.
+    pub fn update_occlusion(
+        active_listener: Res<ActiveListener>,
+        single_listeners: Query<Entity, With<SingleAudioListenerComponent>>,
+        transforms: Query<&TransformComponent>,
+        sources: Query<(Entity, &TransformComponent, Ref<AudioSourceComponent>)>,
+        geometry: OcclusionGeometry,
+        mut audio_control: ResMut<AudioControl>,
+        mut occluded: Local<HashSet<Entity>>,
+    ) {
+        let listener = active_listener
+            .get()
+            .or_else(|| single_listeners.iter().next());
+        let Some((listener, listener_transform)) =
+            listener.and_then(|entity| Some((entity, transforms.get(entity).ok()?)))
+        else {
+            return;
+        };
+
+        for (entity, transform, source) in sources.iter() {
+            // Sources that stop being occludable are cleared like any other unblocked source
+            let blocked = source.occludable
+                && geometry.blocks(
+                    transform.position,
+                    listener_transform.position,
+                    [entity, listener],
+                );
+            let changed = if blocked {
+                occluded.insert(entity)
+            } else {
+                occluded.remove(&entity)
+            };
+            if changed || (blocked && source.is_added()) {
+                audio_control.set_voice_occlusion(entity, blocked);
+            }
+        }
+        occluded.retain(|entity| sources.contains(*entity));
+    }
+
     pub fn remove_deleted_sources(
         mut removed: RemovedComponents<AudioSourceComponent>,
         mut audio_control: ResMut<AudioControl>,
@@ -53,3 +169,212 @@ impl SpatialAudioSystem {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        audio::audio_control::AudioCommand,
+        components::collider_component::{Collider, CollisionLayer},
+    };
+
+    fn last_listener_position(world: &World) -> Option<Vec3> {
+        world
+            .resource::<AudioControl>()
+            .queue()
+            .iter()
+            .rev()
+            .find_map(|command| match command {
+                AudioCommand::UpdateListenerInfo { info } => Some(info.0),
+                _ => None,
+            })
+    }
+
+    fn spawn_at(world: &mut World, position: Vec3) -> Entity {
+        world
+            .spawn(TransformComponent {
+                position,
+                ..Default::default()
+            })
+            .id()
+    }
+
+    #[test]
+    fn switching_active_listener_changes_reference_position() {
+        let mut world = World::new();
+        world.insert_resource(AudioControl::default());
+        world.insert_resource(ActiveListener::default());
+
+        let fallback = spawn_at(&mut world, Vec3::new(1.0, 0.0, 0.0));
+        world
+            .entity_mut(fallback)
+            .insert(SingleAudioListenerComponent);
+        let first = spawn_at(&mut world, Vec3::new(0.0, 5.0, 0.0));
+        let second = spawn_at(&mut world, Vec3::new(0.0, 0.0, -8.0));
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(SpatialAudioSystem::update_listener_position);
+
+        schedule.run(&mut world);
+        assert_eq!(
+            last_listener_position(&world),
+            Some(Vec3::new(1.0, 0.0, 0.0))
+        );
+
+        world.resource_mut::<AudioControl>().clear();
+        world.resource_mut::<ActiveListener>().set(first);
+        schedule.run(&mut world);
+        assert_eq!(
+            last_listener_position(&world),
+            Some(Vec3::new(0.0, 5.0, 0.0))
+        );
+
+        // A stationary listener is not resent every frame
+        world.clear_trackers();
+        world.resource_mut::<AudioControl>().clear();
+        schedule.run(&mut world);
+        assert_eq!(last_listener_position(&world), None);
+
+        world.resource_mut::<ActiveListener>().set(second);
+        schedule.run(&mut world);
+        assert_eq!(
+            last_listener_position(&world),
+            Some(Vec3::new(0.0, 0.0, -8.0))
+        );
+    }
+
+    fn static_body() -> PhysicsComponent {
+        PhysicsComponent {
+            physics_type: PhysicsType::Static,
+            mass: 0.0,
+            friction: 0.5,
+            rolling_friction: 0.0,
+            drag_coefficient: 0.0,
+            angular_drag_coefficient: 0.0,
+            restitution: 0.0,
+            local_inertia: glam::Mat3::ZERO,
+        }
+    }
+
+    fn occludable_source() -> AudioSourceComponent {
+        AudioSourceComponent {
+            sound: crate::SoundHandle::default(),
+            volume: 1.0,
+            pitch: 1.0,
+            looping: true,
+            occludable: true,
+        }
+    }
+
+    /// A world with a listener at the origin and a static collider registered with the
+    /// broadphase.
+    fn occlusion_world(collider: ConvexCollider, position: Vec3) -> World {
+        let mut world = World::new();
+        world.insert_resource(AudioControl::default());
+        world.insert_resource(ActiveListener::default());
+        world.insert_resource(RenderBodyResource::default());
+        world.insert_resource(MeshResource::default());
+
+        let transform = TransformComponent {
+            position,
+            ..Default::default()
+        };
+        let aabb =
+            crate::components::collider_component::Collider::aabb(&collider, &transform.to_mat4());
+        let blocker = world.spawn((static_body(), transform, collider)).id();
+        let mut physics = PhysicsResource::default();
+        physics.world_aabbs.insert(blocker, aabb);
+        physics.bake_broadphase();
+        world.insert_resource(physics);
+
+        let listener = spawn_at(&mut world, Vec3::ZERO);
+        world
+            .entity_mut(listener)
+            .insert(SingleAudioListenerComponent);
+        world
+    }
+
+    fn spawn_source(world: &mut World, position: Vec3) -> Entity {
+        world
+            .spawn((
+                TransformComponent {
+                    position,
+                    ..Default::default()
+                },
+                occludable_source(),
+            ))
+            .id()
+    }
+
+    fn occlusion_commands(world: &World) -> Vec<(Entity, bool)> {
+        world
+            .resource::<AudioControl>()
+            .queue()
+            .iter()
+            .filter_map(|command| match command {
+                AudioCommand::SetVoiceOcclusion { voice, occluded } => Some((*voice, *occluded)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn source_behind_static_wall_is_occluded() {
+        let mut world = occlusion_world(
+            ConvexCollider::cuboid(Vec3::new(4.0, 4.0, 0.2), CollisionLayer::Default),
+            Vec3::new(0.0, 0.0, 5.0),
+        );
+        let behind = spawn_source(&mut world, Vec3::new(0.0, 0.0, 10.0));
+        spawn_source(&mut world, Vec3::new(10.0, 0.0, 0.0));
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(SpatialAudioSystem::update_occlusion);
+        schedule.run(&mut world);
+        assert_eq!(occlusion_commands(&world), vec![(behind, true)]);
+
+        // Unchanged occlusion is not resent
+        world.clear_trackers();
+        world.resource_mut::<AudioControl>().clear();
+        schedule.run(&mut world);
+        assert!(occlusion_commands(&world).is_empty());
+    }
+
+    #[test]
+    fn line_through_the_bounds_but_past_the_shape_is_not_occluded() {
+        let mut world = occlusion_world(
+            ConvexCollider::sphere(1.0, CollisionLayer::Default),
+            Vec3::new(0.0, 0.0, 5.0),
+        );
+        // Crosses the corner of the sphere's bounding box, well outside the sphere
+        spawn_source(&mut world, Vec3::new(1.8, 1.8, 10.0));
+        let behind = spawn_source(&mut world, Vec3::new(0.0, 0.0, 10.0));
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(SpatialAudioSystem::update_occlusion);
+        schedule.run(&mut world);
+        assert_eq!(occlusion_commands(&world), vec![(behind, true)]);
+    }
+
+    #[test]
+    fn source_made_unoccludable_is_unmuffled() {
+        let mut world = occlusion_world(
+            ConvexCollider::cuboid(Vec3::new(4.0, 4.0, 0.2), CollisionLayer::Default),
+            Vec3::new(0.0, 0.0, 5.0),
+        );
+        let behind = spawn_source(&mut world, Vec3::new(0.0, 0.0, 10.0));
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(SpatialAudioSystem::update_occlusion);
+        schedule.run(&mut world);
+        assert_eq!(occlusion_commands(&world), vec![(behind, true)]);
+
+        world.clear_trackers();
+        world.resource_mut::<AudioControl>().clear();
+        world
+            .get_mut::<AudioSourceComponent>(behind)
+            .unwrap()
+            .occludable = false;
+        schedule.run(&mut world);
+        assert_eq!(occlusion_commands(&world), vec![(behind, false)]);
+    }
+}
@@ -9,13 +9,22 @@ const ITD_DELAY_BUFFER_SIZE: usize = 128;
 const PAN_SMOOTH_TIME_SECONDS: f32 = 0.05;
 const BACK_LPF_MIX_MULT: f32 = 0.8;
 const LPF_CUTOFF_HZ: f32 = 400.0;
+const OCCLUDED_GAIN: f32 = 0.4;
+const OCCLUDED_LPF_MIX: f32 = 0.7;
+/// Lowest playback rate a voice accepts, so zero or negative pitches can't stall it.
+const MIN_PITCH: f32 = 0.01;
 
 #[derive(Debug)]
 pub(crate) struct Voice {
     samples: Arc<[f32]>,
     sample_rate: f32,
     cursor: usize,
+    /// Fractional part of the playback position, between `cursor` and the next frame.
+    cursor_fraction: f32,
     volume: f32,
+    target_volume: f32,
+    /// Playback rate relative to the source; 2.0 plays an octave up in half the time.
+    pitch: f32,
     looping: bool,
     pub(crate) channels: u16,
     pub(crate) buffer: Vec<f32>,
@@ -26,6 +35,9 @@ pub(crate) struct Voice {
     lpf_left: LowPassFilter,
     lpf_right: LowPassFilter,
     pan_smoothed: f32,
+    /// How far the occlusion muffling is applied, from 0.0 (clear) to 1.0 (fully occluded).
+    occlusion: f32,
+    target_occlusion: f32,
 }
 
 #[derive(Debug)]
@@ -55,6 +67,47 @@ impl Voice {
         self.channels
     }
 
+    pub(crate) fn source(&self) -> Option<Entity> {
+        self.source
+    }
+
+    /// Marks the voice as blocked by geometry, which lowers its gain and muffles it. Like a
+    /// volume change, the switch is ramped across the next block to avoid clicks.
+    pub(crate) fn set_occluded(&mut self, occluded: bool) {
+        self.target_occlusion = if occluded { 1.0 } else { 0.0 };
+    }
+
+    /// Updates the gain and pitch of a playing voice. The gain change is ramped across the
+    /// next block to avoid clicks; the pitch applies from the next frame.
+    pub(crate) fn set_params(&mut self, volume: f32, pitch: f32) {
+        self.target_volume = volume;
+        self.pitch = pitch.max(MIN_PITCH);
+    }
+
+    /// Source sample of `channel` at the current fractional position, interpolated linearly
+    /// with the next frame.
+    fn sample_at(&self, channel: usize, total_frames: usize) -> f32 {
+        let channels = self.source_channels as usize;
+        let next = if self.cursor + 1 < total_frames {
+            self.cursor + 1
+        } else if self.looping {
+            0
+        } else {
+            self.cursor
+        };
+        let current = self.samples[self.cursor * channels + channel];
+        let following = self.samples[next * channels + channel];
+        current + (following - current) * self.cursor_fraction
+    }
+
+    /// Moves the playback position on by one output frame at the current pitch.
+    fn advance(&mut self) {
+        self.cursor_fraction += self.pitch;
+        let whole_frames = self.cursor_fraction as usize;
+        self.cursor += whole_frames;
+        self.cursor_fraction -= whole_frames as f32;
+    }
+
     pub(crate) fn new(
         samples: Arc<[f32]>,
         sample_rate: f32,
@@ -78,8 +131,11 @@ impl Voice {
         Self {
             samples,
             cursor: 0,
+            cursor_fraction: 0.0,
             sample_rate,
             volume,
+            target_volume: volume,
+            pitch: 1.0,
             looping,
             channels: 2, // We always output stereo from the voice, even if the source is mono. The mixer will handle downmixing if necessary.
             buffer: vec![0.0; required_buffer_size], // stereo output buffer
@@ -94,6 +150,8 @@ impl Voice {
             lpf_left: LowPassFilter { z: 0.0, alpha },
             lpf_right: LowPassFilter { z: 0.0, alpha },
             pan_smoothed: 0.0,
+            occlusion: 0.0,
+            target_occlusion: 0.0,
             location,
         }
     }
@@ -105,7 +163,10 @@ impl Voice {
         source_map: &HashMap<Entity, Vec3>,
     ) -> bool {
         let total_frames = self.samples.len() / self.source_channels as usize;
-        let frames_to_fill = (total_frames - self.cursor).min(required_frames);
+        let remaining_frames =
+            total_frames.saturating_sub(self.cursor) as f32 - self.cursor_fraction;
+        let frames_to_fill =
+            ((remaining_frames / self.pitch).ceil().max(0.0) as usize).min(required_frames);
 
         let mut location = self.location;
         // Simple pan based spatialization
@@ -166,23 +227,44 @@ impl Voice {
         let right_delay_samples = right_delay as usize;
         let right_interpolation_factor = right_delay - right_delay_samples as f32;
 
-        let combined_volume = self.volume * distance_attenuation;
+        // Ramp linearly towards the target volume over this block
+        let start_volume = self.volume;
+        let volume_step = if frames_to_fill > 0 {
+            (self.target_volume - start_volume) / frames_to_fill as f32
+        } else {
+            0.0
+        };
+        self.volume = self.target_volume;
+
+        // Occlusion is ramped the same way
+        let start_occlusion = self.occlusion;
+        let occlusion_step = if frames_to_fill > 0 {
+            (self.target_occlusion - start_occlusion) / frames_to_fill as f32
+        } else {
+            0.0
+        };
+        self.occlusion = self.target_occlusion;
+        let occlusion_at = |frame: usize| start_occlusion + occlusion_step * (frame + 1) as f32;
+        let occlusion_gain = |occlusion: f32| 1.0 + (OCCLUDED_GAIN - 1.0) * occlusion;
 
         // Head shadow effect
-        let behind_lpf_mix = BACK_LPF_MIX_MULT * back_strength;
+        let back_lpf_mix = BACK_LPF_MIX_MULT * back_strength;
         let left_itd_shadow = (left_delay / itd_range_f32).clamp(0.0, 1.0);
         let right_itd_shadow = (right_delay / itd_range_f32).clamp(0.0, 1.0);
-        let left_shadow_mix =
-            (left_itd_shadow + (1.0 - left_itd_shadow) * behind_lpf_mix).clamp(0.0, 1.0);
-        let right_shadow_mix =
-            (right_itd_shadow + (1.0 - right_itd_shadow) * behind_lpf_mix).clamp(0.0, 1.0);
+        let shadow_mix = |itd_shadow: f32, occlusion: f32| {
+            let behind_lpf_mix = back_lpf_mix.max(OCCLUDED_LPF_MIX * occlusion);
+            (itd_shadow + (1.0 - itd_shadow) * behind_lpf_mix).clamp(0.0, 1.0)
+        };
 
         // Match outside of the loop for a tiny performance boost
         match self.source_channels {
             1 => {
                 for frame in 0..frames_to_fill {
-                    let sample_idx = self.cursor * self.source_channels as usize;
-                    let mono = self.samples[sample_idx] * combined_volume;
+                    let occlusion = occlusion_at(frame);
+                    let combined_volume = (start_volume + volume_step * (frame + 1) as f32)
+                        * distance_attenuation
+                        * occlusion_gain(occlusion);
+                    let mono = self.sample_at(0, total_frames) * combined_volume;
 
                     // +++ ITD +++
                     self.itd_delay.buffer[self.itd_delay.write_idx] = mono;
@@ -219,6 +301,8 @@ impl Voice {
                     let right_filtered = self.lpf_right.process(right_sample);
 
                     // Blend continuously to avoid discontinuities when crossing center.
+                    let left_shadow_mix = shadow_mix(left_itd_shadow, occlusion);
+                    let right_shadow_mix = shadow_mix(right_itd_shadow, occlusion);
                     left_sample =
                         left_sample * (1.0 - left_shadow_mix) + left_filtered * left_shadow_mix;
                     right_sample =
@@ -229,21 +313,23 @@ impl Voice {
 
                     self.itd_delay.write_idx = (self.itd_delay.write_idx + 1) & self.itd_delay.mask;
 
-                    self.cursor += 1;
+                    self.advance();
                 }
             }
             2 => {
                 for frame in 0..frames_to_fill {
-                    let sample_idx = self.cursor * self.source_channels as usize;
+                    let combined_volume = (start_volume + volume_step * (frame + 1) as f32)
+                        * distance_attenuation
+                        * occlusion_gain(occlusion_at(frame));
                     // Stereo source, apply panning and distance attenuation to each channel
                     // but not ITD since the source is already stereo and that would really mess things up
                     // Stereo voices should ideally not be used with spatialization but we should still support it in some way?
-                    let left_sample = self.samples[sample_idx] * combined_volume;
-                    let right_sample = self.samples[sample_idx + 1] * combined_volume;
+                    let left_sample = self.sample_at(0, total_frames) * combined_volume;
+                    let right_sample = self.sample_at(1, total_frames) * combined_volume;
                     self.buffer[frame * 2] = left_sample * left_gain; // Left channel
                     self.buffer[frame * 2 + 1] = right_sample * right_gain; // Right channel
 
-                    self.cursor += 1;
+                    self.advance();
                 }
             }
             _ => {
@@ -263,7 +349,8 @@ impl Voice {
         }
         if self.cursor >= total_frames {
             if self.looping {
-                self.cursor = 0;
+                // Keeps the overshoot of a pitched voice, so the loop stays seamless
+                self.cursor -= total_frames;
             } else {
                 return false;
             }
@@ -271,3 +358,112 @@ impl Voice {
         self.looping || self.cursor < total_frames
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stereo_voice(volume: f32) -> Voice {
+        let samples: Arc<[f32]> = vec![1.0; 2 * 1024].into();
+        Voice::new(samples, 48_000.0, volume, true, None, None, 2, 2 * 64)
+    }
+
+    #[test]
+    fn set_params_ramps_playing_voice_to_new_gain() {
+        let source_map = HashMap::new();
+        let mut unchanged = stereo_voice(1.0);
+        let mut changed = stereo_voice(1.0);
+
+        unchanged.next_block(None, 64, &source_map);
+        changed.next_block(None, 64, &source_map);
+        assert_eq!(unchanged.buffer, changed.buffer);
+
+        changed.set_params(0.25, 1.0);
+
+        unchanged.next_block(None, 64, &source_map);
+        changed.next_block(None, 64, &source_map);
+        // The first frame of the ramp is still close to the old gain
+        assert!(changed.buffer[0] > 0.9 * unchanged.buffer[0]);
+        // The last frame reaches the new gain
+        let last = 63 * 2;
+        assert!((changed.buffer[last] - 0.25 * unchanged.buffer[last]).abs() < 1e-6);
+
+        unchanged.next_block(None, 64, &source_map);
+        changed.next_block(None, 64, &source_map);
+        assert!((changed.buffer[0] - 0.25 * unchanged.buffer[0]).abs() < 1e-6);
+    }
+
+    #[test]
+    fn pitch_changes_the_playback_rate() {
+        let source_map = HashMap::new();
+        // A mono ramp, so the output shows how far playback has moved through the source
+        let samples: Arc<[f32]> = (0..1024).map(|i| i as f32 / 1024.0).collect();
+        let mut normal = Voice::new(samples.clone(), 48_000.0, 1.0, false, None, None, 1, 128);
+        let mut raised = Voice::new(samples, 48_000.0, 1.0, false, None, None, 1, 128);
+        raised.set_params(1.0, 2.0);
+
+        normal.next_block(None, 64, &source_map);
+        raised.next_block(None, 64, &source_map);
+        assert_eq!(normal.cursor, 64);
+        assert_eq!(raised.cursor, 128);
+
+        // Left channel, read past the ITD delay of a centered voice
+        let step = |voice: &Voice| voice.buffer[2 * 20] - voice.buffer[2 * 19];
+        assert!((step(&raised) - 2.0 * step(&normal)).abs() < 1e-4);
+
+        // 1024 source frames at double rate last 512 output frames, or eight blocks
+        let mut blocks = 1;
+        loop {
+            blocks += 1;
+            if !raised.next_block(None, 64, &source_map) {
+                break;
+            }
+        }
+        assert_eq!(blocks, 8);
+    }
+
+    #[test]
+    fn occluded_voice_is_quieter_than_unoccluded() {
+        let source_map = HashMap::new();
+        let listener = (Vec3::ZERO, glam::Quat::IDENTITY);
+        let location = Some(Vec3::new(0.0, 0.0, -2.0));
+        let samples: Arc<[f32]> = vec![0.5; 1024].into();
+        let mut clear = Voice::new(samples.clone(), 48_000.0, 1.0, true, None, location, 1, 128);
+        let mut blocked = Voice::new(samples, 48_000.0, 1.0, true, None, location, 1, 128);
+        blocked.set_occluded(true);
+
+        for _ in 0..4 {
+            clear.next_block(Some(&listener), 64, &source_map);
+            blocked.next_block(Some(&listener), 64, &source_map);
+        }
+
+        let energy = |voice: &Voice| voice.buffer.iter().map(|s| s * s).sum::<f32>();
+        assert!(energy(&blocked) > 0.0);
+        assert!(energy(&blocked) < 0.5 * energy(&clear));
+    }
+
+    #[test]
+    fn occlusion_is_ramped_across_a_block() {
+        let source_map = HashMap::new();
+        let mut unchanged = stereo_voice(1.0);
+        let mut occluded = stereo_voice(1.0);
+        unchanged.next_block(None, 64, &source_map);
+        occluded.next_block(None, 64, &source_map);
+
+        occluded.set_occluded(true);
+        unchanged.next_block(None, 64, &source_map);
+        occluded.next_block(None, 64, &source_map);
+        // No jump at the start of the block, full occlusion at its end
+        assert!(occluded.buffer[0] > 0.95 * unchanged.buffer[0]);
+        let last = 63 * 2;
+        assert!((occluded.buffer[last] - OCCLUDED_GAIN * unchanged.buffer[last]).abs() < 1e-6);
+        // Consecutive frames change by at most one ramp step
+        let max_step = occluded
+            .buffer
+            .chunks(2)
+            .zip(occluded.buffer.chunks(2).skip(1))
+            .map(|(a, b)| (a[0] - b[0]).abs())
+            .fold(0.0, f32::max);
+        assert!(max_step < 0.02 * unchanged.buffer[0]);
+    }
+}
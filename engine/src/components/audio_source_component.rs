@@ -1,12 +1,54 @@
-use bevy_ecs::prelude::*;
+use bevy_ecs::{lifecycle::HookContext, prelude::*, world::DeferredWorld};
 
-use crate::{SoundHandle, TransformComponent};
+use crate::{SoundHandle, TransformComponent, assets::sound_resource::SoundResource};
 
+/// Holds a reference on its sound, so `SoundResource::unload` waits until the component is
+/// removed. The sound is fixed once created; insert a new component to play another one.
 #[derive(Component)]
 #[require(TransformComponent)]
+#[component(on_insert = acquire_sound, on_replace = release_sound)]
 pub struct AudioSourceComponent {
-    pub sound: SoundHandle,
+    pub(crate) sound: SoundHandle,
     pub volume: f32,
     pub pitch: f32,
     pub looping: bool,
+    /// Muffle this source when static geometry blocks the line to the listener.
+    /// Costs a segment query per source each frame, so it is off unless requested.
+    pub occludable: bool,
+}
+
+impl AudioSourceComponent {
+    /// A source for `sound` at full volume and normal pitch that plays once and isn't
+    /// occluded. The other fields can be changed freely afterwards.
+    pub fn new(sound: SoundHandle) -> Self {
+        Self {
+            sound,
+            volume: 1.0,
+            pitch: 1.0,
+            looping: false,
+            occludable: false,
+        }
+    }
+
+    pub fn sound(&self) -> SoundHandle {
+        self.sound
+    }
+}
+
+fn acquire_sound(world: DeferredWorld, context: HookContext) {
+    let Some(source) = world.get::<AudioSourceComponent>(context.entity) else {
+        return;
+    };
+    if let Some(sounds) = world.get_resource::<SoundResource>() {
+        sounds.write().acquire_sound(source.sound);
+    }
+}
+
+fn release_sound(world: DeferredWorld, context: HookContext) {
+    let Some(source) = world.get::<AudioSourceComponent>(context.entity) else {
+        return;
+    };
+    if let Some(sounds) = world.get_resource::<SoundResource>() {
+        sounds.write().release_sound(source.sound);
+    }
 }
@@ -1,10 +1,18 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
 use bevy_ecs::component::Component;
 use glam::{Mat4, Vec3};
 
 use crate::TransformComponent;
-use crate::assets::{handles::RenderBodyHandle, mesh::Aabb};
+use crate::assets::{
+    handles::RenderBodyHandle,
+    mesh::{Aabb, Mesh},
+};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CollisionLayer {
     Default,
     Player,
@@ -12,8 +20,17 @@ pub enum CollisionLayer {
     Environment,
 }
 
+impl CollisionLayer {
+    /// Bit used to represent this layer in a `u32` layer mask.
+    pub const fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
 const SUPPORT_EPSILON: f32 = 1e-6;
 const SUPPORT_DIRECTION_DEADZONE: f32 = SUPPORT_EPSILON * 16.0;
+/// Half-size used wherever a plane needs finite bounds (support points and AABBs).
+const PLANE_HALF_EXTENT: f32 = 10_000.0;
 
 #[derive(Debug, Clone)]
 pub struct Triangle {
@@ -30,52 +47,174 @@ pub struct BVHNode {
     pub triangles: Vec<Triangle>,
 }
 
+/// How `BVHNode` picks the split for each internal node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BvhSplit {
+    /// Split at the centroid median along the longest axis. Fast to build.
+    #[default]
+    Median,
+    /// Sweep every centroid-ordered partition on all three axes and keep the one with the
+    /// lowest surface-area cost. Slower to build, but cheaper to query on uneven meshes.
+    SurfaceAreaHeuristic,
+}
+
+/// Cancellation flag and progress counter shared with a `BVHNode::build_cancelable` call,
+/// typically running on another thread.
+#[derive(Debug, Default)]
+pub struct BvhBuildControl {
+    cancelled: AtomicBool,
+    placed_triangles: AtomicUsize,
+}
+
+impl BvhBuildControl {
+    /// Asks the build to stop at the next node.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Number of triangles placed into finished leaves so far.
+    pub fn placed_triangles(&self) -> usize {
+        self.placed_triangles.load(Ordering::Relaxed)
+    }
+}
+
 impl BVHNode {
     pub fn build(triangles: Vec<Triangle>, max_leaf_size: usize) -> Self {
-        let mut min = triangles[0].v0;
-        let mut max = triangles[0].v0;
-        for tri in &triangles {
-            min = min.min(tri.v0).min(tri.v1).min(tri.v2);
-            max = max.max(tri.v0).max(tri.v1).max(tri.v2);
+        Self::build_with_split(triangles, max_leaf_size, BvhSplit::Median)
+    }
+
+    pub fn build_with_split(
+        triangles: Vec<Triangle>,
+        max_leaf_size: usize,
+        split: BvhSplit,
+    ) -> Self {
+        Self::build_cancelable(triangles, max_leaf_size, split, &BvhBuildControl::default())
+            .expect("BVH build without a shared control cannot be cancelled")
+    }
+
+    /// Builds like `build_with_split`, reporting progress through `control` and checking it
+    /// for cancellation at every node. A cancelled build returns `None` and drops whatever
+    /// subtrees it had finished, so callers never see a partial tree.
+    pub fn build_cancelable(
+        triangles: Vec<Triangle>,
+        max_leaf_size: usize,
+        split: BvhSplit,
+        control: &BvhBuildControl,
+    ) -> Option<Self> {
+        if control.is_cancelled() {
+            return None;
         }
-        let aabb = Aabb { min, max };
+
+        let aabb = triangles_aabb(&triangles);
 
         if triangles.len() <= max_leaf_size {
-            return BVHNode {
+            control
+                .placed_triangles
+                .fetch_add(triangles.len(), Ordering::Relaxed);
+            return Some(BVHNode {
                 aabb,
                 left: None,
                 right: None,
                 triangles,
-            };
+            });
         }
 
-        let extent = max - min;
-        let axis = if extent.x > extent.y && extent.x > extent.z {
-            0
-        } else if extent.y > extent.z {
-            1
-        } else {
-            2
+        let (sorted, mid) = match split {
+            BvhSplit::Median => median_split(triangles, aabb),
+            BvhSplit::SurfaceAreaHeuristic => sah_split(triangles),
         };
 
-        let mut sorted = triangles.clone();
-        sorted.sort_by(|a, b| {
-            let ca = (a.v0 + a.v1 + a.v2) / 3.0;
-            let cb = (b.v0 + b.v1 + b.v2) / 3.0;
-            ca[axis].partial_cmp(&cb[axis]).unwrap()
-        });
-
-        let mid = sorted.len() / 2;
-        let left = BVHNode::build(sorted[..mid].to_vec(), max_leaf_size);
-        let right = BVHNode::build(sorted[mid..].to_vec(), max_leaf_size);
+        let left =
+            BVHNode::build_cancelable(sorted[..mid].to_vec(), max_leaf_size, split, control)?;
+        let right =
+            BVHNode::build_cancelable(sorted[mid..].to_vec(), max_leaf_size, split, control)?;
 
-        BVHNode {
+        Some(BVHNode {
             aabb,
             left: Some(Box::new(left)),
             right: Some(Box::new(right)),
             triangles: vec![],
+        })
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.triangles.len()
+            + self.left.as_ref().map_or(0, |n| n.triangle_count())
+            + self.right.as_ref().map_or(0, |n| n.triangle_count())
+    }
+}
+
+fn triangles_aabb(triangles: &[Triangle]) -> Aabb {
+    let mut min = triangles[0].v0;
+    let mut max = triangles[0].v0;
+    for tri in triangles {
+        min = min.min(tri.v0).min(tri.v1).min(tri.v2);
+        max = max.max(tri.v0).max(tri.v1).max(tri.v2);
+    }
+    Aabb { min, max }
+}
+
+fn triangle_centroid(tri: &Triangle) -> Vec3 {
+    (tri.v0 + tri.v1 + tri.v2) / 3.0
+}
+
+fn sort_by_centroid(triangles: &mut [Triangle], axis: usize) {
+    triangles.sort_by(|a, b| {
+        triangle_centroid(a)[axis]
+            .partial_cmp(&triangle_centroid(b)[axis])
+            .unwrap()
+    });
+}
+
+fn median_split(mut triangles: Vec<Triangle>, aabb: Aabb) -> (Vec<Triangle>, usize) {
+    let extent = aabb.max - aabb.min;
+    let axis = if extent.x > extent.y && extent.x > extent.z {
+        0
+    } else if extent.y > extent.z {
+        1
+    } else {
+        2
+    };
+
+    sort_by_centroid(&mut triangles, axis);
+    let mid = triangles.len() / 2;
+    (triangles, mid)
+}
+
+fn sah_split(triangles: Vec<Triangle>) -> (Vec<Triangle>, usize) {
+    let count = triangles.len();
+    let mut best: Option<(f32, usize, usize)> = None;
+
+    for axis in 0..3 {
+        let mut sorted = triangles.clone();
+        sort_by_centroid(&mut sorted, axis);
+
+        // suffix_area[i] is the area of the bounds of sorted[i..]
+        let mut suffix_area = vec![0.0; count];
+        let mut bounds = triangles_aabb(&sorted[count - 1..]);
+        for i in (0..count).rev() {
+            bounds = bounds.union(&triangles_aabb(&sorted[i..=i]));
+            suffix_area[i] = bounds.area();
+        }
+
+        let mut prefix = triangles_aabb(&sorted[..1]);
+        for mid in 1..count {
+            let cost = mid as f32 * prefix.area() + (count - mid) as f32 * suffix_area[mid];
+            if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                best = Some((cost, axis, mid));
+            }
+            prefix = prefix.union(&triangles_aabb(&sorted[mid..=mid]));
         }
     }
+
+    let (_, axis, mid) = best.expect("SAH split needs at least two triangles");
+    let mut sorted = triangles;
+    sort_by_centroid(&mut sorted, axis);
+    (sorted, mid)
 }
 
 pub trait Collider {
@@ -107,6 +246,13 @@ pub enum ConvexShape {
         length: f32,
         radius: f32,
     },
+    /// Half-space of all local points `p` with `p.dot(normal) <= offset`.
+    /// Contacts against spheres and cuboids are computed directly; other shapes see
+    /// it as a very large slab through GJK.
+    Plane {
+        normal: Vec3,
+        offset: f32,
+    },
 }
 
 #[derive(Component, Debug, Clone, Copy)]
@@ -114,6 +260,9 @@ pub enum ConvexShape {
 pub struct ConvexCollider {
     pub shape: ConvexShape,
     pub layer: CollisionLayer,
+    /// World-space margin added around the shape for support points and AABBs.
+    /// A small skin makes contacts start slightly before the surfaces touch, which reduces tunneling.
+    pub skin_width: f32,
 }
 
 impl ConvexCollider {
@@ -125,6 +274,7 @@ impl ConvexCollider {
                 height: size.z,
             },
             layer,
+            skin_width: 0.0,
         }
     }
 
@@ -141,6 +291,7 @@ impl ConvexCollider {
         Self {
             shape: ConvexShape::Sphere { radius },
             layer,
+            skin_width: 0.0,
         }
     }
 
@@ -148,6 +299,18 @@ impl ConvexCollider {
         Self {
             shape: ConvexShape::Egg { length, radius },
             layer,
+            skin_width: 0.0,
+        }
+    }
+
+    pub fn plane(normal: Vec3, offset: f32, layer: CollisionLayer) -> Self {
+        Self {
+            shape: ConvexShape::Plane {
+                normal: normal.try_normalize().unwrap_or(Vec3::Y),
+                offset,
+            },
+            layer,
+            skin_width: 0.0,
         }
     }
 
@@ -155,6 +318,7 @@ impl ConvexCollider {
         Self {
             shape: ConvexShape::Triangle { v0, v1, v2 },
             layer,
+            skin_width: 0.0,
         }
     }
 
@@ -173,6 +337,7 @@ impl ConvexCollider {
                 half_thickness: half_thickness.max(1e-5),
             },
             layer,
+            skin_width: 0.0,
         }
     }
 
@@ -182,6 +347,11 @@ impl ConvexCollider {
         Self::sphere(radius, layer)
     }
 
+    pub fn with_skin_width(mut self, skin_width: f32) -> Self {
+        self.skin_width = skin_width.max(0.0);
+        self
+    }
+
     pub fn as_cuboid(&self) -> Option<(f32, f32, f32)> {
         match self.shape {
             ConvexShape::Cuboid {
@@ -200,6 +370,13 @@ impl ConvexCollider {
         }
     }
 
+    pub fn as_plane(&self) -> Option<(Vec3, f32)> {
+        match self.shape {
+            ConvexShape::Plane { normal, offset } => Some((normal, offset)),
+            _ => None,
+        }
+    }
+
     pub fn support(&self, transform: Mat4, dir_world: Vec3) -> Vec3 {
         let mut local_dir = if dir_world.length_squared() <= SUPPORT_EPSILON {
             Vec3::ZERO
@@ -312,15 +489,32 @@ impl ConvexCollider {
                     local_point
                 }
             }
+            ConvexShape::Plane { normal, offset } => {
+                let (tangent, bitangent) = normal.any_orthonormal_pair();
+                let depth = if local_dir.dot(normal) >= 0.0 {
+                    0.0
+                } else {
+                    PLANE_HALF_EXTENT
+                };
+                normal * (offset - depth)
+                    + tangent * PLANE_HALF_EXTENT.copysign(local_dir.dot(tangent))
+                    + bitangent * PLANE_HALF_EXTENT.copysign(local_dir.dot(bitangent))
+            }
         };
 
-        transform.transform_point3(local_point)
+        let skin = if self.skin_width > 0.0 {
+            dir_world.normalize_or_zero() * self.skin_width
+        } else {
+            Vec3::ZERO
+        };
+
+        transform.transform_point3(local_point) + skin
     }
 }
 
 impl Collider for ConvexCollider {
     fn aabb(&self, transform: &Mat4) -> Aabb {
-        match self.shape {
+        let aabb = match self.shape {
             ConvexShape::Cuboid {
                 length,
                 width,
@@ -383,15 +577,31 @@ impl Collider for ConvexCollider {
                 };
                 transform_aabb(local_aabb, transform)
             }
+            ConvexShape::Plane { normal, offset } => {
+                // The plane reaches past any scene, so its bounds are simply very large.
+                let center = transform.transform_point3(normal * offset);
+                Aabb {
+                    min: center - Vec3::splat(PLANE_HALF_EXTENT),
+                    max: center + Vec3::splat(PLANE_HALF_EXTENT),
+                }
+            }
+        };
+
+        Aabb {
+            min: aabb.min - Vec3::splat(self.skin_width),
+            max: aabb.max + Vec3::splat(self.skin_width),
         }
     }
 }
 
-#[derive(Component, Clone, Copy)]
+#[derive(Component, Clone)]
 #[require(TransformComponent)]
 pub struct MeshCollider {
     pub render_body_id: RenderBodyHandle,
     pub layer: CollisionLayer,
+    /// Collision BVHs used instead of the meshes' own, one per render body part. Lets a
+    /// collider use simplified geometry without changing the shared meshes.
+    pub part_bvhs: Option<Arc<[Option<BVHNode>]>>,
 }
 
 impl MeshCollider {
@@ -399,6 +609,16 @@ impl MeshCollider {
         Self {
             render_body_id,
             layer,
+            part_bvhs: None,
+        }
+    }
+
+    /// The BVH to collide against for part `part_index` of the render body, whose mesh is
+    /// `mesh`.
+    pub(crate) fn part_bvh<'a>(&'a self, part_index: usize, mesh: &'a Mesh) -> Option<&'a BVHNode> {
+        match &self.part_bvhs {
+            Some(part_bvhs) => part_bvhs.get(part_index)?.as_ref(),
+            None => mesh.bvh.as_ref(),
         }
     }
 }
@@ -494,6 +714,99 @@ pub(crate) fn closest_point_on_triangle(p: Vec3, tri: &Triangle) -> Vec3 {
 mod tests {
     use super::*;
 
+    fn count_visited_nodes(node: &BVHNode, query: &Aabb) -> usize {
+        if !node.aabb.intersects(query) {
+            return 1;
+        }
+        1 + node
+            .left
+            .as_ref()
+            .map_or(0, |n| count_visited_nodes(n, query))
+            + node
+                .right
+                .as_ref()
+                .map_or(0, |n| count_visited_nodes(n, query))
+    }
+
+    #[test]
+    fn sah_build_visits_fewer_nodes_on_skewed_mesh() {
+        let small_triangle = |origin: Vec3| Triangle {
+            v0: origin,
+            v1: origin + Vec3::new(0.05, 0.0, 0.0),
+            v2: origin + Vec3::new(0.0, 0.05, 0.0),
+        };
+        // A dense cluster near the origin plus a few far-flung outliers.
+        let mut triangles: Vec<Triangle> = (0..120)
+            .map(|i| {
+                small_triangle(Vec3::new(
+                    (i % 12) as f32 / 12.0,
+                    (i / 12) as f32 / 10.0,
+                    0.0,
+                ))
+            })
+            .collect();
+        triangles.extend((1..=8).map(|k| small_triangle(Vec3::new(10.0 * k as f32, 0.5, 0.0))));
+
+        let median = BVHNode::build(triangles.clone(), 4);
+        let sah = BVHNode::build_with_split(triangles, 4, BvhSplit::SurfaceAreaHeuristic);
+        assert_eq!(median.triangle_count(), sah.triangle_count());
+
+        let query = Aabb {
+            min: Vec3::new(49.9, 0.4, -0.1),
+            max: Vec3::new(50.2, 0.7, 0.1),
+        };
+        let median_visits = count_visited_nodes(&median, &query);
+        let sah_visits = count_visited_nodes(&sah, &query);
+        assert!(
+            sah_visits < median_visits,
+            "SAH visited {sah_visits} nodes, median visited {median_visits}"
+        );
+    }
+
+    #[test]
+    fn cancelable_build_reports_progress_and_stops_when_cancelled() {
+        let triangles: Vec<Triangle> = (0..256)
+            .map(|i| {
+                let origin = Vec3::new(i as f32, 0.0, 0.0);
+                Triangle {
+                    v0: origin,
+                    v1: origin + Vec3::X * 0.5,
+                    v2: origin + Vec3::Y * 0.5,
+                }
+            })
+            .collect();
+
+        let control = BvhBuildControl::default();
+        let tree = BVHNode::build_cancelable(triangles.clone(), 4, BvhSplit::Median, &control)
+            .expect("uncancelled build completes");
+        assert_eq!(tree.triangle_count(), 256);
+        assert_eq!(control.placed_triangles(), 256);
+
+        let control = BvhBuildControl::default();
+        control.cancel();
+        assert!(
+            BVHNode::build_cancelable(triangles.clone(), 4, BvhSplit::Median, &control).is_none()
+        );
+        assert_eq!(control.placed_triangles(), 0);
+
+        // Cancelling from another thread mid-build still yields no tree
+        let control = BvhBuildControl::default();
+        let result = std::thread::scope(|scope| {
+            let build = scope.spawn(|| {
+                BVHNode::build_cancelable(triangles, 1, BvhSplit::SurfaceAreaHeuristic, &control)
+            });
+            while control.placed_triangles() == 0 && !build.is_finished() {
+                std::thread::yield_now();
+            }
+            control.cancel();
+            build.join().unwrap()
+        });
+        if let Some(tree) = result {
+            // The build may finish before the flag is seen; it must then be complete.
+            assert_eq!(tree.triangle_count(), 256);
+        }
+    }
+
     fn assert_vec3_eq(actual: Vec3, expected: Vec3) {
         let diff = actual - expected;
         let eps = 1e-5;
@@ -576,4 +889,45 @@ mod tests {
 
         assert_vec3_eq(support, Vec3::new(1.0, 2.0, 3.0));
     }
+
+    #[test]
+    fn skin_width_enlarges_world_aabb() {
+        let transform = Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0));
+        let collider = ConvexCollider::cube(2.0, CollisionLayer::Default);
+        let skinned = collider.with_skin_width(0.05);
+
+        let aabb = collider.aabb(&transform);
+        let skinned_aabb = skinned.aabb(&transform);
+
+        assert_vec3_eq(skinned_aabb.min, aabb.min - Vec3::splat(0.05));
+        assert_vec3_eq(skinned_aabb.max, aabb.max + Vec3::splat(0.05));
+
+        let support = skinned.support(transform, Vec3::X);
+        assert_vec3_eq(support, Vec3::new(2.05, 3.0, 4.0));
+    }
+
+    #[test]
+    fn mesh_collider_prefers_its_own_part_bvhs() {
+        let triangle = |offset: f32| Triangle {
+            v0: Vec3::new(offset, 0.0, 0.0),
+            v1: Vec3::new(offset + 1.0, 0.0, 0.0),
+            v2: Vec3::new(offset, 1.0, 0.0),
+        };
+        let mesh = Mesh {
+            bvh: Some(BVHNode::build(vec![triangle(0.0), triangle(2.0)], 4)),
+            ..Default::default()
+        };
+
+        let shared = MeshCollider::new(RenderBodyHandle::default(), CollisionLayer::Default);
+        assert_eq!(shared.part_bvh(0, &mesh).unwrap().triangle_count(), 2);
+
+        let simplified = MeshCollider {
+            part_bvhs: Some(vec![Some(BVHNode::build(vec![triangle(0.0)], 4)), None].into()),
+            ..shared.clone()
+        };
+        assert_eq!(simplified.part_bvh(0, &mesh).unwrap().triangle_count(), 1);
+        assert!(simplified.part_bvh(1, &mesh).is_none());
+        // The shared mesh keeps its full BVH
+        assert_eq!(mesh.bvh.as_ref().unwrap().triangle_count(), 2);
+    }
 }
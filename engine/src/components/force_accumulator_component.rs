@@ -0,0 +1,24 @@
+use bevy_ecs::component::Component;
+use glam::Vec3;
+
+/// Forces and torques to apply to a dynamic body, in world space. `MovementSystem::update`
+/// turns them into velocity changes on every physics step of the frame, and they are cleared
+/// once per frame after the physics steps. Continuous forces such as thrust or wind are added
+/// again every frame; for a one-off push, change the velocity directly instead, since a frame
+/// can run no physics step at all.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct ForceAccumulatorComponent {
+    pub force: Vec3,
+    pub torque: Vec3,
+    /// Force from the `ForceFieldComponent`s the body is in. Recomputed every physics step by
+    /// `ForceFieldSystem::apply_force_fields`, so it is cleared after each step instead.
+    pub field_force: Vec3,
+}
+
+impl ForceAccumulatorComponent {
+    pub fn clear(&mut self) {
+        self.force = Vec3::ZERO;
+        self.torque = Vec3::ZERO;
+        self.field_force = Vec3::ZERO;
+    }
+}
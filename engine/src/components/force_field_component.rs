@@ -0,0 +1,23 @@
+use bevy_ecs::component::Component;
+use glam::Vec3;
+
+use crate::{TransformComponent, assets::mesh::Aabb};
+
+/// How a force field pushes the bodies inside it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldForce {
+    /// The same world-space force on every body, e.g. a fan or a water current.
+    Directional(Vec3),
+    /// A force of this magnitude pointing away from the field's center, e.g. an explosion.
+    /// Negative values pull bodies in.
+    Radial(f32),
+}
+
+/// A box-shaped region that applies a continuous force to the dynamic bodies inside it.
+/// `aabb` is relative to the entity's position, so the field moves with its entity.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[require(TransformComponent)]
+pub struct ForceFieldComponent {
+    pub aabb: Aabb,
+    pub force: FieldForce,
+}
@@ -0,0 +1,37 @@
+use bevy_ecs::component::Component;
+
+/// Tracks whether an entity is standing on something, and for how long it has not been.
+/// Game code can read `time_since_grounded` to allow jumps shortly after leaving a ledge.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct GroundedStateComponent {
+    pub is_grounded: bool,
+    pub time_since_grounded: f32,
+    /// Minimum dot product between a contact normal and the up direction for the contact to count as ground.
+    pub min_ground_normal_dot: f32,
+}
+
+impl Default for GroundedStateComponent {
+    fn default() -> Self {
+        Self {
+            is_grounded: false,
+            time_since_grounded: f32::INFINITY,
+            min_ground_normal_dot: 0.7,
+        }
+    }
+}
+
+impl GroundedStateComponent {
+    pub fn update(&mut self, grounded: bool, delta_time: f32) {
+        self.is_grounded = grounded;
+        if grounded {
+            self.time_since_grounded = 0.0;
+        } else {
+            self.time_since_grounded += delta_time;
+        }
+    }
+
+    /// True while grounded or within `grace_time` seconds of having left the ground.
+    pub fn is_within_coyote_time(&self, grace_time: f32) -> bool {
+        self.is_grounded || self.time_since_grounded <= grace_time
+    }
+}
@@ -0,0 +1,47 @@
+use bevy_ecs::component::Component;
+use glam::{BVec3, Vec3};
+
+use crate::VelocityComponent;
+
+/// Per-axis locks on a dynamic body's motion, in world space. Locked components of the
+/// velocity are zeroed after solving, so e.g. a player capsule with all rotation axes locked
+/// can be pushed around without toppling.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub struct LockedAxes {
+    pub translation: BVec3,
+    pub rotation: BVec3,
+}
+
+impl LockedAxes {
+    pub const ROTATION_LOCKED: Self = Self {
+        translation: BVec3::FALSE,
+        rotation: BVec3::TRUE,
+    };
+
+    pub fn apply(&self, velocity: &mut VelocityComponent) {
+        velocity.translational = Vec3::select(self.translation, Vec3::ZERO, velocity.translational);
+        velocity.angular = Vec3::select(self.rotation, Vec3::ZERO, velocity.angular);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_zeroes_only_locked_components() {
+        let locks = LockedAxes {
+            translation: BVec3::new(false, true, false),
+            rotation: BVec3::new(true, false, true),
+        };
+        let mut velocity = VelocityComponent {
+            translational: Vec3::new(1.0, 2.0, 3.0),
+            angular: Vec3::new(4.0, 5.0, 6.0),
+        };
+
+        locks.apply(&mut velocity);
+
+        assert_eq!(velocity.translational, Vec3::new(1.0, 0.0, 3.0));
+        assert_eq!(velocity.angular, Vec3::new(0.0, 5.0, 0.0));
+    }
+}
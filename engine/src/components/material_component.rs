@@ -2,6 +2,8 @@ use bevy_ecs::prelude::*;
 
 use crate::assets::handles::MaterialHandle;
 
+/// Draws every part of the entity's render body with this material instead of the materials
+/// baked into the render body.
 #[derive(Component, Debug, Clone, Copy)]
 pub struct MaterialComponent {
     pub material_id: MaterialHandle,
@@ -1,12 +1,18 @@
 pub mod audio_source_component;
 pub mod camera_component;
 pub mod collider_component;
+pub mod force_accumulator_component;
+pub mod force_field_component;
+pub mod grounded_state_component;
+pub mod locked_axes_component;
 pub mod material_component;
+pub mod name_component;
 pub mod physics_component;
 pub mod physics_event_listener_component;
 pub mod render_body_component;
 pub mod simple_on_hit_audio_component;
 pub mod single_audio_listener_component;
 pub mod sleep_component;
+pub mod surface_velocity_component;
 pub mod transform_component;
 pub mod velocity_component;
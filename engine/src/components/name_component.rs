@@ -0,0 +1,60 @@
+use bevy_ecs::{component::Component, entity::Entity, world::World};
+
+/// Human-readable identifier for debugging and scripting. Names need not be unique.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NameComponent(pub String);
+
+impl NameComponent {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    /// Returns an entity named `name`. If several share the name, which one is unspecified.
+    pub fn find(world: &World, name: &str) -> Option<Entity> {
+        let mut query = world.try_query::<(Entity, &NameComponent)>()?;
+        query
+            .iter(world)
+            .find(|(_, entity_name)| entity_name.0 == name)
+            .map(|(entity, _)| entity)
+    }
+
+    /// Returns every entity named `name`.
+    pub fn find_all(world: &World, name: &str) -> Vec<Entity> {
+        let Some(mut query) = world.try_query::<(Entity, &NameComponent)>() else {
+            return Vec::new();
+        };
+        query
+            .iter(world)
+            .filter(|(_, entity_name)| entity_name.0 == name)
+            .map(|(entity, _)| entity)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_entities_are_found_and_unknown_names_are_not() {
+        let mut world = World::new();
+        assert_eq!(NameComponent::find(&world, "crate"), None);
+
+        let crate_a = world.spawn(NameComponent::new("crate")).id();
+        let crate_b = world.spawn(NameComponent::new("crate")).id();
+        let door = world.spawn(NameComponent::new("door")).id();
+
+        assert_eq!(NameComponent::find(&world, "door"), Some(door));
+        let found = NameComponent::find(&world, "crate").unwrap();
+        assert!(found == crate_a || found == crate_b);
+
+        let mut crates = NameComponent::find_all(&world, "crate");
+        crates.sort();
+        let mut expected = vec![crate_a, crate_b];
+        expected.sort();
+        assert_eq!(crates, expected);
+
+        assert_eq!(NameComponent::find(&world, "barrel"), None);
+        assert!(NameComponent::find_all(&world, "barrel").is_empty());
+    }
+}
@@ -15,6 +15,9 @@ pub struct PhysicsComponent {
     pub physics_type: PhysicsType,
     pub mass: f32,
     pub friction: f32,
+    /// Resistance to rolling, as the lever arm (in meters) of the torque that opposes a body
+    /// rolling over a contact. Zero lets bodies roll until drag stops them.
+    pub rolling_friction: f32,
     pub drag_coefficient: f32,
     pub angular_drag_coefficient: f32,
     pub restitution: f32,
@@ -1,7 +1,33 @@
 use bevy_ecs::prelude::*;
 
+use crate::components::collider_component::CollisionLayer;
+
 #[derive(Component, Debug)]
-/// A marker component for entities that want to listen to physics events.
+/// A component for entities that want to listen to physics events.
 /// Entities with this component will receive `PhysicsEvent` events when they occur.
 /// This allows us to not have to trigger events for every entity in the world, only those that are interested.
-pub struct PhysicsEventListenerComponent;
+/// Events are only forwarded when the other collider's layer is part of `layer_mask`.
+pub struct PhysicsEventListenerComponent {
+    pub layer_mask: u32,
+}
+
+impl Default for PhysicsEventListenerComponent {
+    fn default() -> Self {
+        Self {
+            layer_mask: u32::MAX,
+        }
+    }
+}
+
+impl PhysicsEventListenerComponent {
+    /// Listen only to contacts with colliders on the given layers.
+    pub fn with_layers(layers: &[CollisionLayer]) -> Self {
+        Self {
+            layer_mask: layers.iter().fold(0, |mask, layer| mask | layer.bit()),
+        }
+    }
+
+    pub fn accepts(&self, layer: CollisionLayer) -> bool {
+        self.layer_mask & layer.bit() != 0
+    }
+}
@@ -1,10 +1,138 @@
-use bevy_ecs::prelude::*;
+use bevy_ecs::{lifecycle::HookContext, prelude::*, world::DeferredWorld};
 
-use crate::{RenderBodyHandle, TransformComponent};
+use crate::{
+    RenderBodyHandle, TransformComponent, assets::mesh_resource::MeshResource,
+    render::render_body_resource::RenderBodyResource,
+};
 
 #[derive(Component, Debug, Clone, Copy)]
 #[require(TransformComponent)]
+#[component(immutable, on_insert = acquire_render_body, on_replace = release_render_body)]
 /// Component that links an entity to its render body, which contains the necessary information for rendering.
+/// It holds a reference on the render body and its meshes, so unloading them waits until the
+/// component is removed. It is immutable; inserting a new one switches bodies and moves the
+/// reference with it.
 pub struct RenderBodyComponent {
     pub render_body_id: RenderBodyHandle,
 }
+
+fn acquire_render_body(world: DeferredWorld, context: HookContext) {
+    let Some(component) = world.get::<RenderBodyComponent>(context.entity) else {
+        return;
+    };
+    let Some(bodies) = world.get_resource::<RenderBodyResource>() else {
+        return;
+    };
+    let mesh_ids: Vec<_> = {
+        let mut bodies = bodies.write();
+        bodies.acquire_render_body(component.render_body_id);
+        bodies
+            .get_render_body(component.render_body_id)
+            .map(|body| body.parts.iter().map(|part| part.mesh_id).collect())
+            .unwrap_or_default()
+    };
+
+    if let Some(meshes) = world.get_resource::<MeshResource>() {
+        let mut meshes = meshes.write();
+        for mesh_id in mesh_ids {
+            meshes.acquire_mesh(mesh_id);
+        }
+    }
+}
+
+fn release_render_body(world: DeferredWorld, context: HookContext) {
+    let Some(component) = world.get::<RenderBodyComponent>(context.entity) else {
+        return;
+    };
+    let Some(bodies) = world.get_resource::<RenderBodyResource>() else {
+        return;
+    };
+    let mesh_ids: Vec<_> = {
+        let mut bodies = bodies.write();
+        // Read the parts first, since releasing the last reference may free the body
+        let mesh_ids = bodies
+            .get_render_body(component.render_body_id)
+            .map(|body| body.parts.iter().map(|part| part.mesh_id).collect())
+            .unwrap_or_default();
+        bodies.release_render_body(component.render_body_id);
+        mesh_ids
+    };
+
+    if let Some(meshes) = world.get_resource::<MeshResource>() {
+        let mut meshes = meshes.write();
+        for mesh_id in mesh_ids {
+            meshes.release_mesh(mesh_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Mat4;
+
+    use super::*;
+    use crate::{
+        MaterialHandle,
+        assets::mesh::Mesh,
+        render::render_body::{RenderBody, RenderBodyPart},
+    };
+
+    #[test]
+    fn unloading_a_referenced_mesh_waits_for_the_entity_to_despawn() {
+        let mut world = World::new();
+        let meshes = MeshResource::default();
+        let bodies = RenderBodyResource::default();
+        world.insert_resource(meshes.clone());
+        world.insert_resource(bodies.clone());
+
+        let mesh_id = meshes.write().add_mesh(Mesh::default());
+        let render_body_id =
+            bodies
+                .write()
+                .add_render_body(RenderBody::new(vec![RenderBodyPart {
+                    mesh_id,
+                    material_id: MaterialHandle::default(),
+                    local_transform: Mat4::IDENTITY,
+                }]));
+        let entity = world.spawn(RenderBodyComponent { render_body_id }).id();
+
+        assert!(meshes.unload(mesh_id));
+        assert!(bodies.unload(render_body_id));
+        assert!(meshes.read().get_mesh(mesh_id).is_some());
+        assert!(bodies.read().get_render_body(render_body_id).is_some());
+
+        world.despawn(entity);
+        assert!(meshes.read().get_mesh(mesh_id).is_none());
+        assert!(bodies.read().get_render_body(render_body_id).is_none());
+        assert_eq!(meshes.read().unloaded, vec![mesh_id]);
+    }
+
+    #[test]
+    fn inserting_another_render_body_moves_the_reference() {
+        let mut world = World::new();
+        let bodies = RenderBodyResource::default();
+        world.insert_resource(bodies.clone());
+
+        let (first, second) = {
+            let mut storage = bodies.write();
+            (
+                storage.add_render_body(RenderBody::new(Vec::new())),
+                storage.add_render_body(RenderBody::new(Vec::new())),
+            )
+        };
+        let entity = world
+            .spawn(RenderBodyComponent {
+                render_body_id: first,
+            })
+            .id();
+        assert!(bodies.unload(first));
+        assert!(bodies.read().get_render_body(first).is_some());
+
+        world.entity_mut(entity).insert(RenderBodyComponent {
+            render_body_id: second,
+        });
+        assert!(bodies.read().get_render_body(first).is_none());
+        assert!(bodies.unload(second));
+        assert!(bodies.read().get_render_body(second).is_some());
+    }
+}
@@ -2,3 +2,25 @@ use bevy_ecs::prelude::*;
 
 #[derive(Component, Debug, Default, Clone)]
 pub struct SingleAudioListenerComponent;
+
+/// The entity whose transform is used as the audio listener.
+///
+/// When unset, the entity with `SingleAudioListenerComponent` is used instead.
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct ActiveListener(pub Option<Entity>);
+
+impl ActiveListener {
+    pub fn get(&self) -> Option<Entity> {
+        self.0
+    }
+
+    /// Sets the active listener entity.
+    pub fn set(&mut self, entity: Entity) {
+        self.0 = Some(entity);
+    }
+
+    /// Falls back to the `SingleAudioListenerComponent` entity.
+    pub fn clear(&mut self) {
+        self.0 = None;
+    }
+}
@@ -0,0 +1,10 @@
+use bevy_ecs::component::Component;
+use glam::Vec3;
+
+/// Velocity of a collider's surface independent of the body's own motion, in world space,
+/// such as a conveyor belt or moving walkway. Friction drags touching bodies towards it. Only
+/// the part tangential to each contact has an effect.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct SurfaceVelocityComponent {
+    pub surface_velocity: Vec3,
+}
@@ -0,0 +1,215 @@
+use std::{collections::HashMap, str::FromStr};
+
+use bevy_ecs::{resource::Resource, world::World};
+use sdl2::keyboard::Keycode;
+
+use crate::{
+    Gravity, LogLevel, input::InputStateResource, physics::physics_resource::PhysicsResource,
+};
+
+type Setter = Box<dyn Fn(&mut World, &str) -> Result<(), String> + Send + Sync>;
+
+/// Runtime tweaking of engine and game resources through text commands such as
+/// `set gravity.z -9.8`. Pressing `toggle_key` opens the console; typed text then collects in
+/// `input` and Return runs it. Until the engine has a text overlay, command results are logged
+/// and kept in `output`.
+#[derive(Resource)]
+pub struct DevConsole {
+    pub toggle_key: Keycode,
+    pub open: bool,
+    pub input: String,
+    /// Results of executed commands, oldest first.
+    pub output: Vec<String>,
+    setters: HashMap<String, Setter>,
+}
+
+impl Default for DevConsole {
+    fn default() -> Self {
+        let mut console = Self {
+            toggle_key: Keycode::Backquote,
+            open: false,
+            input: String::new(),
+            output: Vec::new(),
+            setters: HashMap::new(),
+        };
+        console.register_engine_commands();
+        console
+    }
+}
+
+impl DevConsole {
+    /// Registers `set <name> <value>`. The value is parsed as `T` before `setter` runs, so a
+    /// malformed value reports an error instead of reaching the setter.
+    pub fn register<T, F>(&mut self, name: &str, setter: F)
+    where
+        T: FromStr,
+        F: Fn(&mut World, T) + Send + Sync + 'static,
+    {
+        let owned_name = name.to_string();
+        self.setters.insert(
+            owned_name.clone(),
+            Box::new(move |world, argument| {
+                let value = argument
+                    .parse::<T>()
+                    .map_err(|_| format!("invalid value '{argument}' for {owned_name}"))?;
+                setter(world, value);
+                Ok(())
+            }),
+        );
+    }
+
+    pub fn commands(&self) -> impl Iterator<Item = &str> {
+        self.setters.keys().map(String::as_str)
+    }
+
+    /// Runs one command line against `world`, which must hold the `DevConsole` resource.
+    pub fn execute(world: &mut World, line: &str) -> Result<(), String> {
+        world.resource_scope::<DevConsole, _>(|world, console| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                ["set", name, value] => {
+                    let setter = console
+                        .setters
+                        .get(*name)
+                        .ok_or_else(|| format!("unknown setting '{name}'"))?;
+                    setter(world, value)
+                }
+                [] => Ok(()),
+                _ => Err(format!("unrecognized command '{line}'")),
+            }
+        })
+    }
+
+    /// Opens and closes the console and, while open, edits and submits the input line from
+    /// this frame's text and key input.
+    pub fn update(world: &mut World) {
+        let Some(console) = world.get_resource::<DevConsole>() else {
+            return;
+        };
+        let toggle_key = console.toggle_key;
+        let (toggled, text, backspace, submit) = {
+            let input = world.resource::<InputStateResource>();
+            (
+                input.key_pressed(toggle_key),
+                input.text_input.clone(),
+                input.key_pressed(Keycode::Backspace),
+                input.key_pressed(Keycode::Return),
+            )
+        };
+
+        let mut console = world.resource_mut::<DevConsole>();
+        if toggled {
+            console.open = !console.open;
+            // The toggle key's own character arrives as text this frame
+            return;
+        }
+        if !console.open {
+            return;
+        }
+        console.input.push_str(&text);
+        if backspace {
+            console.input.pop();
+        }
+        if !submit {
+            return;
+        }
+
+        let line = std::mem::take(&mut console.input);
+        let result = match Self::execute(world, &line) {
+            Ok(()) => format!("> {line}"),
+            Err(err) => format!("> {line}: {err}"),
+        };
+        log::info!("{result}");
+        world.resource_mut::<DevConsole>().output.push(result);
+    }
+
+    fn register_engine_commands(&mut self) {
+        for (axis, name) in ["gravity.x", "gravity.y", "gravity.z"]
+            .into_iter()
+            .enumerate()
+        {
+            self.register(name, move |world, value: f32| {
+                let mut gravity = world.resource_mut::<Gravity>();
+                let mut vector = gravity.gravity_normal * gravity.gravity_magnitude;
+                vector[axis] = value;
+                gravity.set_vector(vector);
+            });
+        }
+        self.register("gravity.magnitude", |world, value: f32| {
+            world.resource_mut::<Gravity>().gravity_magnitude = value;
+        });
+        self.register("log.level", |world, value: log::LevelFilter| {
+            world.resource_mut::<LogLevel>().0 = value;
+        });
+        self.register("physics.ccd_speed_threshold", |world, value: f32| {
+            world.resource_mut::<PhysicsResource>().ccd_speed_threshold = value;
+        });
+        self.register("physics.convex_max_contacts", |world, value: usize| {
+            world
+                .resource_mut::<PhysicsResource>()
+                .contact_merge
+                .convex_max_contacts = value;
+        });
+        self.register("physics.mesh_max_contacts", |world, value: usize| {
+            world
+                .resource_mut::<PhysicsResource>()
+                .contact_merge
+                .mesh_max_contacts = value;
+        });
+        self.register(
+            "physics.restitution_velocity_threshold",
+            |world, value: f32| {
+                world
+                    .resource_mut::<PhysicsResource>()
+                    .restitution_velocity_threshold = value;
+            },
+        );
+        self.register("physics.swept_max_samples", |world, value: usize| {
+            world
+                .resource_mut::<PhysicsResource>()
+                .mesh_contact
+                .swept_max_samples = value;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Tunable(f32);
+
+    #[test]
+    fn registered_command_parses_its_argument_for_the_setter() {
+        let mut world = World::new();
+        world.insert_resource(Tunable::default());
+        let mut console = DevConsole::default();
+        console.register("tunable", |world, value: f32| {
+            world.resource_mut::<Tunable>().0 = value;
+        });
+        world.insert_resource(console);
+
+        DevConsole::execute(&mut world, "set tunable -9.8").unwrap();
+        assert_eq!(world.resource::<Tunable>().0, -9.8);
+
+        assert!(DevConsole::execute(&mut world, "set tunable fast").is_err());
+        assert!(DevConsole::execute(&mut world, "set missing 1").is_err());
+        assert_eq!(world.resource::<Tunable>().0, -9.8);
+    }
+
+    #[test]
+    fn gravity_axis_command_keeps_the_other_axes() {
+        let mut world = World::new();
+        world.insert_resource(Gravity::from_vector(Vec3::new(1.0, 0.0, -9.81)));
+        world.insert_resource(DevConsole::default());
+
+        DevConsole::execute(&mut world, "set gravity.z -3").unwrap();
+
+        let gravity = world.resource::<Gravity>();
+        let vector = gravity.gravity_normal * gravity.gravity_magnitude;
+        assert!((vector - Vec3::new(1.0, 0.0, -3.0)).length() < 1e-5);
+    }
+}
@@ -4,6 +4,7 @@
 use std::collections::HashSet;
 
 use bevy_ecs::resource::Resource;
+use glam::Vec2;
 use sdl2::keyboard::Keycode;
 
 #[derive(Debug, Clone, Copy, PartialEq, Hash, Eq)]
@@ -29,7 +30,36 @@ impl From<sdl2::mouse::MouseButton> for MouseButton {
     }
 }
 
-#[derive(Resource, Default)]
+/// What drives relative mouse mode (cursor hidden and locked to the window).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MouseCaptureMode {
+    /// Capture only changes through `InputStateResource::set_mouse_captured`.
+    #[default]
+    Manual,
+    /// Capture while the button is held.
+    HoldButton(MouseButton),
+    /// Capture while the key is held.
+    HoldKey(Keycode),
+    /// Flip capture each time the key is pressed.
+    ToggleKey(Keycode),
+}
+
+/// Default radial dead zone for analog sticks, as a fraction of full deflection.
+pub const DEFAULT_STICK_DEAD_ZONE: f32 = 0.15;
+
+/// Zeroes stick input whose magnitude is inside `dead_zone` and rescales the rest so the
+/// output ramps from zero at the dead zone edge to one at full deflection.
+pub fn apply_radial_dead_zone(raw: Vec2, dead_zone: f32) -> Vec2 {
+    let magnitude = raw.length();
+    if magnitude <= dead_zone || magnitude <= f32::EPSILON {
+        return Vec2::ZERO;
+    }
+    let dead_zone = dead_zone.clamp(0.0, 1.0 - f32::EPSILON);
+    let scaled = ((magnitude - dead_zone) / (1.0 - dead_zone)).min(1.0);
+    raw / magnitude * scaled
+}
+
+#[derive(Resource)]
 pub struct InputStateResource {
     pub(crate) current_keys: HashSet<Keycode>,
     pub(crate) previous_keys: HashSet<Keycode>,
@@ -38,6 +68,32 @@ pub struct InputStateResource {
     pub scroll_delta: f32,
     pub current_mouse_buttons: HashSet<MouseButton>,
     pub previous_mouse_buttons: HashSet<MouseButton>,
+
+    pub mouse_capture_mode: MouseCaptureMode,
+    mouse_captured: bool,
+
+    /// Text typed this frame, after keyboard layout and IME processing.
+    pub text_input: String,
+
+    /// Radial dead zone applied by `stick_axis`.
+    pub stick_dead_zone: f32,
+}
+
+impl Default for InputStateResource {
+    fn default() -> Self {
+        Self {
+            current_keys: HashSet::new(),
+            previous_keys: HashSet::new(),
+            mouse_delta: (0.0, 0.0),
+            scroll_delta: 0.0,
+            current_mouse_buttons: HashSet::new(),
+            previous_mouse_buttons: HashSet::new(),
+            mouse_capture_mode: MouseCaptureMode::default(),
+            mouse_captured: false,
+            text_input: String::new(),
+            stick_dead_zone: DEFAULT_STICK_DEAD_ZONE,
+        }
+    }
 }
 
 impl InputStateResource {
@@ -66,4 +122,150 @@ impl InputStateResource {
         !self.current_mouse_buttons.contains(&button)
             && self.previous_mouse_buttons.contains(&button)
     }
+
+    /// Maps a raw analog stick reading in [-1, 1] through the configured dead zone.
+    pub fn stick_axis(&self, raw: Vec2) -> Vec2 {
+        apply_radial_dead_zone(raw, self.stick_dead_zone)
+    }
+
+    /// Whether the engine currently has the mouse in relative mode.
+    pub fn mouse_captured(&self) -> bool {
+        self.mouse_captured
+    }
+
+    pub fn set_mouse_captured(&mut self, captured: bool) {
+        self.mouse_captured = captured;
+    }
+
+    /// Applies `mouse_capture_mode` to this frame's input. Called by the engine after
+    /// events are polled; the result is mirrored into SDL's relative mouse mode.
+    pub(crate) fn update_mouse_capture(&mut self) {
+        match self.mouse_capture_mode {
+            MouseCaptureMode::Manual => {}
+            MouseCaptureMode::HoldButton(button) => {
+                if self.mouse_button_pressed(button) {
+                    self.mouse_captured = true;
+                } else if self.mouse_button_released(button) {
+                    self.mouse_captured = false;
+                }
+            }
+            MouseCaptureMode::HoldKey(key) => {
+                if self.key_pressed(key) {
+                    self.mouse_captured = true;
+                } else if self.key_released(key) {
+                    self.mouse_captured = false;
+                }
+            }
+            MouseCaptureMode::ToggleKey(key) => {
+                if self.key_pressed(key) {
+                    self.mouse_captured = !self.mouse_captured;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn next_frame(input: &mut InputStateResource) {
+        input.previous_keys = input.current_keys.clone();
+        input.previous_mouse_buttons = input.current_mouse_buttons.clone();
+    }
+
+    #[test]
+    fn stick_inside_dead_zone_reads_zero() {
+        let input = InputStateResource::default();
+
+        assert_eq!(input.stick_axis(Vec2::new(0.1, 0.05)), Vec2::ZERO);
+        assert_eq!(input.stick_axis(Vec2::new(0.0, -0.15)), Vec2::ZERO);
+    }
+
+    #[test]
+    fn stick_outside_dead_zone_is_rescaled_from_zero() {
+        let input = InputStateResource {
+            stick_dead_zone: 0.2,
+            ..Default::default()
+        };
+
+        let just_outside = input.stick_axis(Vec2::new(0.21, 0.0));
+        assert!(just_outside.x > 0.0 && just_outside.x < 0.02);
+        assert_eq!(just_outside.y, 0.0);
+
+        let halfway = input.stick_axis(Vec2::new(0.0, -0.6));
+        assert!((halfway.y + 0.5).abs() < 1e-6);
+
+        let full = input.stick_axis(Vec2::new(1.0, 1.0));
+        assert!((full.length() - 1.0).abs() < 1e-6);
+        assert!((full.x - full.y).abs() < 1e-6, "direction is preserved");
+    }
+
+    #[test]
+    fn hold_key_captures_on_press_and_releases_on_key_up() {
+        let mut input = InputStateResource {
+            mouse_capture_mode: MouseCaptureMode::HoldKey(Keycode::LAlt),
+            ..Default::default()
+        };
+
+        input.current_keys.insert(Keycode::LAlt);
+        input.update_mouse_capture();
+        assert!(input.mouse_captured());
+
+        next_frame(&mut input);
+        input.update_mouse_capture();
+        assert!(input.mouse_captured(), "holding the key keeps capture");
+
+        next_frame(&mut input);
+        input.current_keys.remove(&Keycode::LAlt);
+        input.update_mouse_capture();
+        assert!(!input.mouse_captured());
+    }
+
+    #[test]
+    fn hold_button_tracks_right_click() {
+        let mut input = InputStateResource {
+            mouse_capture_mode: MouseCaptureMode::HoldButton(MouseButton::Right),
+            ..Default::default()
+        };
+
+        input.current_mouse_buttons.insert(MouseButton::Left);
+        input.update_mouse_capture();
+        assert!(!input.mouse_captured());
+
+        next_frame(&mut input);
+        input.current_mouse_buttons.insert(MouseButton::Right);
+        input.update_mouse_capture();
+        assert!(input.mouse_captured());
+
+        next_frame(&mut input);
+        input.current_mouse_buttons.remove(&MouseButton::Right);
+        input.update_mouse_capture();
+        assert!(!input.mouse_captured());
+    }
+
+    #[test]
+    fn toggle_key_flips_capture_on_each_press() {
+        let mut input = InputStateResource {
+            mouse_capture_mode: MouseCaptureMode::ToggleKey(Keycode::Tab),
+            ..Default::default()
+        };
+
+        input.current_keys.insert(Keycode::Tab);
+        input.update_mouse_capture();
+        assert!(input.mouse_captured());
+
+        next_frame(&mut input);
+        input.current_keys.remove(&Keycode::Tab);
+        input.update_mouse_capture();
+        assert!(
+            input.mouse_captured(),
+            "releasing a toggle key keeps capture"
+        );
+
+        next_frame(&mut input);
+        input.current_keys.insert(Keycode::Tab);
+        input.update_mouse_capture();
+        assert!(!input.mouse_captured());
+    }
 }
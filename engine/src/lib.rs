@@ -6,10 +6,13 @@ mod action_manager;
 pub mod assets;
 pub mod audio;
 pub mod components;
+mod dev_console;
 pub mod input;
+mod logging;
 pub mod physics;
 pub mod render;
 pub mod scene;
+mod system_sets;
 mod time_resource;
 mod utils;
 pub mod world_basis;
@@ -19,7 +22,7 @@ use std::{
     time::{Duration, Instant},
 };
 
-use bevy_ecs::prelude::*;
+use bevy_ecs::{prelude::*, system::ScheduleSystem};
 use glam::{Mat4, Vec3};
 use glow::HasContext;
 
@@ -37,6 +40,7 @@ use crate::{
     components::physics_component::PhysicsComponent,
     input::InputStateResource,
     physics::{
+        force_field_system::ForceFieldSystem, grounded_system::GroundedSystem,
         movement_system::MovementSystem, physics_event_dispatcher, physics_system::PhysicsSystem,
     },
     render::{
@@ -44,6 +48,7 @@ use crate::{
         render_queue::RenderQueue,
         render_system::RenderSystem,
         renderer::{CameraRenderData, RenderParams, Renderer},
+        screenshot::ScreenshotRequestResource,
     },
     scene::{
         scene::Scene, scene_changer_resource::SceneChangerResource, scene_services::SceneServices,
@@ -55,30 +60,48 @@ pub use physics::collision_system::CollisionSystem;
 pub use physics::gravity_resource::Gravity;
 
 pub use crate::assets::handles::{MaterialHandle, MeshHandle, RenderBodyHandle, SoundHandle};
-pub use crate::assets::mesh::Aabb;
+pub use crate::assets::material_resource::DefaultMaterial;
+pub use crate::assets::mesh::{Aabb, FrontFace};
 pub use crate::components::camera_component::{ActiveCamera, CameraComponent};
 pub use crate::components::collider_component::{
     CollisionLayer, ConvexCollider, ConvexShape, MeshCollider,
 };
+pub use crate::components::force_accumulator_component::ForceAccumulatorComponent;
+pub use crate::components::force_field_component::{FieldForce, ForceFieldComponent};
+pub use crate::components::grounded_state_component::GroundedStateComponent;
+pub use crate::components::locked_axes_component::LockedAxes;
 pub use crate::components::material_component::MaterialComponent;
+pub use crate::components::name_component::NameComponent;
 pub use crate::components::render_body_component::RenderBodyComponent;
 pub use crate::components::sleep_component::SleepComponent;
+pub use crate::components::surface_velocity_component::SurfaceVelocityComponent;
 pub use crate::components::transform_component::TransformComponent;
 pub use crate::components::velocity_component::VelocityComponent;
+pub use crate::dev_console::DevConsole;
 pub use crate::input::MouseButton;
-pub use crate::time_resource::TimeResource;
+pub use crate::logging::LogLevel;
+pub use crate::system_sets::EngineSet;
+pub use crate::time_resource::{FramePacing, FrameStats, TimeResource};
 pub use crate::world_basis::WorldBasis;
+
+/// Adds a game system to an engine schedule.
+type ScheduleHook = Box<dyn Fn(&mut Schedule)>;
+
 pub struct Engine {
     pub scene: Scene,
     _scene_services: SceneServices,
     physics_schedule: Schedule,
     frame_schedule: Schedule,
     cleanup_schedule: Schedule,
+    /// Game systems registered through `add_system_after`. They are re-added whenever the
+    /// engine schedules are rebuilt, e.g. on a scene switch.
+    custom_systems: Vec<(EngineSet, ScheduleHook)>,
     gl: Rc<glow::Context>,
     window: sdl2::video::Window,
     events_loop: sdl2::EventPump,
     renderer: Renderer,
     audio_mixer: AudioMixer,
+    frame_pacing: FramePacing,
     _gl_context: sdl2::video::GLContext,
 }
 
@@ -87,34 +110,112 @@ impl Engine {
         Scene::new(&self._scene_services)
     }
 
-    fn add_frame_schedule(&mut self) {
-        self.frame_schedule.add_systems(
+    pub fn frame_pacing(&self) -> FramePacing {
+        self.frame_pacing
+    }
+
+    /// Starts teeing the mixed audio output to a WAV file at `path`.
+    pub fn start_audio_capture(&mut self, path: impl AsRef<std::path::Path>) -> Result<(), String> {
+        self.audio_mixer
+            .start_capture(path.as_ref())
+            .map_err(|e| format!("Failed to start audio capture: {e}"))
+    }
+
+    /// Stops the audio capture started by `start_audio_capture` and finalizes the file.
+    pub fn stop_audio_capture(&mut self) -> Result<(), String> {
+        self.audio_mixer
+            .stop_capture()
+            .map_err(|e| format!("Failed to finish audio capture: {e}"))
+    }
+
+    /// Switches how frames are paced. `Vsync` enables the swap interval and skips the
+    /// manual sleep; the other modes disable it.
+    pub fn set_frame_pacing(&mut self, pacing: FramePacing) {
+        let interval = match pacing {
+            FramePacing::Vsync => sdl2::video::SwapInterval::VSync,
+            FramePacing::SleepTo(_) | FramePacing::Uncapped => sdl2::video::SwapInterval::Immediate,
+        };
+        if let Err(e) = self.window.subsystem().gl_set_swap_interval(interval) {
+            log::error!("Failed to set swap interval for {pacing:?}: {e}");
+        }
+        self.frame_pacing = pacing;
+    }
+
+    /// Runs `system` in the engine schedule that owns `label`, after every system in `label`
+    /// and before the set that follows it. For example, `EngineSet::Solve` places the system
+    /// after the contact solver has produced final velocities but before they are integrated.
+    /// The registration survives scene switches.
+    pub fn add_system_after<M: 'static>(
+        &mut self,
+        label: EngineSet,
+        system: impl IntoScheduleConfigs<ScheduleSystem, M> + Clone + 'static,
+    ) {
+        self.custom_systems.push((
+            label,
+            Box::new(move |schedule: &mut Schedule| {
+                add_system_after(schedule, label, system.clone())
+            }),
+        ));
+    }
+
+    fn add_frame_schedule(schedule: &mut Schedule) {
+        schedule.configure_sets((EngineSet::Render, EngineSet::Time, EngineSet::Audio).chain());
+        schedule.add_systems((
+            RenderSystem::build_render_queue.in_set(EngineSet::Render),
+            TimeResource::update_time_resource.in_set(EngineSet::Time),
             (
-                RenderSystem::build_render_queue,
-                TimeResource::update_time_resource,
                 AudioCommandQueueSystem::build_command_queue,
+                AudioCommandQueueSystem::update_changed_sources,
                 SpatialAudioSystem::update_listener_position,
                 SpatialAudioSystem::update_moved_sources,
+                SpatialAudioSystem::update_occlusion,
                 SpatialAudioSystem::remove_deleted_sources,
                 SimplePhysAudioSystem::on_hit_audio_system,
             )
-                .chain(),
-        );
+                .chain()
+                .in_set(EngineSet::Audio),
+        ));
     }
 
-    fn add_physics_schedule(&mut self) {
-        self.physics_schedule.add_systems(
+    fn add_physics_schedule(schedule: &mut Schedule) {
+        schedule.configure_sets(
+            (
+                EngineSet::Movement,
+                EngineSet::Collision,
+                EngineSet::Solve,
+                EngineSet::Integrate,
+                EngineSet::Events,
+            )
+                .chain(),
+        );
+        schedule.add_systems((
+            (ForceFieldSystem::apply_force_fields, MovementSystem::update)
+                .chain()
+                .in_set(EngineSet::Movement),
             (
-                MovementSystem::update,
                 CollisionSystem::update_world_aabb_cache,
                 CollisionSystem::update_world_dynamic_tree,
+                CollisionSystem::rebuild_degraded_broadphase,
                 CollisionSystem::generate_manifolds,
+            )
+                .chain()
+                .in_set(EngineSet::Collision),
+            (
+                PhysicsSystem::wake_touched_bodies,
                 PhysicsSystem::physics_solver,
-                PhysicsSystem::integrate_motion,
+                PhysicsSystem::damp_resting_spin,
+            )
+                .chain()
+                .in_set(EngineSet::Solve),
+            PhysicsSystem::integrate_motion.in_set(EngineSet::Integrate),
+            (
+                GroundedSystem::update_grounded_state,
                 physics_event_dispatcher::dispatch_physics_events,
+                physics_event_dispatcher::dispatch_settled_events,
             )
-                .chain(),
-        );
+                .chain()
+                .in_set(EngineSet::Events),
+        ));
     }
 
     fn add_cleanup_schedule(&mut self) {
@@ -123,19 +224,28 @@ impl Engine {
                 // RenderSystem::cleanup_render_queue,
                 AudioCommandQueueSystem::clear_command_queue,
                 CollisionSystem::cleanup_removed_entities,
+                MovementSystem::clear_force_accumulators,
+                MeshResource::install_finished_bvh_builds,
             )
                 .chain(),
         );
     }
 
     fn add_schedules(&mut self) {
-        self.add_frame_schedule();
-        self.add_physics_schedule();
+        Self::add_frame_schedule(&mut self.frame_schedule);
+        Self::add_physics_schedule(&mut self.physics_schedule);
         self.add_cleanup_schedule();
+        for (label, add) in &self.custom_systems {
+            if label.is_physics() {
+                add(&mut self.physics_schedule);
+            } else {
+                add(&mut self.frame_schedule);
+            }
+        }
     }
 
     pub fn new() -> Self {
-        env_logger::init();
+        logging::init();
         let (gl, window, events_loop, gl_context) = unsafe { Self::create_sdl2_context() };
         let gl = Rc::new(gl);
 
@@ -151,6 +261,11 @@ impl Engine {
             materials: MaterialResource::default(),
         };
         let scene = Scene::new(&scene_services);
+        let frame_pacing = scene
+            .world
+            .get_resource::<TimeResource>()
+            .map(|time| FramePacing::sleep_to_frame_duration(time.target_frame_duration()))
+            .unwrap_or_default();
         let physics_schedule = Schedule::default();
         let frame_schedule = Schedule::default();
         let cleanup_schedule = Schedule::default();
@@ -161,11 +276,13 @@ impl Engine {
             physics_schedule,
             frame_schedule,
             cleanup_schedule,
+            custom_systems: Vec::new(),
             gl,
             window,
             events_loop,
             renderer,
             audio_mixer,
+            frame_pacing,
             _gl_context: gl_context,
         }
     }
@@ -177,11 +294,11 @@ impl Engine {
                 self.gl.get_parameter_string(glow::SHADING_LANGUAGE_VERSION);
             let major_version = self.gl.get_parameter_i32(glow::MAJOR_VERSION);
             let minor_version = self.gl.get_parameter_i32(glow::MINOR_VERSION);
-            println!("OpenGL Version: {}", version);
-            println!("GLSL Version: {}", shading_language_version);
-            println!(
-                "OpenGL Major Version: {}. OpenGL Minor Version: {}",
-                major_version, minor_version
+            log::info!(target: "gl", "OpenGL version: {version}");
+            log::info!(target: "gl", "GLSL version: {shading_language_version}");
+            log::info!(
+                target: "gl",
+                "OpenGL major version: {major_version}. OpenGL minor version: {minor_version}"
             );
         }
 
@@ -199,7 +316,6 @@ impl Engine {
                 .get_resource::<TimeResource>()
                 .expect("TimeResource resource not found");
             let fixed_dt: Duration = time_resource.simulation_fixed_dt();
-            let frame_target: Duration = time_resource.target_frame_duration();
 
             {
                 let mut input_state = self
@@ -208,9 +324,20 @@ impl Engine {
                     .get_resource_mut::<InputStateResource>()
                     .expect("InputStateResource resource not found");
 
-                if !Self::handle_input(&mut input_state, &mut self.events_loop) {
+                let mut resized = None;
+                if !Self::handle_input(&mut input_state, &mut self.events_loop, &mut resized) {
                     break 'game;
                 }
+                input_state.update_mouse_capture();
+                let mouse = self.window.subsystem().sdl().mouse();
+                if mouse.relative_mouse_mode() != input_state.mouse_captured() {
+                    mouse.set_relative_mouse_mode(input_state.mouse_captured());
+                }
+                if let Some((width, height)) = resized {
+                    Self::apply_window_resize(&mut self.scene.world, width, height);
+                }
+                DevConsole::update(&mut self.scene.world);
+                LogLevel::update(&mut self.scene.world);
 
                 // Update things that should run only once per frame
                 self.frame_schedule.run(&mut self.scene.world);
@@ -235,6 +362,20 @@ impl Engine {
                         .expect("RenderQueue resource not found")
                         .instances,
                 );
+                self.renderer.release_unloaded(
+                    &mut self
+                        .scene
+                        .world
+                        .get_resource::<MeshResource>()
+                        .expect("MeshResource resource not found")
+                        .write(),
+                    &mut self
+                        .scene
+                        .world
+                        .get_resource::<TextureResource>()
+                        .expect("TextureResource resource not found")
+                        .write(),
+                );
                 {
                     let _timer = ScopeTimer::new("Render");
                     let mesh_resource = &self
@@ -275,9 +416,17 @@ impl Engine {
                 let now = Instant::now();
                 let frame_time = now - last_frame;
                 last_frame = now;
+                self.scene
+                    .world
+                    .resource_mut::<FrameStats>()
+                    .record(frame_time);
 
                 // Prevent absurd frame times (debugger pauses, window drag, etc.)
-                let frame_time = frame_time.min(Duration::from_millis(250));
+                let frame_time = self
+                    .scene
+                    .world
+                    .resource::<TimeResource>()
+                    .catch_up_time(frame_time);
 
                 accumulator += frame_time;
 
@@ -293,11 +442,22 @@ impl Engine {
                     {
                         let phys_time = phys_start.elapsed();
                         if phys_time > fixed_dt {
+                            let stats = self
+                                .scene
+                                .world
+                                .resource::<physics::physics_resource::CollisionFrameData>()
+                                .stats;
                             log::warn!(
-                                "Physics schedule took {:?}, which is {:.2}% longer than the fixed dt of {:?}.",
+                                "Physics schedule took {:?}, which is {:.2}% longer than the fixed dt of {:?}. \
+                                 Broadphase {:?} ({} pairs), narrowphase {:?} ({} manifolds, {} contacts).",
                                 phys_time,
                                 phys_time.as_secs_f32() / fixed_dt.as_secs_f32() * 100.0,
-                                fixed_dt
+                                fixed_dt,
+                                stats.broadphase_time,
+                                stats.candidate_pairs,
+                                stats.narrowphase_time,
+                                stats.manifolds,
+                                stats.contacts
                             );
                         }
                     }
@@ -329,9 +489,19 @@ impl Engine {
             self.cleanup_schedule.run(&mut self.scene.world);
             // Reset bevy_ecs change detection (Added/Changed/Removed) so the next frame starts with a fresh diff.
             self.scene.world.clear_trackers();
-            let frame_time = frame_start.elapsed();
-            if frame_time < frame_target {
-                sleep(frame_target - frame_time);
+            if let Some(remaining) = self.frame_pacing.sleep_duration(frame_start.elapsed()) {
+                sleep(remaining);
+            }
+            if let Some(path) = self
+                .scene
+                .world
+                .get_resource_mut::<ScreenshotRequestResource>()
+                .and_then(|mut request| request.take_pending())
+            {
+                match self.capture_screenshot(&path) {
+                    Ok(()) => log::info!("Saved screenshot to {}", path.display()),
+                    Err(e) => log::error!("{e}"),
+                }
             }
             self.window.gl_swap_window();
             // Scene swapping
@@ -355,20 +525,30 @@ impl Engine {
         }
     }
 
+    /// Drains pending SDL events into `input_state`. The latest window size change, if any,
+    /// is written to `resized`. Returns `false` when the window was asked to close.
     fn handle_input(
         input_state: &mut InputStateResource,
         events_loop: &mut sdl2::EventPump,
+        resized: &mut Option<(u32, u32)>,
     ) -> bool {
         input_state.previous_keys = input_state.current_keys.clone();
         input_state.previous_mouse_buttons = input_state.current_mouse_buttons.clone();
         input_state.mouse_delta = (0.0, 0.0);
         input_state.scroll_delta = 0.0;
+        input_state.text_input.clear();
 
         for event in events_loop.poll_iter() {
             match event {
                 sdl2::event::Event::Quit { .. } => {
                     return false;
                 }
+                sdl2::event::Event::Window {
+                    win_event: sdl2::event::WindowEvent::SizeChanged(width, height),
+                    ..
+                } => {
+                    *resized = Some((width.max(1) as u32, height.max(1) as u32));
+                }
                 sdl2::event::Event::MouseMotion { xrel, yrel, .. } => {
                     input_state.mouse_delta = (xrel as f32, yrel as f32);
                 }
@@ -399,6 +579,9 @@ impl Engine {
                 } => {
                     input_state.current_keys.remove(&keycode);
                 }
+                sdl2::event::Event::TextInput { text, .. } => {
+                    input_state.text_input.push_str(&text);
+                }
                 _ => {}
             }
         }
@@ -435,6 +618,28 @@ impl Engine {
         }
     }
 
+    /// Updates the active camera's aspect ratio to match the new window size, if it is fixed.
+    /// A camera with an auto aspect ratio (`<= 0`) already follows the window each frame, and
+    /// inactive cameras keep theirs, since they may be rendering elsewhere.
+    /// The renderer sets its viewport from the window size every frame and owns no
+    /// size-dependent offscreen targets, so there is nothing else to reallocate.
+    fn apply_window_resize(world: &mut World, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        let Some(entity) = world
+            .get_resource::<ActiveCamera>()
+            .and_then(ActiveCamera::get)
+        else {
+            return;
+        };
+        if let Some(mut camera) = world.get_mut::<CameraComponent>(entity)
+            && camera.aspect_ratio > 0.0
+        {
+            camera.aspect_ratio = width as f32 / height as f32;
+        }
+    }
+
     /// Builds camera render data from the ECS world.
     /// Returns `None` if there is no active camera or if the camera entity is invalid.
     fn build_camera_render_data(
@@ -444,7 +649,16 @@ impl Engine {
     ) -> Option<CameraRenderData> {
         let active = world.get_resource::<ActiveCamera>()?;
         let entity = active.0?;
+        Self::camera_render_data(world, entity, width, height)
+    }
 
+    /// View-projection and position of `entity`'s camera for a `width` x `height` target.
+    fn camera_render_data(
+        world: &mut World,
+        entity: Entity,
+        width: u32,
+        height: u32,
+    ) -> Option<CameraRenderData> {
         let mut query = world.query::<(&TransformComponent, &CameraComponent)>();
         let Ok((transform, camera)) = query.get(world, entity) else {
             return None;
@@ -460,7 +674,7 @@ impl Engine {
         };
 
         let projection =
-            Mat4::perspective_rh(aspect_ratio, camera.fov_y_radians, camera.near, camera.far);
+            Mat4::perspective_rh(camera.fov_y_radians, aspect_ratio, camera.near, camera.far);
 
         Some(CameraRenderData {
             view_proj: projection * view,
@@ -513,6 +727,91 @@ impl Engine {
         Some(MeshCollider::new(render_body_id, layer))
     }
 
+    /// How far the background collision BVH builds of `render_body_id`'s meshes have come, from
+    /// 0 to 1, while any of them is still processing. Mesh colliders skip a part until its BVH
+    /// is ready. `None` once every part is ready, or if the render body isn't loaded.
+    pub fn collision_build_progress(&self, render_body_id: RenderBodyHandle) -> Option<f32> {
+        let bodies = self
+            .scene
+            .world
+            .get_resource::<RenderBodyResource>()?
+            .read();
+        let render_body = bodies.get_render_body(render_body_id)?;
+        let meshes = self.scene.world.get_resource::<MeshResource>()?.read();
+        render_body
+            .parts
+            .iter()
+            .filter_map(|part| meshes.bvh_build_progress(part.mesh_id))
+            .reduce(f32::min)
+    }
+
+    /// Like [`Self::mesh_collider_from_render_body`], but builds the collision BVH of every part
+    /// from a decimated copy of its triangles, keeping roughly `ratio` of them. The BVHs are
+    /// stored on the returned collider, so the meshes and other colliders using them keep
+    /// their full-res geometry.
+    pub fn mesh_collider_from_render_body_simplified(
+        &self,
+        render_body_id: RenderBodyHandle,
+        layer: CollisionLayer,
+        ratio: f32,
+    ) -> Option<MeshCollider> {
+        let render_body_resource = self
+            .scene
+            .world
+            .get_resource::<RenderBodyResource>()?
+            .read();
+        let render_body = render_body_resource.get_render_body(render_body_id)?;
+
+        let meshes = self.scene.world.get_resource::<MeshResource>()?.read();
+        let part_bvhs = render_body
+            .parts
+            .iter()
+            .map(|part| {
+                meshes
+                    .get_mesh(part.mesh_id)
+                    .and_then(|mesh| mesh.simplified_bvh(8, ratio))
+            })
+            .collect();
+
+        Some(MeshCollider {
+            part_bvhs: Some(part_bvhs),
+            ..MeshCollider::new(render_body_id, layer)
+        })
+    }
+
+    /// Returns an entity of the current scene whose `NameComponent` is `name`.
+    pub fn find_by_name(&self, name: &str) -> Option<Entity> {
+        NameComponent::find(&self.scene.world, name)
+    }
+
+    /// Returns every entity of the current scene whose `NameComponent` is `name`.
+    pub fn find_all_by_name(&self, name: &str) -> Vec<Entity> {
+        NameComponent::find_all(&self.scene.world, name)
+    }
+
+    /// Adds `force` to `entity`'s [`ForceAccumulatorComponent`], inserting one if needed. The
+    /// force acts on every physics step of the current frame.
+    pub fn add_force(&mut self, entity: Entity, force: Vec3) {
+        if let Some(mut accumulator) = self.force_accumulator(entity) {
+            accumulator.force += force;
+        }
+    }
+
+    /// Like [`Self::add_force`], for a torque about the body's center of mass.
+    pub fn add_torque(&mut self, entity: Entity, torque: Vec3) {
+        if let Some(mut accumulator) = self.force_accumulator(entity) {
+            accumulator.torque += torque;
+        }
+    }
+
+    fn force_accumulator(&mut self, entity: Entity) -> Option<Mut<'_, ForceAccumulatorComponent>> {
+        let mut entity = self.scene.world.get_entity_mut(entity).ok()?;
+        if !entity.contains::<ForceAccumulatorComponent>() {
+            entity.insert(ForceAccumulatorComponent::default());
+        }
+        entity.into_mut::<ForceAccumulatorComponent>()
+    }
+
     pub fn do_fake_impulse(
         velocity: &mut VelocityComponent,
         physics: &PhysicsComponent,
@@ -523,6 +822,17 @@ impl Engine {
     }
 }
 
+fn add_system_after<M>(
+    schedule: &mut Schedule,
+    label: EngineSet,
+    system: impl IntoScheduleConfigs<ScheduleSystem, M>,
+) {
+    match label.next() {
+        Some(next) => schedule.add_systems(system.after(label).before(next)),
+        None => schedule.add_systems(system.after(label)),
+    };
+}
+
 fn transform_aabb_with_mat4(aabb: Aabb, transform: &Mat4) -> Aabb {
     let min = aabb.min;
     let max = aabb.max;
@@ -559,3 +869,139 @@ fn union_aabb(a: Aabb, b: Aabb) -> Aabb {
         max: a.max.max(b.max),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn_active_camera(world: &mut World, aspect_ratio: f32) -> Entity {
+        let camera = world
+            .spawn((
+                TransformComponent::default(),
+                CameraComponent {
+                    fov_y_radians: 60.0_f32.to_radians(),
+                    aspect_ratio,
+                    near: 0.1,
+                    far: 100.0,
+                },
+            ))
+            .id();
+        world.insert_resource(ActiveCamera(Some(camera)));
+        camera
+    }
+
+    #[test]
+    fn auto_aspect_camera_uses_live_window_size() {
+        let mut world = World::new();
+        spawn_active_camera(&mut world, 0.0);
+
+        let fov = 60.0_f32.to_radians();
+        let wide = Engine::build_camera_render_data(&mut world, 1600, 800).unwrap();
+        let tall = Engine::build_camera_render_data(&mut world, 800, 1600).unwrap();
+
+        let expected_wide = Mat4::perspective_rh(fov, 2.0, 0.1, 100.0);
+        let expected_tall = Mat4::perspective_rh(fov, 0.5, 0.1, 100.0);
+        assert!(wide.view_proj.abs_diff_eq(expected_wide, 1e-5));
+        assert!(tall.view_proj.abs_diff_eq(expected_tall, 1e-5));
+    }
+
+    #[test]
+    fn window_resize_updates_only_the_active_fixed_aspect_camera() {
+        let mut world = World::new();
+        let inactive = spawn_active_camera(&mut world, 4.0 / 3.0);
+        let fixed = spawn_active_camera(&mut world, 4.0 / 3.0);
+
+        Engine::apply_window_resize(&mut world, 1920, 1080);
+
+        let fixed_camera = world.get::<CameraComponent>(fixed).unwrap();
+        assert!((fixed_camera.aspect_ratio - 1920.0 / 1080.0).abs() < 1e-6);
+        let inactive_camera = world.get::<CameraComponent>(inactive).unwrap();
+        assert_eq!(inactive_camera.aspect_ratio, 4.0 / 3.0);
+
+        let auto = spawn_active_camera(&mut world, 0.0);
+        Engine::apply_window_resize(&mut world, 800, 600);
+        let auto_camera = world.get::<CameraComponent>(auto).unwrap();
+        assert_eq!(auto_camera.aspect_ratio, 0.0);
+    }
+
+    #[derive(Resource, Default)]
+    struct ObservedVelocities(Vec<Vec3>);
+
+    fn record_velocities(
+        query: Query<(&VelocityComponent, &ConvexCollider)>,
+        mut observed: ResMut<ObservedVelocities>,
+    ) {
+        for (velocity, collider) in &query {
+            if collider.as_sphere_radius().is_some() {
+                observed.0.push(velocity.translational);
+            }
+        }
+    }
+
+    #[test]
+    fn system_added_after_solve_sees_post_solve_velocities() {
+        let mut world = World::new();
+        world.insert_resource(RenderBodyResource::default());
+        world.insert_resource(MeshResource::default());
+        world.insert_resource(physics::physics_resource::PhysicsResource::default());
+        world.insert_resource(physics::physics_resource::CollisionFrameData::default());
+        world.insert_resource(physics::physics_resource::PhysicsFrameData::default());
+        world.insert_resource(physics::physics_resource::PreSolveHooks::default());
+        world.insert_resource(TimeResource::new(60, 120));
+        world.insert_resource(Gravity::default());
+        world.init_resource::<ObservedVelocities>();
+
+        world.spawn((
+            TransformComponent {
+                position: Vec3::new(0.0, 0.0, -0.5),
+                ..Default::default()
+            },
+            ConvexCollider::cuboid(Vec3::new(10.0, 10.0, 1.0), CollisionLayer::Environment),
+            PhysicsComponent {
+                physics_type: components::physics_component::PhysicsType::Static,
+                mass: 0.0,
+                friction: 0.5,
+                rolling_friction: 0.0,
+                drag_coefficient: 0.0,
+                angular_drag_coefficient: 0.0,
+                restitution: 0.0,
+                local_inertia: glam::Mat3::IDENTITY,
+            },
+        ));
+        // Already touching the ground and driving into it
+        world.spawn((
+            TransformComponent {
+                position: Vec3::new(0.0, 0.0, 0.45),
+                ..Default::default()
+            },
+            VelocityComponent {
+                translational: Vec3::new(0.0, 0.0, -5.0),
+                ..Default::default()
+            },
+            ConvexCollider::sphere(0.5, CollisionLayer::Default),
+            PhysicsComponent {
+                physics_type: components::physics_component::PhysicsType::Dynamic,
+                mass: 1.0,
+                friction: 0.5,
+                rolling_friction: 0.0,
+                drag_coefficient: 0.0,
+                angular_drag_coefficient: 0.0,
+                restitution: 0.0,
+                local_inertia: glam::Mat3::IDENTITY,
+            },
+        ));
+
+        let mut schedule = Schedule::default();
+        Engine::add_physics_schedule(&mut schedule);
+        add_system_after(&mut schedule, EngineSet::Solve, record_velocities);
+        schedule.run(&mut world);
+
+        let observed = &world.resource::<ObservedVelocities>().0;
+        assert_eq!(observed.len(), 1);
+        assert!(
+            observed[0].z > -0.5,
+            "solver should have stopped the approach, saw {:?}",
+            observed[0]
+        );
+    }
+}
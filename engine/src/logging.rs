@@ -0,0 +1,94 @@
+use bevy_ecs::{resource::Resource, world::World};
+use log::LevelFilter;
+
+/// Most verbose log level that is printed, adjustable while the game runs. `RUST_LOG` still
+/// bounds what can be printed at all, so this can only narrow the environment's filter.
+/// Engine records use targets such as `gl`, `physics`, `audio` and `assets` for filtering
+/// through `RUST_LOG`.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogLevel(pub LevelFilter);
+
+impl Default for LogLevel {
+    /// Starts from the level currently in effect, so a new scene keeps the previous one's level.
+    fn default() -> Self {
+        Self(log::max_level())
+    }
+}
+
+impl LogLevel {
+    pub fn apply(&self) {
+        log::set_max_level(self.0);
+    }
+
+    /// Applies the world's `LogLevel` when it was inserted or changed since the last frame.
+    pub(crate) fn update(world: &mut World) {
+        if world.is_resource_changed::<LogLevel>() {
+            world.resource::<LogLevel>().apply();
+        }
+    }
+}
+
+/// Installs the engine's logger. Without `RUST_LOG` everything down to `Info` is printed;
+/// `LogLevel` can then raise or lower the level at runtime.
+pub(crate) fn init() {
+    let from_env = std::env::var_os("RUST_LOG").is_some();
+    env_logger::Builder::new()
+        .filter_level(LevelFilter::Trace)
+        .parse_default_env()
+        .init();
+    if !from_env {
+        log::set_max_level(LevelFilter::Info);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use log::{Level, Log, Metadata, Record};
+
+    use super::*;
+
+    const TARGET: &str = "log_level_test";
+
+    struct CapturingLogger(Mutex<Vec<Level>>);
+
+    impl Log for CapturingLogger {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            if record.target() == TARGET {
+                self.0.lock().unwrap().push(record.level());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CapturingLogger = CapturingLogger(Mutex::new(Vec::new()));
+
+    #[test]
+    fn setting_the_level_filters_out_less_severe_records() {
+        log::set_logger(&LOGGER).expect("no other logger is installed in tests");
+        let mut world = World::new();
+
+        world.insert_resource(LogLevel(LevelFilter::Warn));
+        LogLevel::update(&mut world);
+        log::info!(target: TARGET, "filtered");
+        log::warn!(target: TARGET, "kept");
+        log::error!(target: TARGET, "kept");
+
+        world.clear_trackers();
+        world.resource_mut::<LogLevel>().0 = LevelFilter::Debug;
+        LogLevel::update(&mut world);
+        log::debug!(target: TARGET, "kept");
+        log::trace!(target: TARGET, "filtered");
+
+        assert_eq!(
+            *LOGGER.0.lock().unwrap(),
+            vec![Level::Warn, Level::Error, Level::Debug]
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use bevy_ecs::entity::Entity;
+
+use crate::{assets::mesh::Aabb, physics::dynamic_aabb_tree::NodeId};
+
+/// Spatial index that `CollisionSystem` uses to find candidate pairs. Leaves store fattened
+/// AABBs, so queries are approximate and may report leaves slightly outside the queried
+/// region. `DynamicAabbTree` suits scenes of varied sizes; `SpatialHashBroadphase` can be
+/// faster for many similar-sized bodies.
+pub trait Broadphase: Send + Sync {
+    /// Adds a leaf for `entity` and returns its id for later `update` and `remove` calls.
+    fn allocate_leaf(&mut self, entity: Entity, aabb: Aabb) -> NodeId;
+
+    /// Moves `leaf` to `aabb`. Cheap while `aabb` still fits in the leaf's fattened bounds.
+    fn update(&mut self, leaf: NodeId, aabb: Aabb);
+
+    fn remove(&mut self, leaf: NodeId);
+
+    /// Calls `callback` once for every leaf whose fattened AABB overlaps `aabb`.
+    fn query(&self, aabb: Aabb, callback: &mut dyn FnMut(Entity));
+
+    /// Replaces every leaf with `leaves` in one go and returns the new leaf ids.
+    fn rebuild_from(&mut self, leaves: Vec<(Entity, Aabb)>) -> Vec<(Entity, NodeId)>;
+
+    /// Height of the deepest leaf below the root. Queries slow down as a hierarchy grows
+    /// taller than its leaf count needs; flat indices always report 0.
+    fn max_height(&self) -> i32 {
+        0
+    }
+
+    /// Summed surface area of the internal bounds, another measure of how loosely the
+    /// hierarchy fits its leaves. Flat indices always report 0.
+    fn total_area(&self) -> f32 {
+        0.0
+    }
+
+    /// Rebuilds the hierarchy from the current leaves. Leaf ids stay valid. Does nothing for
+    /// flat indices.
+    fn rebuild(&mut self) {}
+
+    /// Checks internal invariants, for tests and debugging.
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
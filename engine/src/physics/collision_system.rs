@@ -4,7 +4,10 @@ use bevy_ecs::{
 };
 use glam::{Mat4, Vec3};
 use rayon::prelude::*;
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
 use crate::{
     TransformComponent,
@@ -27,8 +30,12 @@ use crate::{
 
 use physics::{
     epa::epa,
-    gjk::{GjkResult, gjk_intersect},
-    physics_resource::{CollisionFrameData, Contact, ContactManifold, PhysicsResource},
+    gjk::{GjkResult, gjk_distance, gjk_intersect},
+    movement_system::MovementSystem,
+    physics_resource::{
+        CollisionFrameData, Contact, ContactManifold, ContactMergeConfig, MeshContactConfig,
+        PhysicsResource,
+    },
 };
 
 #[derive(Default)]
@@ -89,6 +96,15 @@ impl CollisionSystem {
         }
     }
 
+    /// Rebuilds the broadphase once it grows taller than
+    /// `PhysicsResource::broadphase_rebuild_height`.
+    pub fn rebuild_degraded_broadphase(mut phys: ResMut<PhysicsResource>) {
+        if phys.broadphase_rebuild_height.is_none() {
+            return;
+        }
+        phys.rebuild_broadphase_if_degraded();
+    }
+
     pub fn cleanup_removed_entities(
         mut phys: ResMut<PhysicsResource>,
         mut removed: RemovedComponents<TransformComponent>,
@@ -176,6 +192,7 @@ impl CollisionSystem {
     ) {
         let delta_t = time.simulation_fixed_dt();
         frame.clear();
+        let broadphase_start = Instant::now();
 
         for (entity, _transform, velocity, _convex, _mesh) in &moving_query {
             let base_aabb = match physics_world.world_aabbs.get(&entity) {
@@ -196,7 +213,7 @@ impl CollisionSystem {
             };
 
             // --- Query dynamic tree ---
-            physics_world.broadphase.query(swept, |other_entity| {
+            physics_world.broadphase.query(swept, &mut |other_entity| {
                 if other_entity != entity {
                     frame.candidate_pairs.push((entity, other_entity));
                 }
@@ -205,8 +222,10 @@ impl CollisionSystem {
 
         // deduplicate pairs (important!)
         Self::deduplicate_pairs(&mut frame.candidate_pairs);
+        frame.stats.broadphase_time = broadphase_start.elapsed();
+        let narrowphase_start = Instant::now();
 
-        let narrowphase_results: Vec<((Entity, Entity), ContactManifold)> = frame
+        let mut narrowphase_results: Vec<(OrderedEntityPair, ContactManifold)> = frame
             .candidate_pairs
             .par_iter()
             .filter_map(|(entity_a, entity_b)| {
@@ -229,6 +248,7 @@ impl CollisionSystem {
                         transform_b,
                         velocity_b,
                         &physics_world.world_aabbs,
+                        &physics_world.contact_merge,
                         previous_manifold,
                         delta_t,
                     )
@@ -256,6 +276,9 @@ impl CollisionSystem {
                         &render_body_resource,
                         &mesh_resource.read(),
                         &physics_world.world_aabbs,
+                        &physics_world.contact_merge,
+                        &physics_world.mesh_contact,
+                        physics_world.ccd_speed_threshold,
                         previous_manifold,
                         delta_t,
                     )
@@ -283,6 +306,9 @@ impl CollisionSystem {
                         &render_body_resource,
                         &mesh_resource.read(),
                         &physics_world.world_aabbs,
+                        &physics_world.contact_merge,
+                        &physics_world.mesh_contact,
+                        physics_world.ccd_speed_threshold,
                         previous_manifold,
                         delta_t,
                     )
@@ -302,6 +328,8 @@ impl CollisionSystem {
             })
             .collect();
 
+        sort_narrowphase_results(&mut narrowphase_results);
+
         for (pair, manifold) in narrowphase_results {
             if let Some(existing) = frame.manifolds.get_mut(pair) {
                 if manifold.contacts.len() > existing.contacts.len()
@@ -314,6 +342,15 @@ impl CollisionSystem {
                 frame.manifolds.push(pair, manifold);
             }
         }
+
+        frame.stats.narrowphase_time = narrowphase_start.elapsed();
+        frame.stats.candidate_pairs = frame.candidate_pairs.len();
+        frame.stats.manifolds = frame.manifolds.iter().count();
+        frame.stats.contacts = frame
+            .manifolds
+            .iter()
+            .map(|entry| entry.manifold.contacts.len())
+            .sum();
     }
 }
 
@@ -346,6 +383,7 @@ fn convex_convex_pair_manifold(
     transform_b: &TransformComponent,
     velocity_b: Option<&VelocityComponent>,
     world_aabbs: &HashMap<Entity, Aabb>,
+    merge_config: &ContactMergeConfig,
     previous_manifold: Option<&ContactManifold>,
     _delta_t: Duration,
 ) -> Option<ContactManifold> {
@@ -371,8 +409,8 @@ fn convex_convex_pair_manifold(
         previous_manifold,
         &oriented_contacts,
         merge_distance,
-        0.95,
-        4,
+        merge_config.convex_normal_cos_threshold,
+        merge_config.convex_max_contacts,
     );
 
     if merged.contacts.is_empty() {
@@ -394,6 +432,9 @@ fn convex_mesh_pair_manifold(
     render_body_resource: &RenderBodyResource,
     mesh_resource: &MeshStorage,
     world_aabbs: &HashMap<Entity, Aabb>,
+    merge_config: &ContactMergeConfig,
+    mesh_contact_config: &MeshContactConfig,
+    ccd_speed_threshold: f32,
     previous_manifold: Option<&ContactManifold>,
     delta_t: Duration,
 ) -> Option<ContactManifold> {
@@ -408,6 +449,8 @@ fn convex_mesh_pair_manifold(
         mesh_transform,
         render_body_resource,
         mesh_resource,
+        mesh_contact_config,
+        ccd_speed_threshold,
         previous_manifold,
         delta_t,
     );
@@ -422,8 +465,8 @@ fn convex_mesh_pair_manifold(
         previous_manifold,
         &oriented_contacts,
         merge_distance,
-        0.9,
-        8,
+        merge_config.mesh_normal_cos_threshold,
+        merge_config.mesh_max_contacts,
     );
 
     if merged.contacts.is_empty() {
@@ -435,6 +478,12 @@ fn convex_mesh_pair_manifold(
 
 pub type OrderedEntityPair = (Entity, Entity);
 
+/// Puts narrowphase results into a fixed order so the solver sees manifolds in the same
+/// order every run, independent of how the parallel narrowphase was scheduled.
+fn sort_narrowphase_results(results: &mut [(OrderedEntityPair, ContactManifold)]) {
+    results.sort_by_key(|(pair, _)| (pair.0.to_bits(), pair.1.to_bits()));
+}
+
 pub fn ordered_pair(a: Entity, b: Entity) -> OrderedEntityPair {
     if a.to_bits() <= b.to_bits() {
         (a, b)
@@ -582,6 +631,8 @@ fn merge_contact_manifold(
         }
     }
 
+    // A zero cap would silently drop every contact and let bodies pass through each other.
+    let max_contacts = max_contacts.max(1);
     if merged.len() > max_contacts {
         let mut scored: Vec<(Contact, f32)> = merged
             .into_iter()
@@ -673,8 +724,8 @@ fn sphere_sphere_contact(
     collider_b: &ConvexCollider,
     transform_b: &TransformComponent,
 ) -> Vec<Contact> {
-    let radius_a = collider_a.as_sphere_radius().unwrap();
-    let radius_b = collider_b.as_sphere_radius().unwrap();
+    let radius_a = collider_a.as_sphere_radius().unwrap() + collider_a.skin_width;
+    let radius_b = collider_b.as_sphere_radius().unwrap() + collider_b.skin_width;
 
     let center_a = transform_a.position;
     let center_b = transform_b.position;
@@ -707,6 +758,94 @@ fn sphere_sphere_contact(
     }]
 }
 
+/// Contacts between a plane and a sphere or cuboid, with the normal pointing out of the plane.
+/// Each candidate point is tested by its signed distance to the plane, so no GJK is needed.
+fn plane_convex_contact(
+    plane_entity: Entity,
+    plane_collider: &ConvexCollider,
+    plane_transform: &TransformComponent,
+    other_entity: Entity,
+    other_collider: &ConvexCollider,
+    other_transform: &TransformComponent,
+) -> Vec<Contact> {
+    let Some((local_normal, local_offset)) = plane_collider.as_plane() else {
+        return Vec::new();
+    };
+
+    let plane_mat = plane_transform.to_mat4();
+    let normal = plane_mat
+        .inverse()
+        .transpose()
+        .transform_vector3(local_normal)
+        .normalize_or_zero();
+    if normal == Vec3::ZERO {
+        return Vec::new();
+    }
+    let plane_point = plane_mat.transform_point3(local_normal * local_offset);
+    let offset = plane_point.dot(normal) + plane_collider.skin_width;
+
+    let candidate_points = match other_collider.shape {
+        ConvexShape::Cuboid {
+            length,
+            width,
+            height,
+        } => {
+            let other_mat = other_transform.to_mat4();
+            let extents = Vec3::new(length * 0.5, width * 0.5, height * 0.5);
+            let skin = -normal * other_collider.skin_width;
+            cuboid_world_vertices(Vec3::ZERO, [Vec3::X, Vec3::Y, Vec3::Z], extents)
+                .into_iter()
+                .map(|vertex| other_mat.transform_point3(vertex) + skin)
+                .collect()
+        }
+        _ => vec![other_collider.support(other_transform.to_mat4(), -normal)],
+    };
+
+    let mut contacts: Vec<Contact> = candidate_points
+        .into_iter()
+        .filter_map(|point| {
+            let penetration = offset - point.dot(normal);
+            (penetration > 0.0).then_some(Contact {
+                entity_a: plane_entity,
+                entity_b: other_entity,
+                normal,
+                penetration,
+                contact_point: point,
+            })
+        })
+        .collect();
+
+    // Keep the deepest points when a sunken cuboid has more than four below the plane.
+    contacts.sort_by(|a, b| {
+        let depth_a = (a.penetration / CONTACT_ORDER_QUANTUM).round();
+        let depth_b = (b.penetration / CONTACT_ORDER_QUANTUM).round();
+        depth_b
+            .total_cmp(&depth_a)
+            .then_with(|| compare_contact_points(a.contact_point, b.contact_point, normal))
+    });
+    contacts.truncate(4);
+    contacts
+}
+
+/// Grid that contact coordinates are snapped to before ordering.
+const CONTACT_ORDER_QUANTUM: f32 = 1e-4;
+
+/// Orders cuboid contact candidates so truncating to four keeps the same points every run.
+/// Coordinates are compared after snapping to `CONTACT_ORDER_QUANTUM`, so float noise cannot
+/// swap nearly equal points. Ties are broken by signed distance along `normal`, then by the
+/// raw coordinates.
+fn compare_contact_points(a: Vec3, b: Vec3, normal: Vec3) -> std::cmp::Ordering {
+    let qa = (a / CONTACT_ORDER_QUANTUM).round();
+    let qb = (b / CONTACT_ORDER_QUANTUM).round();
+    qa.x.total_cmp(&qb.x)
+        .then_with(|| qa.y.total_cmp(&qb.y))
+        .then_with(|| qa.z.total_cmp(&qb.z))
+        .then_with(|| a.dot(normal).total_cmp(&b.dot(normal)))
+        .then_with(|| a.x.total_cmp(&b.x))
+        .then_with(|| a.y.total_cmp(&b.y))
+        .then_with(|| a.z.total_cmp(&b.z))
+}
+
 fn cuboid_cuboid_contact(
     entity_a: Entity,
     collider_a: &ConvexCollider,
@@ -915,11 +1054,7 @@ fn cuboid_cuboid_contact(
         candidate_points.push((a_support + b_support) * 0.5);
     }
 
-    candidate_points.sort_by(|a, b| {
-        a.x.total_cmp(&b.x)
-            .then_with(|| a.y.total_cmp(&b.y))
-            .then_with(|| a.z.total_cmp(&b.z))
-    });
+    candidate_points.sort_by(|a, b| compare_contact_points(*a, *b, normal));
 
     let mut unique_points: Vec<Vec3> = Vec::with_capacity(candidate_points.len());
     for point in candidate_points {
@@ -959,7 +1094,7 @@ fn convex_convex_contact(
     _velocity_b: Option<&VelocityComponent>,
     previous_manifold: Option<&ContactManifold>,
 ) -> Vec<Contact> {
-    match (collider_a.shape, collider_b.shape) {
+    let mut contacts = match (collider_a.shape, collider_b.shape) {
         (ConvexShape::Sphere { .. }, ConvexShape::Sphere { .. }) => sphere_sphere_contact(
             entity_a,
             collider_a,
@@ -968,14 +1103,47 @@ fn convex_convex_contact(
             collider_b,
             transform_b,
         ),
-        (ConvexShape::Cuboid { .. }, ConvexShape::Cuboid { .. }) => cuboid_cuboid_contact(
-            entity_a,
-            collider_a,
-            transform_a,
-            entity_b,
-            collider_b,
-            transform_b,
-        ),
+        (ConvexShape::Plane { .. }, ConvexShape::Sphere { .. } | ConvexShape::Cuboid { .. }) => {
+            plane_convex_contact(
+                entity_a,
+                collider_a,
+                transform_a,
+                entity_b,
+                collider_b,
+                transform_b,
+            )
+        }
+        (ConvexShape::Sphere { .. } | ConvexShape::Cuboid { .. }, ConvexShape::Plane { .. }) => {
+            plane_convex_contact(
+                entity_b,
+                collider_b,
+                transform_b,
+                entity_a,
+                collider_a,
+                transform_a,
+            )
+            .into_iter()
+            .map(|contact| Contact {
+                entity_a,
+                entity_b,
+                normal: -contact.normal,
+                ..contact
+            })
+            .collect()
+        }
+        // The box-box path works on the bare extents, so skinned cuboids go through GJK/EPA instead.
+        (ConvexShape::Cuboid { .. }, ConvexShape::Cuboid { .. })
+            if collider_a.skin_width <= 0.0 && collider_b.skin_width <= 0.0 =>
+        {
+            cuboid_cuboid_contact(
+                entity_a,
+                collider_a,
+                transform_a,
+                entity_b,
+                collider_b,
+                transform_b,
+            )
+        }
         _ => {
             let contact = gjk_epa(
                 collider_a,
@@ -996,7 +1164,29 @@ fn convex_convex_contact(
                 .into_iter()
                 .collect()
         }
-    }
+    };
+
+    contacts.retain(|contact| {
+        let valid = is_valid_contact(contact.normal, contact.penetration, contact.contact_point);
+        if !valid {
+            log::trace!(
+                "Rejecting degenerate contact between {:?} and {:?}: {:?}",
+                entity_a,
+                entity_b,
+                contact
+            );
+        }
+        valid
+    });
+    contacts
+}
+
+/// A NaN or zero-length normal would poison the solver, so such results are dropped instead.
+fn is_valid_contact(normal: Vec3, penetration: f32, contact_point: Vec3) -> bool {
+    normal.is_finite()
+        && normal.length_squared() > f32::EPSILON
+        && penetration.is_finite()
+        && contact_point.is_finite()
 }
 
 fn point_inside_obb(
@@ -1025,6 +1215,108 @@ fn cuboid_world_vertices(center: Vec3, axes: [Vec3; 3], extents: Vec3) -> [Vec3;
     ]
 }
 
+/// Clearance between the surfaces of two convex colliders, or zero if they overlap. Unlike
+/// an AABB check this follows the actual shapes, for proximity triggers or to verify that
+/// parts keep a minimum gap.
+pub fn distance_between(
+    collider_a: &ConvexCollider,
+    transform_a: &TransformComponent,
+    collider_b: &ConvexCollider,
+    transform_b: &TransformComponent,
+) -> f32 {
+    gjk_distance(
+        collider_a,
+        transform_a.to_mat4(),
+        collider_b,
+        transform_b.to_mat4(),
+    )
+}
+
+/// Whether the segment from `start` to `end` passes through the actual shape of a convex
+/// collider. Narrows the AABB-only results of `PhysicsResource::query_segment`.
+pub fn segment_intersects_convex(
+    start: Vec3,
+    end: Vec3,
+    collider: &ConvexCollider,
+    transform: &TransformComponent,
+) -> bool {
+    let world = transform.to_mat4();
+    if let Some((normal, offset)) = collider.as_plane() {
+        let Some(world_inv) = world.try_inverse() else {
+            return false;
+        };
+        let depth = |point: Vec3| world_inv.transform_point3(point).dot(normal) - offset;
+        return depth(start).min(depth(end)) <= 0.0;
+    }
+    // A triangle with two equal corners has the segment as its support
+    let segment = ConvexCollider::triangle(start, end, end, collider.layer);
+    gjk_distance(&segment, Mat4::IDENTITY, collider, world) <= 0.0
+}
+
+/// Whether the segment from `start` to `end` crosses any triangle of a mesh collider.
+pub fn segment_intersects_mesh(
+    start: Vec3,
+    end: Vec3,
+    mesh_collider: &MeshCollider,
+    transform: &TransformComponent,
+    render_body_resource: &RenderBodyResource,
+    mesh_resource: &MeshStorage,
+) -> bool {
+    let binding = render_body_resource.read();
+    let Some(render_body) = binding.get_render_body(mesh_collider.render_body_id) else {
+        return false;
+    };
+    let mesh_entity_world = transform.to_mat4();
+    let mut triangles = Vec::new();
+    render_body.parts.iter().enumerate().any(|(index, part)| {
+        let Some(bvh) = mesh_resource
+            .get_mesh(part.mesh_id)
+            .and_then(|mesh| mesh_collider.part_bvh(index, mesh))
+        else {
+            return false;
+        };
+        let Some(mesh_world_inv) = (mesh_entity_world * part.local_transform).try_inverse() else {
+            return false;
+        };
+        let local_start = mesh_world_inv.transform_point3(start);
+        let local_end = mesh_world_inv.transform_point3(end);
+        let bounds = Aabb {
+            min: local_start.min(local_end),
+            max: local_start.max(local_end),
+        };
+        triangles.clear();
+        collect_triangles_in_aabb(bvh, &bounds, &mut triangles);
+        triangles
+            .iter()
+            .any(|tri| segment_intersects_triangle(local_start, local_end, tri))
+    })
+}
+
+/// Möller–Trumbore intersection, limited to the segment and accepting either winding.
+fn segment_intersects_triangle(start: Vec3, end: Vec3, tri: &Triangle) -> bool {
+    let dir = end - start;
+    let edge1 = tri.v1 - tri.v0;
+    let edge2 = tri.v2 - tri.v0;
+    let p = dir.cross(edge2);
+    let det = edge1.dot(p);
+    if det.abs() <= f32::EPSILON {
+        return false;
+    }
+    let inv_det = 1.0 / det;
+    let s = start - tri.v0;
+    let u = s.dot(p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return false;
+    }
+    let q = s.cross(edge1);
+    let v = dir.dot(q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return false;
+    }
+    let t = edge2.dot(q) * inv_det;
+    (0.0..=1.0).contains(&t)
+}
+
 struct GjkEpaResult {
     normal: Vec3,
     penetration_depth: f32,
@@ -1068,6 +1360,15 @@ fn gjk_epa(
     let b_support = collider_b.support(b_world, -normal);
     let contact_point = (a_support + b_support) * 0.5;
 
+    if !is_valid_contact(normal, epa_result.penetration_depth, contact_point) {
+        log::trace!(
+            "Rejecting degenerate GJK/EPA result: normal {:?}, penetration {}",
+            normal,
+            epa_result.penetration_depth
+        );
+        return None;
+    }
+
     Some(GjkEpaResult {
         normal,
         penetration_depth: epa_result.penetration_depth,
@@ -1086,6 +1387,8 @@ fn convex_mesh_contact(
     mesh_transform: &TransformComponent,
     render_body_resource: &RenderBodyResource,
     mesh_resource: &MeshStorage,
+    mesh_contact_config: &MeshContactConfig,
+    ccd_speed_threshold: f32,
     previous_manifold: Option<&ContactManifold>,
     delta_t: Duration,
 ) -> Vec<Contact> {
@@ -1095,10 +1398,10 @@ fn convex_mesh_contact(
     };
 
     let convex_world = convex_transform.to_mat4();
+    let delta_seconds = delta_t.as_secs_f32();
     let sweep_delta = convex_velocity
-        .map(|v| v.translational * delta_t.as_secs_f32())
+        .map(|v| v.translational * delta_seconds)
         .unwrap_or(Vec3::ZERO);
-    let has_sweep = sweep_delta.length_squared() > 0.0;
     let swept_transform = TransformComponent {
         position: convex_transform.position + sweep_delta,
         rotation: convex_transform.rotation,
@@ -1108,13 +1411,30 @@ fn convex_mesh_contact(
     let mesh_entity_world = mesh_transform.to_mat4();
 
     let convex_aabb_world = convex_collider.aabb(&convex_world);
+    let has_sweep = needs_swept_contact(sweep_delta, &convex_aabb_world, ccd_speed_threshold);
+
+    // The TOI sweep only follows translation, so fast spins fall back to sampling poses.
+    let angular_velocity = convex_velocity.map(|v| v.angular).unwrap_or(Vec3::ZERO);
+    let rotation_samples = rotation_sample_count(
+        angular_velocity.length() * delta_seconds,
+        &convex_aabb_world,
+        mesh_contact_config.swept_max_samples,
+    );
+    let sample_end = TransformComponent {
+        rotation: MovementSystem::apply_rotation(
+            &convex_transform.rotation,
+            &angular_velocity,
+            delta_seconds,
+        ),
+        ..swept_transform
+    };
     let mut candidates: Vec<ContactCandidate> = Vec::with_capacity(32);
 
-    for part in &render_body.parts {
+    for (index, part) in render_body.parts.iter().enumerate() {
         let Some(mesh) = mesh_resource.get_mesh(part.mesh_id) else {
             continue;
         };
-        let Some(bvh) = mesh.bvh.as_ref() else {
+        let Some(bvh) = mesh_collider.part_bvh(index, mesh) else {
             continue;
         };
 
@@ -1129,6 +1449,7 @@ fn convex_mesh_contact(
             &mesh_world,
             &mesh_world_inv,
             bvh,
+            mesh_contact_config,
             previous_manifold,
         ));
 
@@ -1140,12 +1461,86 @@ fn convex_mesh_contact(
                 &mesh_world,
                 &mesh_world_inv,
                 bvh,
+                mesh_contact_config,
+            ));
+        }
+
+        if rotation_samples > 1 {
+            candidates.extend(convex_mesh_sampled_contacts(
+                convex_collider,
+                convex_transform,
+                &sample_end,
+                rotation_samples,
+                &mesh_world,
+                &mesh_world_inv,
+                bvh,
+                mesh_contact_config,
             ));
         }
     }
     reduce_contact_candidates(mesh_entity, convex_entity, candidates, convex_aabb_world)
 }
 
+/// How many poses to sample for a body turning by `angle` radians this step: enough that its
+/// outermost point moves no more than its smallest extent between samples, capped at
+/// `max_samples`. One means only the current pose is tested.
+fn rotation_sample_count(angle: f32, world_aabb: &Aabb, max_samples: usize) -> usize {
+    let extent = world_aabb.max - world_aabb.min;
+    let size = extent.min_element();
+    if angle <= f32::EPSILON || size <= f32::EPSILON {
+        return 1;
+    }
+    let arc = angle * extent.length() * 0.5;
+    ((arc / size).ceil() as usize).clamp(1, max_samples.max(1))
+}
+
+/// Discrete contacts at evenly spaced poses between `start` and `end`. The start pose is
+/// already covered by the regular discrete test, so only the poses after it are checked.
+#[allow(clippy::too_many_arguments)]
+fn convex_mesh_sampled_contacts(
+    convex_collider: &ConvexCollider,
+    start: &TransformComponent,
+    end: &TransformComponent,
+    samples: usize,
+    mesh_world: &Mat4,
+    mesh_world_inv: &Mat4,
+    bvh: &BVHNode,
+    config: &MeshContactConfig,
+) -> Vec<ContactCandidate> {
+    let mut candidates = Vec::new();
+    for i in 1..samples {
+        let t = i as f32 / samples as f32;
+        let pose = TransformComponent {
+            position: start.position.lerp(end.position, t),
+            rotation: start.rotation.slerp(end.rotation, t),
+            scale: start.scale,
+        };
+        candidates.extend(convex_mesh_contact_at_transform(
+            convex_collider,
+            pose.to_mat4(),
+            mesh_world,
+            mesh_world_inv,
+            bvh,
+            config,
+            None,
+        ));
+    }
+    candidates
+}
+
+/// Whether a body moving `sweep_delta` this step is fast enough for swept contacts.
+/// Slow bodies are left to the discrete test, which catches their overlap next step anyway.
+fn needs_swept_contact(sweep_delta: Vec3, world_aabb: &Aabb, ccd_speed_threshold: f32) -> bool {
+    let displacement_sq = sweep_delta.length_squared();
+    if displacement_sq <= 0.0 {
+        return false;
+    }
+    let extent = world_aabb.max - world_aabb.min;
+    let size = extent.min_element().max(0.0);
+    let min_displacement = size * ccd_speed_threshold.max(0.0);
+    displacement_sq > min_displacement * min_displacement
+}
+
 /// Continuous convex-vs-mesh candidate generation using swept support-plane TOI.
 fn convex_mesh_swept_contact_at_transform(
     convex_collider: &ConvexCollider,
@@ -1154,6 +1549,7 @@ fn convex_mesh_swept_contact_at_transform(
     mesh_world: &Mat4,
     mesh_world_inv: &Mat4,
     bvh: &BVHNode,
+    config: &MeshContactConfig,
 ) -> Vec<ContactCandidate> {
     let collider_start_mesh = *mesh_world_inv * start_world;
     let collider_end_mesh = *mesh_world_inv * end_world;
@@ -1218,7 +1614,7 @@ fn convex_mesh_swept_contact_at_transform(
 
         // Reject far-off projections (outside triangle neighborhood).
         let lateral_error = (closest - projected).length_squared();
-        if lateral_error > 0.25 {
+        if lateral_error > config.swept_max_lateral_error * config.swept_max_lateral_error {
             continue;
         }
 
@@ -1239,6 +1635,7 @@ fn convex_mesh_contact_at_transform(
     mesh_world: &Mat4,
     mesh_world_inv: &Mat4,
     bvh: &BVHNode,
+    config: &MeshContactConfig,
     previous_manifold: Option<&ContactManifold>,
 ) -> Vec<ContactCandidate> {
     let collider_in_mesh_space = *mesh_world_inv * convex_world;
@@ -1360,7 +1757,8 @@ fn convex_mesh_contact_at_transform(
                 let edge1 = (tri_world.v2 - tri_world.v1).length();
                 let edge2 = (tri_world.v0 - tri_world.v2).length();
                 let tri_extent = edge0.max(edge1).max(edge2).max(0.01);
-                let lateral_tolerance = tri_extent * 0.25 + 0.05;
+                let lateral_tolerance =
+                    tri_extent * config.lateral_tolerance_scale + config.lateral_tolerance_bias;
                 if lateral_error > lateral_tolerance * lateral_tolerance {
                     continue;
                 }
@@ -1530,7 +1928,9 @@ mod tests {
     use approx::assert_relative_eq;
     use glam::{Mat4, Quat, Vec3};
 
+    use crate::assets::mesh::decimate_triangles;
     use crate::components::collider_component::CollisionLayer;
+    use crate::physics::{broadphase::Broadphase, dynamic_aabb_tree::NodeId};
 
     use super::*;
 
@@ -1616,6 +2016,7 @@ mod tests {
             &mesh_world,
             &mesh_world_inv,
             &bvh,
+            &MeshContactConfig::default(),
             None,
         );
 
@@ -1642,6 +2043,49 @@ mod tests {
             .fold(0.0_f32, f32::max)
     }
 
+    #[test]
+    fn simplified_collision_bvh_keeps_ground_contacts() {
+        let ground_obj = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("./test_resources/test_ground/test_ground.obj");
+        let triangles = load_obj_triangles(ground_obj.to_str().expect("Invalid UTF-8 path"));
+        let full_count = triangles.len();
+        let bvh = BVHNode::build(decimate_triangles(&triangles, 0.25), 8);
+
+        assert!(
+            bvh.triangle_count() < full_count,
+            "Expected fewer collision triangles than the render mesh ({} >= {})",
+            bvh.triangle_count(),
+            full_count
+        );
+
+        let mesh_transform = make_transform(
+            Vec3::new(0.0, 0.0, -20.0),
+            Quat::IDENTITY,
+            Vec3::splat(10.0),
+        );
+        let mesh_world = mesh_transform.to_mat4();
+        // Tall enough to straddle the whole height range of the ground.
+        let convex_collider =
+            ConvexCollider::cuboid(Vec3::new(2.0, 2.0, 4.0), CollisionLayer::Player);
+        let convex_world =
+            make_transform(Vec3::new(0.0, 0.0, -19.5), Quat::IDENTITY, Vec3::ONE).to_mat4();
+
+        let candidates = convex_mesh_contact_at_transform(
+            &convex_collider,
+            convex_world,
+            &mesh_world,
+            &mesh_world.inverse(),
+            &bvh,
+            &MeshContactConfig::default(),
+            None,
+        );
+
+        assert!(
+            !candidates.is_empty(),
+            "Expected the simplified ground to still produce contacts"
+        );
+    }
+
     #[test]
     fn convex_mesh_contact_at_transform_hits_triangle() {
         let tri = make_triangle();
@@ -1664,6 +2108,7 @@ mod tests {
             &mesh_world,
             &mesh_world_inv,
             &bvh,
+            &MeshContactConfig::default(),
             None,
         );
 
@@ -1697,6 +2142,7 @@ mod tests {
             &mesh_world,
             &mesh_world_inv,
             &bvh,
+            &MeshContactConfig::default(),
             None,
         );
 
@@ -1729,6 +2175,7 @@ mod tests {
             &mesh_world,
             &mesh_world_inv,
             &bvh,
+            &MeshContactConfig::default(),
             None,
         );
 
@@ -2271,6 +2718,7 @@ mod tests {
             &transform_b,
             None,
             &world_aabbs,
+            &ContactMergeConfig::default(),
             None,
             Duration::from_secs_f32(1.0 / 60.0),
         )
@@ -2405,4 +2853,705 @@ mod tests {
         let merged_2 = merge_contact_manifold(Some(&merged), &contacts, 0.1, 0.9, 8);
         assert_eq!(merged_2.contacts.len(), 4);
     }
+
+    #[test]
+    fn frame_stats_match_generated_pairs_and_manifolds() {
+        use crate::{
+            assets::mesh_resource::MeshResource,
+            physics::physics_resource::{CollisionFrameData, PhysicsResource},
+            time_resource::TimeResource,
+        };
+        use bevy_ecs::prelude::*;
+
+        let mut world = World::new();
+        world.insert_resource(RenderBodyResource::default());
+        world.insert_resource(MeshResource::default());
+        world.insert_resource(PhysicsResource::default());
+        world.insert_resource(CollisionFrameData::default());
+        world.insert_resource(TimeResource::new(60, 120));
+
+        // Two overlapping cubes and one far away from both.
+        for x in [0.0, 0.9, 20.0] {
+            world.spawn((
+                make_transform(Vec3::new(x, 0.0, 0.0), Quat::IDENTITY, Vec3::ONE),
+                ConvexCollider::cube(1.0, CollisionLayer::Default),
+            ));
+        }
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                CollisionSystem::update_world_aabb_cache,
+                CollisionSystem::update_world_dynamic_tree,
+                CollisionSystem::generate_manifolds,
+            )
+                .chain(),
+        );
+        schedule.run(&mut world);
+
+        let frame = world.resource::<CollisionFrameData>();
+        let contacts: usize = frame
+            .manifolds
+            .iter()
+            .map(|entry| entry.manifold.contacts.len())
+            .sum();
+        assert_eq!(frame.stats.candidate_pairs, 1);
+        assert_eq!(frame.stats.candidate_pairs, frame.candidate_pairs.len());
+        assert_eq!(frame.stats.manifolds, 1);
+        assert!(frame.stats.contacts > 0);
+        assert_eq!(frame.stats.contacts, contacts);
+    }
+
+    /// Brute-force broadphase that counts its queries, standing in for a custom implementation.
+    #[derive(Default)]
+    struct MockBroadphase {
+        leaves: Vec<Option<(Entity, Aabb)>>,
+        queries: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl Broadphase for MockBroadphase {
+        fn allocate_leaf(&mut self, entity: Entity, aabb: Aabb) -> NodeId {
+            self.leaves.push(Some((entity, aabb)));
+            NodeId::new(self.leaves.len()).unwrap()
+        }
+
+        fn update(&mut self, leaf: NodeId, aabb: Aabb) {
+            if let Some((_, stored)) = &mut self.leaves[leaf.get() - 1] {
+                *stored = aabb;
+            }
+        }
+
+        fn remove(&mut self, leaf: NodeId) {
+            self.leaves[leaf.get() - 1] = None;
+        }
+
+        fn query(&self, aabb: Aabb, callback: &mut dyn FnMut(Entity)) {
+            self.queries
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            for (entity, stored) in self.leaves.iter().flatten() {
+                if stored.intersects(&aabb) {
+                    callback(*entity);
+                }
+            }
+        }
+
+        fn rebuild_from(&mut self, leaves: Vec<(Entity, Aabb)>) -> Vec<(Entity, NodeId)> {
+            self.leaves.clear();
+            leaves
+                .into_iter()
+                .map(|(entity, aabb)| (entity, self.allocate_leaf(entity, aabb)))
+                .collect()
+        }
+    }
+
+    type StepSummary = (Vec<(Entity, Entity)>, Vec<(OrderedEntityPair, usize)>);
+
+    /// Runs two collision steps over a row of cubes, moving two of them in between, and
+    /// returns each step's candidate pairs and manifolds with their contact counts.
+    fn collision_steps(physics: PhysicsResource) -> Vec<StepSummary> {
+        use crate::{
+            assets::mesh_resource::MeshResource, physics::physics_resource::CollisionFrameData,
+            time_resource::TimeResource,
+        };
+        use bevy_ecs::prelude::*;
+
+        let mut world = World::new();
+        world.insert_resource(RenderBodyResource::default());
+        world.insert_resource(MeshResource::default());
+        world.insert_resource(physics);
+        world.insert_resource(CollisionFrameData::default());
+        world.insert_resource(TimeResource::new(60, 120));
+
+        // Gaps between separate cubes are wider than the tree's fat margin, so both
+        // broadphases agree on the candidates.
+        let cubes: Vec<Entity> = [0.0, 0.9, 5.0, 5.8, 20.0]
+            .into_iter()
+            .map(|x| {
+                world
+                    .spawn((
+                        make_transform(Vec3::new(x, 0.0, 0.0), Quat::IDENTITY, Vec3::ONE),
+                        ConvexCollider::cube(1.0, CollisionLayer::Default),
+                    ))
+                    .id()
+            })
+            .collect();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                CollisionSystem::update_world_aabb_cache,
+                CollisionSystem::update_world_dynamic_tree,
+                CollisionSystem::generate_manifolds,
+            )
+                .chain(),
+        );
+        let summarize = |world: &World| {
+            let frame = world.resource::<CollisionFrameData>();
+            let mut candidates = frame.candidate_pairs.clone();
+            candidates.sort();
+            let manifolds = frame
+                .manifolds
+                .iter()
+                .map(|entry| {
+                    (
+                        ordered_pair(entry.entity_a, entry.entity_b),
+                        entry.manifold.contacts.len(),
+                    )
+                })
+                .collect();
+            (candidates, manifolds)
+        };
+
+        schedule.run(&mut world);
+        let first = summarize(&world);
+        world
+            .get_mut::<TransformComponent>(cubes[4])
+            .unwrap()
+            .position = Vec3::new(0.5, 0.0, 0.0);
+        world
+            .get_mut::<TransformComponent>(cubes[3])
+            .unwrap()
+            .position = Vec3::new(10.0, 0.0, 0.0);
+        schedule.run(&mut world);
+        vec![first, summarize(&world)]
+    }
+
+    #[test]
+    fn collision_system_behaves_the_same_with_a_custom_broadphase() {
+        let mock = MockBroadphase::default();
+        let queries = mock.queries.clone();
+
+        let with_mock = collision_steps(PhysicsResource::with_broadphase(mock));
+        let with_tree = collision_steps(PhysicsResource::default());
+
+        assert!(queries.load(std::sync::atomic::Ordering::Relaxed) > 0);
+        assert_eq!(with_mock, with_tree);
+        // The moved cube joins the first pair and the second pair separates.
+        assert_eq!(with_mock[0].1.len(), 2);
+        assert_eq!(with_mock[1].1.len(), 3);
+    }
+
+    #[test]
+    fn distance_between_spheres_matches_their_center_gap_minus_radii() {
+        let big = ConvexCollider::sphere(1.0, CollisionLayer::Default);
+        let small = ConvexCollider::sphere(0.5, CollisionLayer::Default);
+        let at = |position: Vec3| make_transform(position, Quat::IDENTITY, Vec3::ONE);
+
+        for offset in [
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(0.0, -2.0, 2.5),
+            Vec3::new(1.2, 0.9, -0.7),
+        ] {
+            let expected = offset.length() - 1.5;
+            let distance = distance_between(&big, &at(Vec3::ONE), &small, &at(Vec3::ONE + offset));
+            assert_relative_eq!(distance, expected, epsilon = 1e-3);
+        }
+
+        let overlapping =
+            distance_between(&big, &at(Vec3::ZERO), &small, &at(Vec3::new(1.2, 0.0, 0.0)));
+        assert_eq!(overlapping, 0.0);
+    }
+
+    #[test]
+    fn segments_are_tested_against_the_shape_not_its_bounds() {
+        let sphere = ConvexCollider::sphere(1.0, CollisionLayer::Default);
+        let at_origin = make_transform(Vec3::ZERO, Quat::IDENTITY, Vec3::ONE);
+
+        assert!(segment_intersects_convex(
+            Vec3::new(-3.0, 0.0, 0.0),
+            Vec3::new(3.0, 0.0, 0.0),
+            &sphere,
+            &at_origin,
+        ));
+        // Crosses the sphere's bounding box near a corner, but misses the sphere
+        assert!(!segment_intersects_convex(
+            Vec3::new(-3.0, 0.9, 0.9),
+            Vec3::new(3.0, 0.9, 0.9),
+            &sphere,
+            &at_origin,
+        ));
+        // Stops short of the surface
+        assert!(!segment_intersects_convex(
+            Vec3::new(-3.0, 0.0, 0.0),
+            Vec3::new(-1.5, 0.0, 0.0),
+            &sphere,
+            &at_origin,
+        ));
+
+        let floor = ConvexCollider::plane(Vec3::Z, 0.0, CollisionLayer::Default);
+        assert!(segment_intersects_convex(
+            Vec3::new(0.0, 0.0, 2.0),
+            Vec3::new(5.0, 0.0, -1.0),
+            &floor,
+            &at_origin,
+        ));
+        assert!(!segment_intersects_convex(
+            Vec3::new(0.0, 0.0, 2.0),
+            Vec3::new(5.0, 0.0, 1.0),
+            &floor,
+            &at_origin,
+        ));
+
+        let tri = make_triangle();
+        let through = |x: f32, y: f32| {
+            segment_intersects_triangle(Vec3::new(x, y, 1.0), Vec3::new(x, y, -1.0), &tri)
+        };
+        assert!(through(0.25, 0.25));
+        assert!(!through(0.75, 0.75));
+        assert!(!segment_intersects_triangle(
+            Vec3::new(0.25, 0.25, 2.0),
+            Vec3::new(0.25, 0.25, 1.0),
+            &tri,
+        ));
+    }
+
+    #[test]
+    fn narrowphase_results_are_sorted_by_pair() {
+        let manifold = |z: f32| ContactManifold {
+            contacts: Vec::new(),
+            normal: Vec3::new(0.0, 0.0, z),
+            relative_normal_speed: 0.0,
+            impact_impulse: 0.0,
+            impact_energy: 0.0,
+        };
+        let pairs = [
+            ordered_pair(Entity::from_bits(7), Entity::from_bits(3)),
+            ordered_pair(Entity::from_bits(1), Entity::from_bits(9)),
+            ordered_pair(Entity::from_bits(3), Entity::from_bits(4)),
+            ordered_pair(Entity::from_bits(1), Entity::from_bits(2)),
+        ];
+
+        let mut first_run: Vec<_> = pairs
+            .iter()
+            .enumerate()
+            .map(|(i, pair)| (*pair, manifold(i as f32)))
+            .collect();
+        let mut second_run: Vec<_> = first_run
+            .iter()
+            .rev()
+            .map(|(pair, manifold)| (*pair, manifold.clone()))
+            .collect();
+
+        sort_narrowphase_results(&mut first_run);
+        sort_narrowphase_results(&mut second_run);
+
+        let order = |results: &[(OrderedEntityPair, ContactManifold)]| {
+            results
+                .iter()
+                .map(|(pair, manifold)| (*pair, manifold.normal.z))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(order(&first_run), order(&second_run));
+        assert_eq!(
+            first_run.iter().map(|(pair, _)| *pair).collect::<Vec<_>>(),
+            vec![pairs[3], pairs[1], pairs[2], pairs[0]]
+        );
+    }
+
+    #[test]
+    fn degenerate_contacts_are_rejected() {
+        assert!(is_valid_contact(Vec3::Z, 0.1, Vec3::ZERO));
+        assert!(!is_valid_contact(Vec3::NAN, 0.1, Vec3::ZERO));
+        assert!(!is_valid_contact(Vec3::ZERO, 0.1, Vec3::ZERO));
+        assert!(!is_valid_contact(Vec3::Z, f32::NAN, Vec3::ZERO));
+        assert!(!is_valid_contact(Vec3::Z, 0.1, Vec3::INFINITY));
+    }
+
+    #[test]
+    fn coincident_centers_produce_no_nan_contacts() {
+        let transform = make_transform(Vec3::new(1.0, 2.0, 3.0), Quat::IDENTITY, Vec3::ONE);
+        let pairs = [
+            (
+                ConvexCollider::sphere(0.5, CollisionLayer::Default),
+                ConvexCollider::cube(1.0, CollisionLayer::Default),
+            ),
+            (
+                ConvexCollider::egg(1.0, 0.5, CollisionLayer::Default),
+                ConvexCollider::sphere(0.5, CollisionLayer::Default),
+            ),
+            (
+                ConvexCollider::sphere(0.5, CollisionLayer::Default),
+                ConvexCollider::sphere(0.5, CollisionLayer::Default),
+            ),
+        ];
+
+        for (collider_a, collider_b) in pairs {
+            let contacts = convex_convex_contact(
+                Entity::from_bits(1),
+                &collider_a,
+                &transform,
+                None,
+                Entity::from_bits(2),
+                &collider_b,
+                &transform,
+                None,
+                None,
+            );
+            for contact in contacts {
+                assert!(contact.normal.is_finite(), "{contact:?}");
+                assert!(contact.penetration.is_finite(), "{contact:?}");
+                assert!(contact.contact_point.is_finite(), "{contact:?}");
+            }
+        }
+    }
+
+    /// Runs the collision pipeline once for a sphere sunk into a shallow mesh valley and
+    /// returns the number of contacts in its manifold.
+    fn valley_contact_count(contact_merge: ContactMergeConfig) -> usize {
+        use crate::{
+            assets::{
+                handles::MaterialHandle,
+                mesh::{Mesh, Vertex},
+            },
+            physics::physics_resource::CollisionFrameData,
+            render::render_body::{RenderBody, RenderBodyPart},
+            time_resource::TimeResource,
+        };
+        use bevy_ecs::prelude::*;
+
+        // Two faces rising ~11.5 degrees either side of the y axis, so their normals are
+        // ~23 degrees apart (cos ~0.92).
+        let rise = 10.0 * 11.5_f32.to_radians().tan();
+        let positions = [
+            [0.0, -10.0, 0.0],
+            [0.0, 10.0, 0.0],
+            [10.0, 0.0, rise],
+            [-10.0, 0.0, rise],
+        ];
+        let vertices: Vec<Vertex> = positions
+            .iter()
+            .map(|&position| Vertex {
+                position,
+                ..Default::default()
+            })
+            .collect();
+        let mesh = Mesh {
+            aabb: Aabb::from_vertices(&vertices),
+            vertices,
+            indices: vec![0, 2, 1, 0, 1, 3],
+            ..Default::default()
+        };
+
+        let meshes = MeshResource::default();
+        let bodies = RenderBodyResource::default();
+        let mesh_id = meshes.write().add_mesh_with_bvh(mesh, 8);
+        let render_body_id =
+            bodies
+                .write()
+                .add_render_body(RenderBody::new(vec![RenderBodyPart {
+                    mesh_id,
+                    material_id: MaterialHandle::default(),
+                    local_transform: Mat4::IDENTITY,
+                }]));
+
+        let mut world = World::new();
+        world.insert_resource(meshes);
+        world.insert_resource(bodies);
+        world.insert_resource(PhysicsResource {
+            contact_merge,
+            ..Default::default()
+        });
+        world.insert_resource(CollisionFrameData::default());
+        world.insert_resource(TimeResource::new(60, 120));
+
+        world.spawn((
+            make_transform(Vec3::ZERO, Quat::IDENTITY, Vec3::ONE),
+            MeshCollider::new(render_body_id, CollisionLayer::Environment),
+        ));
+        // Deep enough that the two face contacts lie within the merge distance
+        world.spawn((
+            make_transform(Vec3::new(0.0, 0.0, 0.05), Quat::IDENTITY, Vec3::ONE),
+            ConvexCollider::sphere(1.0, CollisionLayer::Default),
+        ));
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                CollisionSystem::update_world_aabb_cache,
+                CollisionSystem::update_world_dynamic_tree,
+                CollisionSystem::generate_manifolds,
+            )
+                .chain(),
+        );
+        schedule.run(&mut world);
+
+        let frame = world.resource::<CollisionFrameData>();
+        assert_eq!(frame.stats.manifolds, 1);
+        frame
+            .manifolds
+            .iter()
+            .map(|entry| entry.manifold.contacts.len())
+            .sum()
+    }
+
+    #[test]
+    fn stricter_normal_threshold_keeps_contacts_separate() {
+        let loose = valley_contact_count(ContactMergeConfig {
+            mesh_normal_cos_threshold: 0.9,
+            ..Default::default()
+        });
+        let strict = valley_contact_count(ContactMergeConfig {
+            mesh_normal_cos_threshold: 0.95,
+            ..Default::default()
+        });
+
+        assert_eq!(loose, 1);
+        assert_eq!(strict, 2);
+    }
+
+    #[test]
+    fn lower_contact_cap_keeps_fewer_contacts_of_a_face_overlap() {
+        let entity_a = Entity::from_bits(1);
+        let entity_b = Entity::from_bits(2);
+        // Corners of a 2 x 2 face resting flat on another box.
+        let contacts: Vec<Contact> = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+            .into_iter()
+            .map(|(x, y)| Contact {
+                entity_a,
+                entity_b,
+                normal: Vec3::Z,
+                penetration: 0.01,
+                contact_point: Vec3::new(x, y, 0.0),
+            })
+            .collect();
+        let merge = |max_contacts: usize| {
+            merge_contact_manifold(None, &contacts, 0.1, 0.95, max_contacts)
+                .contacts
+                .len()
+        };
+
+        assert_eq!(merge(ContactMergeConfig::default().convex_max_contacts), 4);
+        assert_eq!(merge(2), 2);
+        assert_eq!(merge(0), 1);
+    }
+
+    #[test]
+    fn only_fast_bodies_take_the_swept_contact_path() {
+        // A 1 x 0.5 x 1 box: its thinnest extent is 0.5
+        let aabb = Aabb {
+            min: Vec3::ZERO,
+            max: Vec3::new(1.0, 0.5, 1.0),
+        };
+        let threshold = 0.5;
+
+        let slow = Vec3::new(0.0, -0.2, 0.0);
+        let fast = Vec3::new(0.0, -0.3, 0.0);
+        assert!(!needs_swept_contact(slow, &aabb, threshold));
+        assert!(needs_swept_contact(fast, &aabb, threshold));
+
+        // Zero threshold sweeps anything that moves, and nothing that doesn't
+        assert!(needs_swept_contact(slow, &aabb, 0.0));
+        assert!(!needs_swept_contact(Vec3::ZERO, &aabb, 0.0));
+    }
+
+    #[test]
+    fn looser_lateral_tolerance_accepts_grazing_swept_contact() {
+        let bvh = BVHNode::build(
+            vec![Triangle {
+                v0: Vec3::ZERO,
+                v1: Vec3::new(0.2, 0.0, 0.0),
+                v2: Vec3::new(0.0, 0.2, 0.0),
+            }],
+            4,
+        );
+        // Sweeps down past the small triangle, crossing its plane 0.9 beyond its edge
+        let sphere = ConvexCollider::sphere(1.0, CollisionLayer::Default);
+        let start = Mat4::from_translation(Vec3::new(1.1, 0.0, 1.5));
+        let end = Mat4::from_translation(Vec3::new(1.1, 0.0, -1.5));
+        let mesh_world = Mat4::IDENTITY;
+
+        let default_config = MeshContactConfig::default();
+        let strict = convex_mesh_swept_contact_at_transform(
+            &sphere,
+            start,
+            end,
+            &mesh_world,
+            &mesh_world,
+            &bvh,
+            &default_config,
+        );
+        assert!(strict.is_empty());
+
+        let loose_config = MeshContactConfig {
+            swept_max_lateral_error: 1.0,
+            ..default_config
+        };
+        let loose = convex_mesh_swept_contact_at_transform(
+            &sphere,
+            start,
+            end,
+            &mesh_world,
+            &mesh_world,
+            &bvh,
+            &loose_config,
+        );
+        assert_eq!(loose.len(), 1);
+        assert_relative_eq!(loose[0].normal.z, 1.0, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn more_rotation_samples_catch_a_spinning_rod_tunneling_through_a_wall() {
+        // Upright triangle on the diagonal, which a rod turning from +X to +Y only crosses
+        // halfway through the step
+        let bvh = BVHNode::build(
+            vec![Triangle {
+                v0: Vec3::new(0.8, 0.8, -0.5),
+                v1: Vec3::new(1.6, 1.6, -0.5),
+                v2: Vec3::new(1.2, 1.2, 0.6),
+            }],
+            4,
+        );
+        let rod = ConvexCollider::cuboid(Vec3::new(4.0, 0.2, 0.2), CollisionLayer::Default);
+        let start = make_transform(Vec3::ZERO, Quat::IDENTITY, Vec3::ONE);
+        let end = make_transform(
+            Vec3::ZERO,
+            Quat::from_rotation_z(std::f32::consts::FRAC_PI_2),
+            Vec3::ONE,
+        );
+        let mesh_world = Mat4::IDENTITY;
+        let config = MeshContactConfig::default();
+
+        for pose in [&start, &end] {
+            let discrete = convex_mesh_contact_at_transform(
+                &rod,
+                pose.to_mat4(),
+                &mesh_world,
+                &mesh_world,
+                &bvh,
+                &config,
+                None,
+            );
+            assert!(discrete.is_empty());
+        }
+
+        let aabb = rod.aabb(&start.to_mat4());
+        let angle = std::f32::consts::FRAC_PI_2;
+        let sampled = |max_samples: usize| {
+            let samples = rotation_sample_count(angle, &aabb, max_samples);
+            convex_mesh_sampled_contacts(
+                &rod,
+                &start,
+                &end,
+                samples,
+                &mesh_world,
+                &mesh_world,
+                &bvh,
+                &config,
+            )
+        };
+
+        assert!(sampled(1).is_empty());
+        assert!(!sampled(20).is_empty());
+    }
+
+    #[test]
+    fn cuboid_contact_points_are_chosen_deterministically() {
+        let entity_a = Entity::from_bits(10);
+        let entity_b = Entity::from_bits(11);
+        let collider = ConvexCollider::cube(1.0, CollisionLayer::Default);
+        // Equal boxes stacked with 0.1 overlap: eight candidate corners, truncated to four
+        let transform_a = make_transform(Vec3::new(0.0, 0.0, 0.9), Quat::IDENTITY, Vec3::ONE);
+        let contacts_with_twist = |angle: f32| {
+            let transform_b = make_transform(Vec3::ZERO, Quat::from_rotation_z(angle), Vec3::ONE);
+            cuboid_cuboid_contact(
+                entity_a,
+                &collider,
+                &transform_a,
+                entity_b,
+                &collider,
+                &transform_b,
+            )
+        };
+
+        let first = contacts_with_twist(0.0);
+        assert_eq!(first.len(), 4);
+        let second = contacts_with_twist(0.0);
+        let points = |contacts: &[Contact]| -> Vec<Vec3> {
+            contacts.iter().map(|c| c.contact_point).collect()
+        };
+        assert_eq!(points(&first), points(&second));
+
+        // Rounding-level twists either way must not reshuffle which corners are kept
+        for angle in [1e-6, -1e-6] {
+            let twisted = contacts_with_twist(angle);
+            assert_eq!(twisted.len(), 4);
+            for (expected, actual) in points(&first).iter().zip(points(&twisted)) {
+                assert!(
+                    expected.abs_diff_eq(actual, 1e-4),
+                    "expected {expected:?}, got {actual:?} at twist {angle}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sphere_resting_on_plane_produces_single_contact() {
+        let plane_entity = Entity::from_bits(150);
+        let sphere_entity = Entity::from_bits(151);
+
+        let plane = ConvexCollider::plane(Vec3::Y, 0.0, CollisionLayer::Default);
+        let sphere = ConvexCollider::sphere(0.5, CollisionLayer::Default);
+
+        let plane_transform = make_transform(Vec3::ZERO, Quat::IDENTITY, Vec3::ONE);
+        let sphere_transform =
+            make_transform(Vec3::new(3.0, 0.45, -2.0), Quat::IDENTITY, Vec3::ONE);
+
+        let contacts = convex_convex_contact(
+            plane_entity,
+            &plane,
+            &plane_transform,
+            None,
+            sphere_entity,
+            &sphere,
+            &sphere_transform,
+            None,
+            None,
+        );
+
+        assert_eq!(contacts.len(), 1);
+        let contact = contacts[0];
+        assert_eq!(contact.entity_a, plane_entity);
+        assert_eq!(contact.entity_b, sphere_entity);
+        assert!(contact.normal.abs_diff_eq(Vec3::Y, 1e-5));
+        assert_relative_eq!(contact.penetration, 0.05, epsilon = 1e-5);
+        assert!(
+            contact
+                .contact_point
+                .abs_diff_eq(Vec3::new(3.0, -0.05, -2.0), 1e-5)
+        );
+
+        // Swapping the pair flips the normal so it still points from A to B
+        let swapped = convex_convex_contact(
+            sphere_entity,
+            &sphere,
+            &sphere_transform,
+            None,
+            plane_entity,
+            &plane,
+            &plane_transform,
+            None,
+            None,
+        );
+        assert_eq!(swapped.len(), 1);
+        assert_eq!(swapped[0].entity_a, sphere_entity);
+        assert!(swapped[0].normal.abs_diff_eq(-Vec3::Y, 1e-5));
+        assert_relative_eq!(swapped[0].penetration, 0.05, epsilon = 1e-5);
+
+        // Lifting the sphere clear of the plane removes the contact
+        let above = make_transform(Vec3::new(3.0, 0.6, -2.0), Quat::IDENTITY, Vec3::ONE);
+        assert!(
+            convex_convex_contact(
+                plane_entity,
+                &plane,
+                &plane_transform,
+                None,
+                sphere_entity,
+                &sphere,
+                &above,
+                None,
+                None,
+            )
+            .is_empty()
+        );
+    }
 }
@@ -3,7 +3,7 @@ use std::num::NonZeroUsize;
 use bevy_ecs::entity::Entity;
 use glam::Vec3;
 
-use crate::assets::mesh::Aabb;
+use crate::{assets::mesh::Aabb, physics::broadphase::Broadphase};
 
 // Using NonZeroUsize allows for some nice memory layout optimization
 // since Option<NodeId> can be represented as usize with 0 reserved for None.
@@ -21,11 +21,16 @@ struct Node {
     entity: Option<Entity>, // Some => leaf
 }
 
+pub const DEFAULT_FAT_MARGIN: f32 = 0.1;
+
 #[derive(Debug)]
 pub struct DynamicAabbTree {
     nodes: Vec<Node>,
     root: Option<NodeId>,
     free_list: Vec<NodeId>,
+    /// Padding added around leaf AABBs. Larger margins mean fewer reinsertions for moving
+    /// bodies at the cost of looser broadphase pairs.
+    pub fat_margin: f32,
 }
 
 impl Default for DynamicAabbTree {
@@ -38,18 +43,70 @@ impl Default for DynamicAabbTree {
             nodes,
             root: None,
             free_list: Vec::with_capacity(512),
+            fat_margin: DEFAULT_FAT_MARGIN,
         }
     }
 }
 
 impl DynamicAabbTree {
-    pub fn update(&mut self, node_id: NodeId, new_aabb: Aabb) {
-        let fat = Self::expand_aabb(new_aabb, 0.1);
+    pub fn with_fat_margin(fat_margin: f32) -> Self {
+        Self {
+            fat_margin,
+            ..Default::default()
+        }
+    }
 
-        if self.nodes[node_id.get()].aabb.contains(&fat) {
+    /// Builds a balanced tree from many leaves at once with a top-down median split. Prefer this
+    /// over repeated `allocate_leaf` calls when a level spawns lots of colliders together.
+    pub fn build(leaves: Vec<(Entity, Aabb)>) -> Self {
+        let mut tree = Self::default();
+        tree.bulk_insert(leaves);
+        tree
+    }
+
+    pub(crate) fn bulk_insert(&mut self, leaves: Vec<(Entity, Aabb)>) {
+        let mut ids: Vec<NodeId> = leaves
+            .into_iter()
+            .map(|(entity, aabb)| {
+                let leaf = self.allocate_node();
+                self.nodes[leaf.get()].aabb = Self::expand_aabb(aabb, self.fat_margin);
+                self.nodes[leaf.get()].entity = Some(entity);
+                leaf
+            })
+            .collect();
+        if ids.is_empty() {
+            return;
+        }
+
+        let root = self.build_subtree(&mut ids);
+        self.nodes[root.get()].parent = None;
+        self.root = Some(root);
+    }
+
+    /// Every leaf currently in the tree, as `(entity, node)` pairs.
+    pub fn leaves(&self) -> Vec<(Entity, NodeId)> {
+        let mut leaves = Vec::new();
+        let mut stack: Vec<NodeId> = self.root.into_iter().collect();
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id.get()];
+            match (node.left, node.right, node.entity) {
+                (Some(left), Some(right), _) => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+                (_, _, Some(entity)) => leaves.push((entity, id)),
+                _ => {}
+            }
+        }
+        leaves
+    }
+
+    pub fn update(&mut self, node_id: NodeId, new_aabb: Aabb) {
+        if self.nodes[node_id.get()].aabb.contains(&new_aabb) {
             return; // still inside fat AABB, no reinsertion needed
         }
 
+        let fat = Self::expand_aabb(new_aabb, self.fat_margin);
         self.remove(node_id);
         self.insert_leaf(node_id, fat);
     }
@@ -68,7 +125,7 @@ impl DynamicAabbTree {
     pub fn allocate_leaf(&mut self, entity: Entity, aabb: Aabb) -> NodeId {
         let leaf = self.allocate_node();
 
-        let fat = Self::expand_aabb(aabb, 0.1);
+        let fat = Self::expand_aabb(aabb, self.fat_margin);
 
         self.nodes[leaf.get()].aabb = fat;
         self.nodes[leaf.get()].entity = Some(entity);
@@ -343,6 +400,181 @@ impl DynamicAabbTree {
         self.nodes[id.get()].entity = None;
         self.free_list.push(id);
     }
+
+    /// Sum of the surface areas of all internal nodes. Lower is better; a growing value over a
+    /// long session means incremental insertion has degraded the tree.
+    pub fn total_area(&self) -> f32 {
+        self.internal_nodes()
+            .iter()
+            .map(|id| self.nodes[id.get()].aabb.area())
+            .sum()
+    }
+
+    /// Height of the root, 0 for a single leaf or an empty tree.
+    pub fn max_height(&self) -> i32 {
+        self.root.map_or(0, |root| self.nodes[root.get()].height)
+    }
+
+    /// Rebuilds the hierarchy top-down from the current leaves. Leaf `NodeId`s and their fat
+    /// AABBs are kept, so handles held elsewhere stay valid.
+    pub fn rebuild(&mut self) {
+        let Some(root) = self.root else {
+            return;
+        };
+
+        let mut leaves = Vec::new();
+        let mut stack = vec![root];
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id.get()];
+            match (node.left, node.right) {
+                (Some(left), Some(right)) => {
+                    stack.push(left);
+                    stack.push(right);
+                    self.recycle_node(id);
+                }
+                _ => leaves.push(id),
+            }
+        }
+
+        let root = self.build_subtree(&mut leaves);
+        self.nodes[root.get()].parent = None;
+        self.root = Some(root);
+    }
+
+    /// Median split along the axis with the widest spread of leaf centers.
+    fn build_subtree(&mut self, leaves: &mut [NodeId]) -> NodeId {
+        if leaves.len() == 1 {
+            return leaves[0];
+        }
+
+        let center = |tree: &Self, id: NodeId| {
+            let aabb = tree.nodes[id.get()].aabb;
+            (aabb.min + aabb.max) * 0.5
+        };
+
+        let first = center(self, leaves[0]);
+        let (mut min, mut max) = (first, first);
+        for &id in leaves.iter() {
+            let c = center(self, id);
+            min = min.min(c);
+            max = max.max(c);
+        }
+        let extent = max - min;
+        let axis = if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        };
+
+        let mid = leaves.len() / 2;
+        leaves.select_nth_unstable_by(mid, |a, b| {
+            center(self, *a)[axis].total_cmp(&center(self, *b)[axis])
+        });
+        let (left_leaves, right_leaves) = leaves.split_at_mut(mid);
+        let left = self.build_subtree(left_leaves);
+        let right = self.build_subtree(right_leaves);
+
+        let parent = self.allocate_node();
+        self.nodes[parent.get()].left = Some(left);
+        self.nodes[parent.get()].right = Some(right);
+        self.nodes[parent.get()].entity = None;
+        self.nodes[left.get()].parent = Some(parent);
+        self.nodes[right.get()].parent = Some(parent);
+        self.update_node(parent);
+        parent
+    }
+
+    /// Checks the structural invariants of the tree: internal nodes have two distinct children,
+    /// parent links are reciprocal, heights are consistent and every internal AABB contains its
+    /// children. Walks the whole tree, so keep it to debug builds and tests.
+    pub fn validate(&self) -> Result<(), String> {
+        let Some(root) = self.root else {
+            return Ok(());
+        };
+        if self.nodes[root.get()].parent.is_some() {
+            return Err(format!("root {root} has a parent"));
+        }
+
+        let mut visited = vec![false; self.nodes.len()];
+        self.validate_node(root, None, &mut visited).map(|_| ())
+    }
+
+    fn validate_node(
+        &self,
+        id: NodeId,
+        expected_parent: Option<NodeId>,
+        visited: &mut [bool],
+    ) -> Result<i32, String> {
+        if std::mem::replace(&mut visited[id.get()], true) {
+            return Err(format!("node {id} is reachable twice"));
+        }
+
+        let node = &self.nodes[id.get()];
+        if node.parent != expected_parent {
+            return Err(format!(
+                "node {id} has parent {:?}, expected {:?}",
+                node.parent, expected_parent
+            ));
+        }
+
+        let (left, right) = match (node.left, node.right) {
+            (None, None) => {
+                if node.entity.is_none() {
+                    return Err(format!("leaf {id} has no entity"));
+                }
+                if node.height != 0 {
+                    return Err(format!("leaf {id} has height {}", node.height));
+                }
+                return Ok(0);
+            }
+            (Some(left), Some(right)) if left != right => (left, right),
+            _ => {
+                return Err(format!(
+                    "internal node {id} does not have two distinct children"
+                ));
+            }
+        };
+
+        if node.entity.is_some() {
+            return Err(format!("internal node {id} has an entity"));
+        }
+
+        let left_height = self.validate_node(left, Some(id), visited)?;
+        let right_height = self.validate_node(right, Some(id), visited)?;
+
+        let expected_height = 1 + left_height.max(right_height);
+        if node.height != expected_height {
+            return Err(format!(
+                "node {id} has height {}, expected {expected_height}",
+                node.height
+            ));
+        }
+
+        for child in [left, right] {
+            if !node.aabb.contains(&self.nodes[child.get()].aabb) {
+                return Err(format!("node {id} AABB does not contain child {child}"));
+            }
+        }
+
+        Ok(expected_height)
+    }
+
+    fn internal_nodes(&self) -> Vec<NodeId> {
+        let mut internal = Vec::new();
+        let mut stack: Vec<NodeId> = self.root.into_iter().collect();
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id.get()];
+            if let (Some(left), Some(right)) = (node.left, node.right) {
+                internal.push(id);
+                stack.push(left);
+                stack.push(right);
+            }
+        }
+        internal
+    }
+
     pub fn query<F>(&self, aabb: Aabb, mut callback: F)
     where
         F: FnMut(Entity),
@@ -371,6 +603,46 @@ impl DynamicAabbTree {
     }
 }
 
+impl Broadphase for DynamicAabbTree {
+    fn allocate_leaf(&mut self, entity: Entity, aabb: Aabb) -> NodeId {
+        DynamicAabbTree::allocate_leaf(self, entity, aabb)
+    }
+
+    fn update(&mut self, leaf: NodeId, aabb: Aabb) {
+        DynamicAabbTree::update(self, leaf, aabb);
+    }
+
+    fn remove(&mut self, leaf: NodeId) {
+        DynamicAabbTree::remove(self, leaf);
+    }
+
+    fn query(&self, aabb: Aabb, callback: &mut dyn FnMut(Entity)) {
+        DynamicAabbTree::query(self, aabb, callback);
+    }
+
+    fn max_height(&self) -> i32 {
+        DynamicAabbTree::max_height(self)
+    }
+
+    fn total_area(&self) -> f32 {
+        DynamicAabbTree::total_area(self)
+    }
+
+    fn rebuild(&mut self) {
+        DynamicAabbTree::rebuild(self);
+    }
+
+    fn rebuild_from(&mut self, leaves: Vec<(Entity, Aabb)>) -> Vec<(Entity, NodeId)> {
+        *self = Self::with_fat_margin(self.fat_margin);
+        self.bulk_insert(leaves);
+        self.leaves()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        DynamicAabbTree::validate(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
@@ -542,7 +814,7 @@ mod tests {
 
         let outside_move = make_aabb(Vec3::new(10.0, 0.0, 0.0), 1.0);
         tree.update(leaf, outside_move);
-        let expected_fat = DynamicAabbTree::expand_aabb(outside_move, 0.1);
+        let expected_fat = DynamicAabbTree::expand_aabb(outside_move, DEFAULT_FAT_MARGIN);
         assert_aabb_eq(
             tree.nodes[leaf.get()].aabb,
             expected_fat,
@@ -551,6 +823,31 @@ mod tests {
         assert_tree_invariants(&tree, true);
     }
 
+    fn count_reinsertions(tree: &mut DynamicAabbTree) -> usize {
+        let leaf = tree.allocate_leaf(Entity::from_bits(1), make_aabb(Vec3::ZERO, 1.0));
+        let mut reinsertions = 0;
+        for step in 1..=100 {
+            let before = tree.nodes[leaf.get()].aabb;
+            let offset = Vec3::new(step as f32 * 0.05, 0.0, 0.0);
+            tree.update(leaf, make_aabb(offset, 1.0));
+            if tree.nodes[leaf.get()].aabb.min != before.min {
+                reinsertions += 1;
+            }
+        }
+        reinsertions
+    }
+
+    #[test]
+    fn larger_fat_margin_reduces_reinsertions() {
+        let small = count_reinsertions(&mut DynamicAabbTree::with_fat_margin(0.1));
+        let large = count_reinsertions(&mut DynamicAabbTree::with_fat_margin(1.0));
+
+        assert!(
+            large < small,
+            "expected fewer reinsertions with a larger margin ({large} >= {small})"
+        );
+    }
+
     #[test]
     fn free_list_reuse() {
         let mut tree = DynamicAabbTree::default();
@@ -636,6 +933,85 @@ mod tests {
         assert_eq!(tree.nodes[new_root.get()].parent, None);
     }
 
+    #[test]
+    fn rebuild_reduces_height_of_degenerate_tree() {
+        let mut tree = DynamicAabbTree::default();
+        let leaves: Vec<NodeId> = (0..16)
+            .map(|i| {
+                add_leaf_node(
+                    &mut tree,
+                    Entity::from_bits(i + 1),
+                    make_aabb(Vec3::new(i as f32 * 2.0, 0.0, 0.0), 0.5),
+                )
+            })
+            .collect();
+
+        // Chain every leaf onto one spine, as naive linear insertion would.
+        let mut root = leaves[0];
+        for &leaf in &leaves[1..] {
+            root = add_internal_node(&mut tree, root, leaf);
+        }
+        tree.root = Some(root);
+        assert_tree_invariants(&tree, false);
+
+        let degenerate_height = tree.max_height();
+        let degenerate_area = tree.total_area();
+        tree.rebuild();
+
+        let (height, rebuilt_leaves) = assert_tree_invariants(&tree, true);
+        assert_eq!(rebuilt_leaves.len(), leaves.len());
+        assert_eq!(height, tree.max_height());
+        assert!(
+            tree.max_height() < degenerate_height,
+            "rebuild should reduce height ({} >= {degenerate_height})",
+            tree.max_height()
+        );
+        assert!(tree.total_area() < degenerate_area);
+
+        let mut hits = HashSet::new();
+        tree.query(make_aabb(Vec3::new(10.0, 0.0, 0.0), 0.1), |e| {
+            hits.insert(e);
+        });
+        assert_eq!(hits, HashSet::from([Entity::from_bits(6)]));
+    }
+
+    #[test]
+    fn bulk_build_is_no_taller_than_incremental_insertion() {
+        let leaves: Vec<(Entity, Aabb)> = (0..128u64)
+            .map(|i| {
+                let center = Vec3::new(
+                    (i % 8) as f32 * 3.0,
+                    (i / 8 % 4) as f32 * 3.0,
+                    (i / 32) as f32 * 3.0,
+                );
+                (Entity::from_bits(i + 1), make_aabb(center, 0.5))
+            })
+            .collect();
+
+        let mut incremental = DynamicAabbTree::default();
+        for (entity, aabb) in &leaves {
+            incremental.allocate_leaf(*entity, *aabb);
+        }
+        let bulk = DynamicAabbTree::build(leaves.clone());
+
+        assert_eq!(bulk.validate(), Ok(()));
+        let (height, leaf_ids) = assert_tree_invariants(&bulk, true);
+        assert_eq!(leaf_ids.len(), leaves.len());
+        assert_eq!(
+            height, 7,
+            "median split of 128 leaves should be perfectly balanced"
+        );
+        assert!(
+            bulk.max_height() <= incremental.max_height(),
+            "bulk build height {} exceeds incremental height {}",
+            bulk.max_height(),
+            incremental.max_height()
+        );
+
+        let entities: HashSet<Entity> = bulk.leaves().into_iter().map(|(e, _)| e).collect();
+        assert_eq!(entities.len(), leaves.len());
+    }
+
     #[test]
     fn leaf_removal() {
         // Root leaf removal
@@ -1010,4 +1386,87 @@ mod tests {
             assert_eq!(found, expected);
         }
     }
+
+    #[test]
+    fn validate_accepts_random_insert_remove_sequences() {
+        let mut rng = StdRng::seed_from_u64(0x0BAD_CAFE_2468_1357);
+        let mut tree = DynamicAabbTree::default();
+        let mut live: Vec<(NodeId, Entity, Aabb)> = Vec::new();
+
+        for step in 0..2000u64 {
+            if live.is_empty() || rng.random_bool(0.55) {
+                let center = Vec3::new(
+                    rng.random_range(-30.0..30.0),
+                    rng.random_range(-30.0..30.0),
+                    rng.random_range(-30.0..30.0),
+                );
+                let aabb = make_aabb(center, rng.random_range(0.1..2.0));
+                let entity = Entity::from_bits(step + 1);
+                live.push((tree.allocate_leaf(entity, aabb), entity, aabb));
+            } else {
+                let (id, _, _) = live.swap_remove(rng.random_range(0..live.len()));
+                tree.remove(id);
+            }
+
+            if let Err(message) = tree.validate() {
+                panic!("tree invalid after step {step}: {message}");
+            }
+        }
+
+        let (_, leaf_ids) = assert_tree_invariants(&tree, false);
+        assert_eq!(leaf_ids.len(), live.len());
+
+        // Re-query after all the churn and compare against a brute-force scan.
+        for _ in 0..50 {
+            let center = Vec3::new(
+                rng.random_range(-35.0..35.0),
+                rng.random_range(-35.0..35.0),
+                rng.random_range(-35.0..35.0),
+            );
+            let query = make_aabb(center, rng.random_range(0.5..6.0));
+
+            let mut found = HashSet::new();
+            tree.query(query, |entity| {
+                found.insert(entity);
+            });
+
+            let expected: HashSet<Entity> = live
+                .iter()
+                .filter(|(_, _, aabb)| {
+                    DynamicAabbTree::expand_aabb(*aabb, DEFAULT_FAT_MARGIN).intersects(&query)
+                })
+                .map(|(_, entity, _)| *entity)
+                .collect();
+
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn validate_rejects_broken_parent_link() {
+        let mut tree = DynamicAabbTree::default();
+        let leaf1 = tree.allocate_leaf(Entity::from_bits(1), make_aabb(Vec3::ZERO, 1.0));
+        tree.allocate_leaf(
+            Entity::from_bits(2),
+            make_aabb(Vec3::new(5.0, 0.0, 0.0), 1.0),
+        );
+        assert_eq!(tree.validate(), Ok(()));
+
+        tree.nodes[leaf1.get()].parent = None;
+        assert!(tree.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_stale_internal_aabb() {
+        let mut tree = DynamicAabbTree::default();
+        tree.allocate_leaf(Entity::from_bits(1), make_aabb(Vec3::ZERO, 1.0));
+        tree.allocate_leaf(
+            Entity::from_bits(2),
+            make_aabb(Vec3::new(5.0, 0.0, 0.0), 1.0),
+        );
+
+        let root = tree.root.expect("root should exist");
+        tree.nodes[root.get()].aabb = make_aabb(Vec3::ZERO, 1.0);
+        assert!(tree.validate().is_err());
+    }
 }
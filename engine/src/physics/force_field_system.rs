@@ -0,0 +1,154 @@
+use bevy_ecs::prelude::*;
+use glam::Vec3;
+use std::collections::HashMap;
+
+use crate::{
+    assets::mesh::Aabb,
+    components::{
+        force_accumulator_component::ForceAccumulatorComponent,
+        force_field_component::{FieldForce, ForceFieldComponent},
+        physics_component::{PhysicsComponent, PhysicsType},
+        transform_component::TransformComponent,
+    },
+    physics::physics_resource::PhysicsResource,
+};
+
+pub struct ForceFieldSystem;
+
+impl ForceFieldSystem {
+    /// Adds each field's force to the field force of the dynamic bodies whose position lies
+    /// inside the field. Candidates come from the broadphase, so only bodies with a collider
+    /// are affected.
+    pub fn apply_force_fields(
+        mut commands: Commands,
+        fields: Query<(Entity, &ForceFieldComponent, &TransformComponent)>,
+        mut bodies: Query<(
+            &TransformComponent,
+            &PhysicsComponent,
+            Option<&mut ForceAccumulatorComponent>,
+        )>,
+        physics_world: Res<PhysicsResource>,
+    ) {
+        let mut forces: HashMap<Entity, Vec3> = HashMap::new();
+        for (field_entity, field, field_transform) in &fields {
+            let region = Aabb {
+                min: field.aabb.min + field_transform.position,
+                max: field.aabb.max + field_transform.position,
+            };
+            let center = (region.min + region.max) * 0.5;
+
+            for entity in physics_world.query_aabb(region) {
+                if entity == field_entity {
+                    continue;
+                }
+                let Ok((transform, physics, _)) = bodies.get(entity) else {
+                    continue;
+                };
+                if !matches!(physics.physics_type, PhysicsType::Dynamic)
+                    || !region.contains_point(transform.position)
+                {
+                    continue;
+                }
+                let force = match field.force {
+                    FieldForce::Directional(force) => force,
+                    FieldForce::Radial(strength) => {
+                        (transform.position - center).normalize_or_zero() * strength
+                    }
+                };
+                *forces.entry(entity).or_default() += force;
+            }
+        }
+
+        for (entity, force) in forces {
+            match bodies.get_mut(entity) {
+                Ok((_, _, Some(mut accumulator))) => accumulator.field_force += force,
+                Ok((_, _, None)) => {
+                    commands.entity(entity).insert(ForceAccumulatorComponent {
+                        field_force: force,
+                        ..Default::default()
+                    });
+                }
+                Err(_) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::velocity_component::VelocityComponent,
+        physics::movement_system::MovementSystem, time_resource::TimeResource,
+    };
+
+    use super::*;
+
+    fn spawn_body(world: &mut World, position: Vec3) -> Entity {
+        let entity = world
+            .spawn((
+                TransformComponent {
+                    position,
+                    ..Default::default()
+                },
+                VelocityComponent::default(),
+                PhysicsComponent {
+                    physics_type: PhysicsType::Dynamic,
+                    mass: 1.0,
+                    friction: 0.0,
+                    rolling_friction: 0.0,
+                    drag_coefficient: 0.0,
+                    angular_drag_coefficient: 0.0,
+                    restitution: 0.0,
+                    local_inertia: glam::Mat3::IDENTITY,
+                },
+            ))
+            .id();
+        world.resource_mut::<PhysicsResource>().world_aabbs.insert(
+            entity,
+            Aabb {
+                min: position - Vec3::splat(0.5),
+                max: position + Vec3::splat(0.5),
+            },
+        );
+        entity
+    }
+
+    #[test]
+    fn body_inside_field_accelerates_along_it_and_outside_body_does_not() {
+        let mut world = World::new();
+        world.insert_resource(TimeResource::new(60, 120));
+        world.insert_resource(PhysicsResource::default());
+        let inside = spawn_body(&mut world, Vec3::new(1.0, 0.0, 0.0));
+        let outside = spawn_body(&mut world, Vec3::new(10.0, 0.0, 0.0));
+        world.resource_mut::<PhysicsResource>().bake_broadphase();
+        world.spawn(ForceFieldComponent {
+            aabb: Aabb {
+                min: Vec3::splat(-3.0),
+                max: Vec3::splat(3.0),
+            },
+            force: FieldForce::Directional(Vec3::new(0.0, 5.0, 0.0)),
+        });
+
+        let mut schedule = Schedule::default();
+        schedule
+            .add_systems((ForceFieldSystem::apply_force_fields, MovementSystem::update).chain());
+        for _ in 0..3 {
+            schedule.run(&mut world);
+        }
+
+        let pushed = world
+            .get::<VelocityComponent>(inside)
+            .unwrap()
+            .translational;
+        assert!(pushed.y > 0.0, "{pushed:?}");
+        assert_eq!(pushed.x, 0.0);
+        assert_eq!(pushed.z, 0.0);
+        assert_eq!(
+            world
+                .get::<VelocityComponent>(outside)
+                .unwrap()
+                .translational,
+            Vec3::ZERO
+        );
+    }
+}
@@ -7,6 +7,9 @@ use crate::components::collider_component::ConvexCollider;
 
 const DEFAULT_MAX_ITERATIONS: usize = 32;
 const EPSILON: f32 = 1e-6;
+/// Relative gap between the distance estimate and its lower bound at which `gjk_distance`
+/// stops. Curved shapes only converge asymptotically, so this bounds their error.
+const DISTANCE_TOLERANCE: f32 = 1e-4;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct GjkHit {
@@ -96,6 +99,87 @@ pub fn gjk_intersect_with_params(
     GjkResult::NoIntersection
 }
 
+/// Minimum distance between the surfaces of two convex colliders, skin widths included.
+/// Overlapping colliders are at distance zero. Runs GJK in closest-point mode: the simplex
+/// is shrunk to the feature of the Minkowski difference nearest the origin each iteration.
+pub fn gjk_distance(
+    a: &ConvexCollider,
+    a_transform: Mat4,
+    b: &ConvexCollider,
+    b_transform: Mat4,
+) -> f32 {
+    if let GjkResult::Intersection(_) = gjk_intersect(a, a_transform, b, b_transform) {
+        return 0.0;
+    }
+
+    let first = support_minkowski(
+        a,
+        a_transform,
+        b,
+        b_transform,
+        initial_direction(a_transform, b_transform),
+    );
+    let mut simplex = vec![first];
+    let mut closest = first;
+
+    for _ in 0..DEFAULT_MAX_ITERATIONS {
+        let distance_sq = closest.length_squared();
+        if distance_sq <= EPSILON {
+            return 0.0;
+        }
+
+        let support = support_minkowski(a, a_transform, b, b_transform, -closest);
+        // `closest.dot(support) / |closest|` is a lower bound on the distance
+        if distance_sq - closest.dot(support) <= DISTANCE_TOLERANCE * distance_sq
+            || simplex
+                .iter()
+                .any(|p| (*p - support).length_squared() <= EPSILON)
+        {
+            break;
+        }
+
+        simplex.push(support);
+        match closest_on_simplex(&mut simplex) {
+            Some(point) => closest = point,
+            None => return 0.0,
+        }
+    }
+    closest.length()
+}
+
+/// Reduces `simplex` to its feature nearest the origin and returns the nearest point, or
+/// `None` if a tetrahedron encloses the origin.
+fn closest_on_simplex(simplex: &mut Vec<Vec3>) -> Option<Vec3> {
+    let mut dir = Vec3::ZERO;
+    match simplex.len() {
+        2 => {
+            handle_line(simplex, &mut dir);
+        }
+        3 => {
+            handle_triangle(simplex, &mut dir);
+        }
+        4 => {
+            // The newest point is last, so only faces through it can be nearer than the
+            // previous triangle.
+            let (a, b, c, d) = (simplex[3], simplex[2], simplex[1], simplex[0]);
+            let faces = [(a, b, c, d), (a, c, d, b), (a, d, b, c)];
+            let nearest = faces
+                .into_iter()
+                .filter_map(|(a, b, c, opposite)| face_outside(a, b, c, opposite, -a))
+                .map(|(mut face, _)| {
+                    let mut face_dir = Vec3::ZERO;
+                    handle_triangle(&mut face, &mut face_dir);
+                    (face, face_dir)
+                })
+                .min_by(|(_, x), (_, y)| x.length_squared().total_cmp(&y.length_squared()))?;
+            *simplex = nearest.0;
+            dir = nearest.1;
+        }
+        _ => return simplex.first().copied(),
+    }
+    Some(-dir)
+}
+
 fn centers_coincident(a_transform: Mat4, b_transform: Mat4) -> bool {
     let a_center = a_transform.transform_point3(Vec3::ZERO);
     let b_center = b_transform.transform_point3(Vec3::ZERO);
@@ -27,6 +27,21 @@ impl Gravity {
         }
     }
 
+    /// Builds gravity from a full acceleration vector, e.g. `Vec3::X * 9.81` for sideways
+    /// gravity. A zero vector disables gravity but keeps the default down direction.
+    pub fn from_vector(gravity: Vec3) -> Self {
+        let mut result = Self::default();
+        result.set_vector(gravity);
+        result
+    }
+
+    pub fn set_vector(&mut self, gravity: Vec3) {
+        self.gravity_magnitude = gravity.length();
+        if let Some(normal) = gravity.try_normalize() {
+            self.gravity_normal = normal;
+        }
+    }
+
     #[allow(dead_code)]
     pub fn rotate_gravity(&mut self, rotation: Quat) {
         self.gravity_normal = (rotation * self.gravity_normal).normalize();
@@ -104,4 +119,16 @@ mod tests {
         assert_approx_eq!(gravity.gravity_normal.y, right.y, 1e-5);
         assert_approx_eq!(gravity.gravity_normal.z, right.z, 1e-5);
     }
+
+    #[test]
+    fn gravity_from_vector_splits_direction_and_magnitude() {
+        let gravity = Gravity::from_vector(Vec3::new(3.0, 0.0, 4.0));
+        assert_approx_eq!(gravity.gravity_magnitude, 5.0, 1e-6);
+        assert_approx_eq!(gravity.gravity_normal.x, 0.6, 1e-6);
+        assert_approx_eq!(gravity.gravity_normal.z, 0.8, 1e-6);
+
+        let zero = Gravity::from_vector(Vec3::ZERO);
+        assert_eq!(zero.gravity_vector(), Vec3::ZERO);
+        assert_eq!(zero.gravity_normal, Gravity::default().gravity_normal);
+    }
 }
@@ -0,0 +1,73 @@
+use bevy_ecs::prelude::*;
+use glam::Vec3;
+
+use crate::{
+    components::grounded_state_component::GroundedStateComponent,
+    physics::{gravity_resource::Gravity, physics_resource::CollisionFrameData},
+    time_resource::TimeResource,
+};
+
+pub struct GroundedSystem;
+
+impl GroundedSystem {
+    /// Updates every `GroundedStateComponent` from this step's contact manifolds.
+    /// An entity is grounded when one of its contacts pushes it up against gravity steeply enough.
+    pub fn update_grounded_state(
+        mut query: Query<(Entity, &mut GroundedStateComponent)>,
+        collision_frame_data: Res<CollisionFrameData>,
+        gravity: Res<Gravity>,
+        time: Res<TimeResource>,
+    ) {
+        let delta_time = time.simulation_fixed_dt().as_secs_f32();
+        let up = gravity.up();
+        for (entity, mut grounded_state) in query.iter_mut() {
+            let grounded = collision_frame_data.manifolds.iter().any(|entry| {
+                // Manifold normals point from A to B, so A is pushed along -normal.
+                let push_direction = if entry.entity_a == entity {
+                    -entry.manifold.normal
+                } else if entry.entity_b == entity {
+                    entry.manifold.normal
+                } else {
+                    return false;
+                };
+                is_ground_normal(push_direction, up, grounded_state.min_ground_normal_dot)
+            });
+            grounded_state.update(grounded, delta_time);
+        }
+    }
+}
+
+fn is_ground_normal(push_direction: Vec3, up: Vec3, min_ground_normal_dot: f32) -> bool {
+    push_direction.normalize_or_zero().dot(up) >= min_ground_normal_dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timer_resets_while_grounded_and_increments_after() {
+        let mut state = GroundedStateComponent::default();
+
+        state.update(true, 0.1);
+        state.update(true, 0.1);
+        assert!(state.is_grounded);
+        assert_eq!(state.time_since_grounded, 0.0);
+
+        state.update(false, 0.1);
+        state.update(false, 0.1);
+        assert!(!state.is_grounded);
+        assert!((state.time_since_grounded - 0.2).abs() < 1e-6);
+        assert!(state.is_within_coyote_time(0.25));
+        assert!(!state.is_within_coyote_time(0.15));
+    }
+
+    #[test]
+    fn only_upward_contacts_count_as_ground() {
+        let up = Vec3::Y;
+        assert!(is_ground_normal(Vec3::Y, up, 0.7));
+        assert!(is_ground_normal(Vec3::new(0.3, 1.0, 0.0), up, 0.7));
+        assert!(!is_ground_normal(Vec3::X, up, 0.7));
+        assert!(!is_ground_normal(-Vec3::Y, up, 0.7));
+    }
+}
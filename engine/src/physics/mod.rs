@@ -1,10 +1,14 @@
+pub mod broadphase;
 pub mod collision_system;
 pub mod dynamic_aabb_tree;
 pub mod epa;
+pub mod force_field_system;
 pub mod gjk;
 pub mod gravity_resource;
+pub mod grounded_system;
 pub mod movement_system;
 pub mod physics_event;
 pub mod physics_event_dispatcher;
 pub mod physics_resource;
 pub mod physics_system;
+pub mod spatial_hash_broadphase;
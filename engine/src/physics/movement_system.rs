@@ -3,7 +3,9 @@ use glam::{Quat, Vec3};
 
 use crate::{
     components::{
-        physics_component::PhysicsComponent, transform_component::TransformComponent,
+        force_accumulator_component::ForceAccumulatorComponent,
+        physics_component::{PhysicsComponent, PhysicsType},
+        transform_component::TransformComponent,
         velocity_component::VelocityComponent,
     },
     time_resource::TimeResource,
@@ -13,9 +15,27 @@ pub struct MovementSystem {}
 impl MovementSystem {
     pub fn update(
         mut query: Query<(&mut TransformComponent, &VelocityComponent), Without<PhysicsComponent>>,
+        mut forced: Query<(
+            &mut ForceAccumulatorComponent,
+            &mut VelocityComponent,
+            &PhysicsComponent,
+        )>,
         time: Res<TimeResource>,
     ) {
         let delta_time = time.simulation_fixed_dt().as_secs_f32();
+
+        // Accumulated forces become velocity changes here, ahead of collision and solving.
+        // Only the field force is per step; the rest stays until `clear_force_accumulators`.
+        for (mut accumulator, mut velocity, physics) in forced.iter_mut() {
+            if matches!(physics.physics_type, PhysicsType::Dynamic) && physics.mass > 0.0 {
+                let force = accumulator.force + accumulator.field_force;
+                velocity.translational += force / physics.mass * delta_time;
+                velocity.angular +=
+                    physics.local_inertia.inverse_or_zero() * accumulator.torque * delta_time;
+            }
+            accumulator.field_force = Vec3::ZERO;
+        }
+
         for (mut transform, velocity) in query.iter_mut() {
             // Update position based on translational velocity
             transform.position =
@@ -30,6 +50,13 @@ impl MovementSystem {
         }
     }
 
+    /// Clears every force accumulator. Runs once per frame, after all physics steps.
+    pub fn clear_force_accumulators(mut accumulators: Query<&mut ForceAccumulatorComponent>) {
+        for mut accumulator in accumulators.iter_mut() {
+            accumulator.clear();
+        }
+    }
+
     pub fn apply_rotation(rotation: &Quat, angular_velocity: &Vec3, delta_time: f32) -> Quat {
         let angular_velocity_magnitude = angular_velocity.length();
         if angular_velocity_magnitude == 0.0 {
@@ -52,6 +79,8 @@ mod tests {
     use std::f32::consts::PI;
 
     use assert_approx_eq::assert_approx_eq;
+    use bevy_ecs::system::RunSystemOnce;
+    use glam::Mat3;
 
     use super::*;
 
@@ -91,4 +120,60 @@ mod tests {
         let new_rotation = MovementSystem::apply_rotation(&rotation, &angular_velocity, DELTA_TIME);
         assert_approx_eq!(new_rotation.z, 1.0 * DELTA_TIME, 1e-6);
     }
+
+    #[test]
+    fn force_applies_on_every_step_until_the_frame_clears_it() {
+        let mut world = World::new();
+        world.insert_resource(TimeResource::new(60, 120));
+        let body = world
+            .spawn((
+                VelocityComponent::default(),
+                ForceAccumulatorComponent::default(),
+                PhysicsComponent {
+                    physics_type: PhysicsType::Dynamic,
+                    mass: 2.0,
+                    friction: 0.0,
+                    rolling_friction: 0.0,
+                    drag_coefficient: 0.0,
+                    angular_drag_coefficient: 0.0,
+                    restitution: 0.0,
+                    local_inertia: Mat3::IDENTITY,
+                },
+            ))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(MovementSystem::update);
+        let force = Vec3::new(3.0, 0.0, -1.0);
+        let steps = 12;
+        // Added once, as game code does per frame, then several steps run within the frame
+        world
+            .get_mut::<ForceAccumulatorComponent>(body)
+            .unwrap()
+            .force += force;
+        for _ in 0..steps {
+            schedule.run(&mut world);
+        }
+        assert_eq!(
+            world.get::<ForceAccumulatorComponent>(body).unwrap().force,
+            force
+        );
+        world
+            .run_system_once(MovementSystem::clear_force_accumulators)
+            .unwrap();
+
+        let delta_time = world
+            .resource::<TimeResource>()
+            .simulation_fixed_dt()
+            .as_secs_f32();
+        let expected = force / 2.0 * delta_time * steps as f32;
+        let velocity = world.get::<VelocityComponent>(body).unwrap().translational;
+        assert_approx_eq!(velocity.x, expected.x, 1e-5);
+        assert_approx_eq!(velocity.y, expected.y, 1e-5);
+        assert_approx_eq!(velocity.z, expected.z, 1e-5);
+        assert_eq!(
+            *world.get::<ForceAccumulatorComponent>(body).unwrap(),
+            ForceAccumulatorComponent::default()
+        );
+    }
 }
@@ -10,6 +10,13 @@ pub struct PhysicsEvent {
     pub other_entity: Entity,
 }
 
+/// Triggered once when a body with a `PhysicsEventListenerComponent` goes from awake to
+/// sleeping, i.e. when the simulation has settled around it.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct SettledEvent {
+    pub entity: Entity,
+}
+
 #[derive(Debug, Clone)]
 pub struct PhysicsEventInfo {
     pub normal: glam::Vec3,
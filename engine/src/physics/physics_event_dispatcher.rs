@@ -1,10 +1,15 @@
 use bevy_ecs::prelude::*;
+use std::collections::HashSet;
 
 use crate::{
-    components::physics_event_listener_component::PhysicsEventListenerComponent,
+    components::{
+        collider_component::{CollisionLayer, ConvexCollider, MeshCollider},
+        physics_event_listener_component::PhysicsEventListenerComponent,
+        sleep_component::SleepComponent,
+    },
     physics::{
         collision_system::ordered_pair,
-        physics_event::{PhysicsEvent, PhysicsEventInfo, PhysicsEventType},
+        physics_event::{PhysicsEvent, PhysicsEventInfo, PhysicsEventType, SettledEvent},
         physics_resource::CollisionFrameData,
     },
 };
@@ -14,10 +19,12 @@ pub struct PhysicsEventDispatcher;
 impl PhysicsEventDispatcher {}
 
 /// We only dispatch collision events for entities that have a PhysicsEventListenerComponent
-/// with listen=true to avoid unnecessary event generation for entities that don't care about physics
-/// events. This will help optimize performance by reducing the number of events we need to create and process each frame.
+/// whose layer mask includes the other collider's layer, to avoid unnecessary event generation for entities
+/// that don't care about physics events. This will help optimize performance by reducing the number of events
+/// we need to create and process each frame.
 pub fn dispatch_physics_events(
     query: Query<&PhysicsEventListenerComponent>,
+    colliders: Query<(Option<&ConvexCollider>, Option<&MeshCollider>)>,
     mut commands: Commands,
     collision_frame_data: Res<CollisionFrameData>,
 ) {
@@ -29,7 +36,12 @@ pub fn dispatch_physics_events(
             PhysicsEventType::Hit
         };
 
-        if query.get(manifold_entry.entity_a).is_ok() {
+        let layer_a = collider_layer(&colliders, manifold_entry.entity_a);
+        let layer_b = collider_layer(&colliders, manifold_entry.entity_b);
+
+        if let Ok(listener) = query.get(manifold_entry.entity_a)
+            && layer_b.is_none_or(|layer| listener.accepts(layer))
+        {
             let event_a = PhysicsEvent {
                 entity: manifold_entry.entity_a,
                 event_type,
@@ -45,7 +57,9 @@ pub fn dispatch_physics_events(
             commands.trigger(event_a);
         }
 
-        if query.get(manifold_entry.entity_b).is_ok() {
+        if let Ok(listener) = query.get(manifold_entry.entity_b)
+            && layer_a.is_none_or(|layer| listener.accepts(layer))
+        {
             let event_b = PhysicsEvent {
                 entity: manifold_entry.entity_b,
                 event_type,
@@ -62,3 +76,195 @@ pub fn dispatch_physics_events(
         }
     }
 }
+
+type SleepChangedListenerQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static SleepComponent),
+    (Changed<SleepComponent>, With<PhysicsEventListenerComponent>),
+>;
+
+/// Triggers a `SettledEvent` for listening bodies that fell asleep since the last run.
+/// Bodies spawned already asleep never fire, only awake-to-asleep transitions do.
+pub fn dispatch_settled_events(
+    query: SleepChangedListenerQuery,
+    mut removed_sleep: RemovedComponents<SleepComponent>,
+    mut removed_listeners: RemovedComponents<PhysicsEventListenerComponent>,
+    mut commands: Commands,
+    mut awake: Local<HashSet<Entity>>,
+) {
+    // Despawned bodies and ones that stopped listening are no longer tracked
+    for entity in removed_sleep.read().chain(removed_listeners.read()) {
+        awake.remove(&entity);
+    }
+    for (entity, sleep) in &query {
+        if !sleep.is_sleeping {
+            awake.insert(entity);
+        } else if awake.remove(&entity) {
+            commands.trigger(SettledEvent { entity });
+        }
+    }
+}
+
+fn collider_layer(
+    colliders: &Query<(Option<&ConvexCollider>, Option<&MeshCollider>)>,
+    entity: Entity,
+) -> Option<CollisionLayer> {
+    match colliders.get(entity) {
+        Ok((Some(convex), _)) => Some(convex.layer),
+        Ok((None, Some(mesh))) => Some(mesh.layer),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::system::RunSystemOnce;
+    use glam::Vec3;
+
+    use super::*;
+    use crate::physics::physics_resource::ContactManifold;
+
+    #[derive(Resource, Default)]
+    struct ReceivedEvents(Vec<Entity>);
+
+    fn manifold() -> ContactManifold {
+        ContactManifold {
+            contacts: Vec::new(),
+            normal: Vec3::Y,
+            relative_normal_speed: 0.0,
+            impact_impulse: 0.0,
+            impact_energy: 0.0,
+        }
+    }
+
+    #[test]
+    fn events_from_excluded_layers_are_not_dispatched() {
+        let mut world = World::new();
+        world.init_resource::<ReceivedEvents>();
+        world.add_observer(
+            |event: On<PhysicsEvent>, mut received: ResMut<ReceivedEvents>| {
+                received.0.push(event.other_entity);
+            },
+        );
+
+        let player = world
+            .spawn((
+                ConvexCollider::sphere(0.5, CollisionLayer::Player),
+                PhysicsEventListenerComponent::with_layers(&[CollisionLayer::Enemy]),
+            ))
+            .id();
+        let enemy = world
+            .spawn(ConvexCollider::sphere(0.5, CollisionLayer::Enemy))
+            .id();
+        let wall = world
+            .spawn(ConvexCollider::cube(1.0, CollisionLayer::Environment))
+            .id();
+
+        let mut frame = CollisionFrameData::default();
+        frame
+            .manifolds
+            .push(ordered_pair(player, enemy), manifold());
+        frame.manifolds.push(ordered_pair(player, wall), manifold());
+        world.insert_resource(frame);
+
+        world.run_system_once(dispatch_physics_events).unwrap();
+
+        assert_eq!(world.resource::<ReceivedEvents>().0, vec![enemy]);
+    }
+
+    #[test]
+    fn dropped_box_settles_exactly_once() {
+        use crate::{
+            TransformComponent, VelocityComponent,
+            assets::mesh_resource::MeshResource,
+            components::physics_component::{PhysicsComponent, PhysicsType},
+            physics::{
+                collision_system::CollisionSystem,
+                gravity_resource::Gravity,
+                physics_resource::{PhysicsFrameData, PhysicsResource, PreSolveHooks},
+                physics_system::PhysicsSystem,
+            },
+            render::render_body_resource::RenderBodyResource,
+            time_resource::TimeResource,
+        };
+
+        let mut world = World::new();
+        world.insert_resource(RenderBodyResource::default());
+        world.insert_resource(MeshResource::default());
+        world.insert_resource(PhysicsResource::default());
+        world.insert_resource(CollisionFrameData::default());
+        world.insert_resource(PhysicsFrameData::default());
+        world.insert_resource(PreSolveHooks::default());
+        world.insert_resource(TimeResource::new(60, 120));
+        world.insert_resource(Gravity::default());
+        world.init_resource::<ReceivedEvents>();
+        world.add_observer(
+            |event: On<SettledEvent>, mut received: ResMut<ReceivedEvents>| {
+                received.0.push(event.entity);
+            },
+        );
+
+        let physics = PhysicsComponent {
+            physics_type: PhysicsType::Dynamic,
+            mass: 1.0,
+            friction: 0.5,
+            rolling_friction: 0.0,
+            drag_coefficient: 0.1,
+            angular_drag_coefficient: 0.2,
+            restitution: 0.0,
+            local_inertia: glam::Mat3::IDENTITY,
+        };
+        world.spawn((
+            TransformComponent {
+                position: Vec3::new(0.0, 0.0, -0.5),
+                ..Default::default()
+            },
+            ConvexCollider::cuboid(Vec3::new(10.0, 10.0, 1.0), CollisionLayer::Environment),
+            PhysicsComponent {
+                physics_type: PhysicsType::Static,
+                ..physics
+            },
+        ));
+        let falling_box = world
+            .spawn((
+                TransformComponent {
+                    position: Vec3::new(0.0, 0.0, 0.75),
+                    ..Default::default()
+                },
+                VelocityComponent::default(),
+                ConvexCollider::cube(1.0, CollisionLayer::Default),
+                physics,
+                // Gravity alone adds ~0.08 m/s per 120 Hz step, so the default threshold is too
+                // tight for a body resting under gravity.
+                SleepComponent {
+                    linear_threshold: 0.15,
+                    ..Default::default()
+                },
+                PhysicsEventListenerComponent::default(),
+            ))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                CollisionSystem::update_world_aabb_cache,
+                CollisionSystem::update_world_dynamic_tree,
+                CollisionSystem::generate_manifolds,
+                PhysicsSystem::wake_touched_bodies,
+                PhysicsSystem::physics_solver,
+                PhysicsSystem::damp_resting_spin,
+                PhysicsSystem::integrate_motion,
+                dispatch_settled_events,
+            )
+                .chain(),
+        );
+
+        for _ in 0..600 {
+            schedule.run(&mut world);
+        }
+
+        assert!(PhysicsSystem::all_bodies_asleep(&mut world));
+        assert_eq!(world.resource::<ReceivedEvents>().0, vec![falling_box]);
+    }
+}
@@ -1,14 +1,17 @@
 use bevy_ecs::prelude::*;
 use glam::Vec3;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, time::Duration};
 
 use crate::{
     assets::mesh::Aabb,
+    components::collider_component::CollisionLayer,
     physics::{self, collision_system::OrderedEntityPair},
 };
 use physics::{
+    broadphase::Broadphase,
     dynamic_aabb_tree::{DynamicAabbTree, NodeId},
-    physics_system::ContactConstraint,
+    physics_system::{CachedImpulse, ContactConstraint},
 };
 
 #[derive(Debug, Clone, Copy)]
@@ -29,17 +32,309 @@ pub struct ContactManifold {
     pub impact_energy: f32,
 }
 
-#[derive(Resource, Default)]
+/// Controls how new contacts are merged into the previous step's manifold.
+/// Higher normal thresholds keep more distinct contacts; lower ones merge more aggressively
+/// for steadier but coarser manifolds.
+/// The `*_max_contacts` caps bound how many contacts a pair keeps. Scenes with many stacked
+/// boxes can lower them to cut solver work; large flat contacts rest more evenly with higher
+/// caps. At least one contact is always kept.
+#[derive(Debug, Clone, Copy)]
+pub struct ContactMergeConfig {
+    pub convex_normal_cos_threshold: f32,
+    pub convex_max_contacts: usize,
+    pub mesh_normal_cos_threshold: f32,
+    pub mesh_max_contacts: usize,
+}
+
+impl Default for ContactMergeConfig {
+    fn default() -> Self {
+        Self {
+            convex_normal_cos_threshold: 0.95,
+            convex_max_contacts: 4,
+            mesh_normal_cos_threshold: 0.9,
+            mesh_max_contacts: 8,
+        }
+    }
+}
+
+/// Extra angular damping for bodies that are resting on something, so tiny residual spin dies
+/// out instead of keeping the body awake. A body counts as resting when it has at least
+/// `min_contacts` contacts and moves slower than `max_linear_speed`.
+#[derive(Debug, Clone, Copy)]
+pub struct RestingDampingConfig {
+    pub min_contacts: usize,
+    pub max_linear_speed: f32,
+    /// Exponential decay rate of the angular velocity, per second.
+    pub angular_damping: f32,
+}
+
+impl Default for RestingDampingConfig {
+    fn default() -> Self {
+        Self {
+            min_contacts: 2,
+            max_linear_speed: 0.1,
+            angular_damping: 5.0,
+        }
+    }
+}
+
+/// Acceptance tolerances for convex-vs-mesh contacts. A contact is rejected when the
+/// convex support point, projected onto the triangle plane, lands too far outside the
+/// triangle. Loosen these for thin walls that miss grazing hits; tighten them to cut
+/// phantom edge contacts.
+#[derive(Debug, Clone, Copy)]
+pub struct MeshContactConfig {
+    /// Largest distance a swept contact's projected point may lie outside its triangle.
+    pub swept_max_lateral_error: f32,
+    /// Discrete contacts allow `lateral_tolerance_scale * longest triangle edge +
+    /// lateral_tolerance_bias` of lateral error.
+    pub lateral_tolerance_scale: f32,
+    pub lateral_tolerance_bias: f32,
+    /// Most poses sampled along a fast-spinning body's rotation, which the translational
+    /// sweep does not follow. Raise it for very fast projectiles; one disables sampling.
+    pub swept_max_samples: usize,
+}
+
+impl Default for MeshContactConfig {
+    fn default() -> Self {
+        Self {
+            swept_max_lateral_error: 0.5,
+            lateral_tolerance_scale: 0.25,
+            lateral_tolerance_bias: 0.05,
+            swept_max_samples: 20,
+        }
+    }
+}
+
+/// Iteration settings for the contact solver. Warm starting seeds each contact with the
+/// impulse of the nearest contact from the previous step's manifold, so persistent contacts
+/// such as resting stacks start close to their solution instead of from zero.
+#[derive(Debug, Clone, Copy)]
+pub struct SolverConfig {
+    pub warm_starting: bool,
+    /// Largest distance between a new contact and a previous one for its impulse to carry over.
+    pub warm_start_distance: f32,
+    /// Iteration stops early once no constraint changes its impulse by more than this in a
+    /// full pass. Zero always runs every iteration.
+    pub convergence_tolerance: f32,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            warm_starting: false,
+            warm_start_distance: 0.05,
+            convergence_tolerance: 0.0,
+        }
+    }
+}
+
+pub const DEFAULT_RESTITUTION_VELOCITY_THRESHOLD: f32 = 0.1;
+
+#[derive(Resource)]
 pub struct PhysicsResource {
     pub world_aabbs: HashMap<Entity, Aabb>,
-    pub broadphase: DynamicAabbTree,
+    pub broadphase: Box<dyn Broadphase>,
     pub entity_node: HashMap<Entity, NodeId>,
+    pub contact_merge: ContactMergeConfig,
+    pub resting_damping: RestingDampingConfig,
+    pub mesh_contact: MeshContactConfig,
+    pub solver: SolverConfig,
+    /// Swept (continuous) convex-vs-mesh contacts only run for bodies whose per-step
+    /// displacement exceeds this fraction of their smallest world extent. Zero sweeps every
+    /// moving body.
+    pub ccd_speed_threshold: f32,
+    /// Impacts slower than this along the contact normal don't bounce, so resting bodies with
+    /// high restitution settle instead of micro-bouncing forever.
+    pub restitution_velocity_threshold: f32,
+    /// `CollisionSystem::rebuild_degraded_broadphase` rebuilds the broadphase once its
+    /// `max_height` goes above this. `None` leaves rebuilding to game code.
+    pub broadphase_rebuild_height: Option<i32>,
+}
+
+impl Default for PhysicsResource {
+    fn default() -> Self {
+        Self {
+            world_aabbs: HashMap::new(),
+            broadphase: Box::new(DynamicAabbTree::default()),
+            entity_node: HashMap::new(),
+            contact_merge: ContactMergeConfig::default(),
+            resting_damping: RestingDampingConfig::default(),
+            mesh_contact: MeshContactConfig::default(),
+            solver: SolverConfig::default(),
+            ccd_speed_threshold: 0.0,
+            restitution_velocity_threshold: DEFAULT_RESTITUTION_VELOCITY_THRESHOLD,
+            broadphase_rebuild_height: None,
+        }
+    }
+}
+
+impl PhysicsResource {
+    /// Uses `margin` to fatten broadphase leaves. Fast-moving scenes benefit from a larger
+    /// margin, precise slow scenes from a smaller one.
+    pub fn with_broadphase_margin(margin: f32) -> Self {
+        Self::with_broadphase(DynamicAabbTree::with_fat_margin(margin))
+    }
+
+    /// Uses `broadphase` instead of the default `DynamicAabbTree`, e.g. a
+    /// `SpatialHashBroadphase` for scenes of many similar-sized bodies. Must be chosen before
+    /// any colliders are added.
+    pub fn with_broadphase(broadphase: impl Broadphase + 'static) -> Self {
+        Self {
+            broadphase: Box::new(broadphase),
+            ..Default::default()
+        }
+    }
+
+    /// Rebuilds the broadphase in one go from the cached world AABBs. Intended as an initial
+    /// bake after spawning many static colliders, which would otherwise be inserted one by one.
+    pub fn bake_broadphase(&mut self) {
+        let mut leaves: Vec<(Entity, Aabb)> = self
+            .world_aabbs
+            .iter()
+            .map(|(entity, aabb)| (*entity, *aabb))
+            .collect();
+        leaves.sort_by_key(|(entity, _)| entity.to_bits());
+
+        self.entity_node = self.broadphase.rebuild_from(leaves).into_iter().collect();
+    }
+
+    /// Rebuilds the broadphase hierarchy from its current leaves, e.g. between levels or when
+    /// `Broadphase::total_area` has grown over a long session. Unlike `bake_broadphase`, leaf
+    /// ids are kept, so `entity_node` stays as it is.
+    pub fn rebuild_broadphase(&mut self) {
+        self.broadphase.rebuild();
+    }
+
+    /// Rebuilds the broadphase if it has grown taller than `broadphase_rebuild_height`.
+    /// Returns whether it was rebuilt.
+    pub fn rebuild_broadphase_if_degraded(&mut self) -> bool {
+        let degraded = self
+            .broadphase_rebuild_height
+            .is_some_and(|height| self.broadphase.max_height() > height);
+        if degraded {
+            self.rebuild_broadphase();
+        }
+        degraded
+    }
+
+    /// Returns every entity whose broadphase leaf overlaps `aabb`.
+    /// Results are broadphase-approximate: leaves are stored as fattened AABBs, so entities that
+    /// are slightly outside the query region can be included. Run a narrowphase test if exact
+    /// overlap matters.
+    pub fn query_aabb(&self, aabb: Aabb) -> Vec<Entity> {
+        let mut entities = Vec::new();
+        self.broadphase
+            .query(aabb, &mut |entity| entities.push(entity));
+        entities
+    }
+
+    /// Returns every entity whose cached world AABB is crossed by the segment from `start`
+    /// to `end`. This is a box-level test, not an exact hit against collider geometry.
+    pub fn query_segment(&self, start: Vec3, end: Vec3) -> Vec<Entity> {
+        let bounds = Aabb {
+            min: start.min(end),
+            max: start.max(end),
+        };
+        let mut entities = self.query_aabb(bounds);
+        entities.retain(|entity| {
+            self.world_aabbs
+                .get(entity)
+                .is_some_and(|aabb| aabb.intersects_segment(start, end))
+        });
+        entities
+    }
 }
 
 #[derive(Resource, Default)]
 pub struct PhysicsFrameData {
     pub constraints: Vec<ContactConstraint>,
     pub corrections: HashMap<Entity, Vec3>,
+    /// Final impulses of the last step's contacts, keyed by their `(entity_a, entity_b)`.
+    /// Only filled while warm starting is enabled.
+    pub warm_start: HashMap<(Entity, Entity), Vec<CachedImpulse>>,
+    /// Solver passes run in the last step.
+    pub solver_iterations: u32,
+}
+
+/// A touching pair as seen by a `PreSolveHooks` callback. `entity_a` is on the first layer
+/// the hook was registered for.
+#[derive(Debug, Clone, Copy)]
+pub struct PreSolveContact {
+    pub entity_a: Entity,
+    pub entity_b: Entity,
+    /// Manifold normal, pointing from A to B.
+    pub normal: Vec3,
+    pub velocity_a: Vec3,
+    pub velocity_b: Vec3,
+}
+
+impl PreSolveContact {
+    fn swapped(&self) -> Self {
+        Self {
+            entity_a: self.entity_b,
+            entity_b: self.entity_a,
+            normal: -self.normal,
+            velocity_a: self.velocity_b,
+            velocity_b: self.velocity_a,
+        }
+    }
+}
+
+pub type PreSolveHook = Box<dyn Fn(&PreSolveContact) -> bool + Send + Sync>;
+
+/// Callbacks that `PhysicsSystem::physics_solver` consults for every touching pair before
+/// solving it. A hook returning `false` skips resolution of the pair for that step, which is
+/// how one-way platforms and other custom responses are built. Manifolds and physics events
+/// are unaffected.
+#[derive(Resource, Default)]
+pub struct PreSolveHooks {
+    hooks: HashMap<(CollisionLayer, CollisionLayer), Vec<PreSolveHook>>,
+}
+
+impl PreSolveHooks {
+    /// Registers `hook` for contacts between `layer_a` and `layer_b`, in either order.
+    pub fn register<F>(&mut self, layer_a: CollisionLayer, layer_b: CollisionLayer, hook: F)
+    where
+        F: Fn(&PreSolveContact) -> bool + Send + Sync + 'static,
+    {
+        self.hooks
+            .entry((layer_a, layer_b))
+            .or_default()
+            .push(Box::new(hook));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Returns `false` if any hook registered for the two layers vetoes `contact`.
+    pub fn allows(
+        &self,
+        layer_a: CollisionLayer,
+        layer_b: CollisionLayer,
+        contact: &PreSolveContact,
+    ) -> bool {
+        let run = |key, contact: &PreSolveContact| {
+            self.hooks
+                .get(&key)
+                .is_none_or(|hooks| hooks.iter().all(|hook| hook(contact)))
+        };
+        if !run((layer_a, layer_b), contact) {
+            return false;
+        }
+        layer_a == layer_b || run((layer_b, layer_a), &contact.swapped())
+    }
+}
+
+/// Per-step collision counters, filled in by `CollisionSystem::generate_manifolds`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CollisionFrameStats {
+    pub candidate_pairs: usize,
+    pub manifolds: usize,
+    pub contacts: usize,
+    pub broadphase_time: Duration,
+    pub narrowphase_time: Duration,
 }
 
 #[derive(Resource, Default)]
@@ -48,6 +343,7 @@ pub struct CollisionFrameData {
     pub candidate_pairs: Vec<(Entity, Entity)>,
     pub manifolds: ManifoldVec,
     pub previous_manifolds: ManifoldVec,
+    pub stats: CollisionFrameStats,
 }
 
 pub struct ManifoldEntry {
@@ -100,6 +396,115 @@ impl CollisionFrameData {
         self.candidate_pairs.clear();
         self.previous_manifolds = std::mem::take(&mut self.manifolds);
         self.manifolds.clear();
+        self.stats = CollisionFrameStats::default();
+    }
+}
+
+/// Serializable dump of one frame's contacts, meant to be attached to bug reports so a
+/// problem scene can be reproduced without rebuilding it by hand.
+/// Entities are stored as their raw bits and vectors as `[x, y, z]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContactSnapshot {
+    pub delta_time: f32,
+    pub manifolds: Vec<ManifoldSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifoldSnapshot {
+    pub entity_a: u64,
+    pub entity_b: u64,
+    pub normal: [f32; 3],
+    pub relative_normal_speed: f32,
+    pub impact_impulse: f32,
+    pub impact_energy: f32,
+    pub contacts: Vec<ContactRecord>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ContactRecord {
+    pub entity_a: u64,
+    pub entity_b: u64,
+    pub normal: [f32; 3],
+    pub penetration: f32,
+    pub contact_point: [f32; 3],
+}
+
+impl From<&Contact> for ContactRecord {
+    fn from(contact: &Contact) -> Self {
+        Self {
+            entity_a: contact.entity_a.to_bits(),
+            entity_b: contact.entity_b.to_bits(),
+            normal: contact.normal.to_array(),
+            penetration: contact.penetration,
+            contact_point: contact.contact_point.to_array(),
+        }
+    }
+}
+
+impl From<&ContactRecord> for Contact {
+    fn from(record: &ContactRecord) -> Self {
+        Self {
+            entity_a: Entity::from_bits(record.entity_a),
+            entity_b: Entity::from_bits(record.entity_b),
+            normal: Vec3::from_array(record.normal),
+            penetration: record.penetration,
+            contact_point: Vec3::from_array(record.contact_point),
+        }
+    }
+}
+
+impl ContactSnapshot {
+    /// Rebuilds frame data holding the recorded manifolds, ready to feed to the solver.
+    pub fn to_frame_data(&self) -> CollisionFrameData {
+        let mut frame = CollisionFrameData {
+            delta_time: self.delta_time,
+            ..Default::default()
+        };
+        for entry in &self.manifolds {
+            let pair = (
+                Entity::from_bits(entry.entity_a),
+                Entity::from_bits(entry.entity_b),
+            );
+            frame.candidate_pairs.push(pair);
+            frame.manifolds.push(
+                pair,
+                ContactManifold {
+                    contacts: entry.contacts.iter().map(Contact::from).collect(),
+                    normal: Vec3::from_array(entry.normal),
+                    relative_normal_speed: entry.relative_normal_speed,
+                    impact_impulse: entry.impact_impulse,
+                    impact_energy: entry.impact_energy,
+                },
+            );
+        }
+        frame
+    }
+}
+
+impl CollisionFrameData {
+    /// Copies every manifold generated this frame into a [`ContactSnapshot`].
+    pub fn snapshot(&self) -> ContactSnapshot {
+        ContactSnapshot {
+            delta_time: self.delta_time,
+            manifolds: self
+                .manifolds
+                .iter()
+                .map(|entry| ManifoldSnapshot {
+                    entity_a: entry.entity_a.to_bits(),
+                    entity_b: entry.entity_b.to_bits(),
+                    normal: entry.manifold.normal.to_array(),
+                    relative_normal_speed: entry.manifold.relative_normal_speed,
+                    impact_impulse: entry.manifold.impact_impulse,
+                    impact_energy: entry.manifold.impact_energy,
+                    contacts: entry
+                        .manifold
+                        .contacts
+                        .iter()
+                        .map(ContactRecord::from)
+                        .collect(),
+                })
+                .collect(),
+        }
     }
 }
 
@@ -108,3 +513,236 @@ impl PhysicsFrameData {
         self.constraints.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::*;
+
+    fn make_aabb(center: Vec3, half_extent: f32) -> Aabb {
+        Aabb {
+            min: center - Vec3::splat(half_extent),
+            max: center + Vec3::splat(half_extent),
+        }
+    }
+
+    #[test]
+    fn query_aabb_returns_entities_with_overlapping_fat_leaves() {
+        let mut physics = PhysicsResource::default();
+        let near = Entity::from_bits(1);
+        let grazing = Entity::from_bits(2);
+        let far = Entity::from_bits(3);
+        for (entity, center) in [
+            (near, Vec3::ZERO),
+            (grazing, Vec3::new(2.05, 0.0, 0.0)),
+            (far, Vec3::new(10.0, 0.0, 0.0)),
+        ] {
+            let node = physics
+                .broadphase
+                .allocate_leaf(entity, make_aabb(center, 0.5));
+            physics.entity_node.insert(entity, node);
+        }
+
+        // Misses the tight AABB of `grazing` (min x = 1.55) but hits its fattened leaf.
+        let mut hits = physics.query_aabb(make_aabb(Vec3::new(1.0, 0.0, 0.0), 0.5));
+        hits.sort();
+        assert_eq!(hits, vec![near, grazing]);
+
+        assert!(
+            physics
+                .query_aabb(make_aabb(Vec3::new(0.0, 20.0, 0.0), 0.5))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn bake_broadphase_maps_every_cached_entity_to_its_leaf() {
+        let mut physics = PhysicsResource::default();
+        for i in 0..20u64 {
+            let center = Vec3::new(i as f32 * 3.0, 0.0, 0.0);
+            physics
+                .world_aabbs
+                .insert(Entity::from_bits(i + 1), make_aabb(center, 0.5));
+        }
+
+        physics.bake_broadphase();
+
+        assert_eq!(physics.broadphase.validate(), Ok(()));
+        assert_eq!(physics.entity_node.len(), 20);
+        assert_eq!(
+            physics.query_aabb(make_aabb(Vec3::new(9.0, 0.0, 0.0), 0.1)),
+            vec![Entity::from_bits(4)]
+        );
+    }
+
+    #[test]
+    fn query_segment_returns_only_boxes_the_segment_crosses() {
+        let mut physics = PhysicsResource::default();
+        let wall = Entity::from_bits(1);
+        let beside = Entity::from_bits(2);
+        physics.world_aabbs.insert(
+            wall,
+            Aabb {
+                min: Vec3::new(-0.1, -2.0, -2.0),
+                max: Vec3::new(0.1, 2.0, 2.0),
+            },
+        );
+        // Inside the segment's bounding box, but off the line itself
+        physics
+            .world_aabbs
+            .insert(beside, make_aabb(Vec3::new(1.0, 0.2, 0.0), 0.25));
+        physics.bake_broadphase();
+
+        let start = Vec3::new(-3.0, 0.0, 0.0);
+        let end = Vec3::new(3.0, 2.0, 0.0);
+        assert_eq!(physics.query_segment(start, end), vec![wall]);
+        assert!(
+            physics
+                .query_segment(Vec3::new(-3.0, 5.0, 0.0), Vec3::new(3.0, 5.0, 0.0))
+                .is_empty()
+        );
+    }
+
+    /// Reports a fixed height and counts rebuild requests.
+    struct FixedHeightBroadphase {
+        height: i32,
+        rebuilds: Arc<AtomicUsize>,
+    }
+
+    impl Broadphase for FixedHeightBroadphase {
+        fn allocate_leaf(&mut self, _entity: Entity, _aabb: Aabb) -> NodeId {
+            NodeId::MIN
+        }
+
+        fn update(&mut self, _leaf: NodeId, _aabb: Aabb) {}
+
+        fn remove(&mut self, _leaf: NodeId) {}
+
+        fn query(&self, _aabb: Aabb, _callback: &mut dyn FnMut(Entity)) {}
+
+        fn rebuild_from(&mut self, _leaves: Vec<(Entity, Aabb)>) -> Vec<(Entity, NodeId)> {
+            Vec::new()
+        }
+
+        fn max_height(&self) -> i32 {
+            self.height
+        }
+
+        fn rebuild(&mut self) {
+            self.rebuilds.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn broadphase_is_rebuilt_only_above_the_height_bound() {
+        let rebuilds = Arc::new(AtomicUsize::new(0));
+        let mut physics = PhysicsResource::with_broadphase(FixedHeightBroadphase {
+            height: 12,
+            rebuilds: rebuilds.clone(),
+        });
+
+        assert!(!physics.rebuild_broadphase_if_degraded());
+        physics.broadphase_rebuild_height = Some(12);
+        assert!(!physics.rebuild_broadphase_if_degraded());
+        assert_eq!(rebuilds.load(Ordering::Relaxed), 0);
+
+        physics.broadphase_rebuild_height = Some(11);
+        assert!(physics.rebuild_broadphase_if_degraded());
+        assert_eq!(rebuilds.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn rebuilding_the_broadphase_keeps_entity_leaves_valid() {
+        let mut physics = PhysicsResource::default();
+        for i in 0..32u64 {
+            let entity = Entity::from_bits(i + 1);
+            let node = physics
+                .broadphase
+                .allocate_leaf(entity, make_aabb(Vec3::new(i as f32 * 3.0, 0.0, 0.0), 0.5));
+            physics.entity_node.insert(entity, node);
+        }
+
+        physics.rebuild_broadphase();
+
+        assert_eq!(physics.broadphase.validate(), Ok(()));
+        let moved = Entity::from_bits(5);
+        physics.broadphase.update(
+            physics.entity_node[&moved],
+            make_aabb(Vec3::new(0.0, 50.0, 0.0), 0.5),
+        );
+        assert_eq!(physics.broadphase.validate(), Ok(()));
+        assert_eq!(
+            physics.query_aabb(make_aabb(Vec3::new(0.0, 50.0, 0.0), 0.1)),
+            vec![moved]
+        );
+    }
+
+    /// Parses a snapshot the way a bug report attachment would be read back.
+    fn load(text: &str) -> ContactSnapshot {
+        toml::from_str(text).expect("snapshot should deserialize")
+    }
+
+    #[test]
+    fn contact_snapshot_round_trips_every_contact_field() {
+        let a = Entity::from_bits(7);
+        let b = Entity::from_bits(9);
+        let mut frame = CollisionFrameData {
+            delta_time: 1.0 / 60.0,
+            ..Default::default()
+        };
+        frame.manifolds.push(
+            (a, b),
+            ContactManifold {
+                contacts: vec![
+                    Contact {
+                        entity_a: a,
+                        entity_b: b,
+                        normal: Vec3::new(0.0, 1.0, 0.0),
+                        penetration: 0.013,
+                        contact_point: Vec3::new(1.25, -0.1, 3.7),
+                    },
+                    Contact {
+                        entity_a: a,
+                        entity_b: b,
+                        normal: Vec3::new(0.6, 0.8, 0.0),
+                        penetration: 0.002,
+                        contact_point: Vec3::new(-4.0, 0.3, 0.1),
+                    },
+                ],
+                normal: Vec3::new(0.3, 0.9, 0.1),
+                relative_normal_speed: -2.5,
+                impact_impulse: 0.75,
+                impact_energy: 1.5,
+            },
+        );
+
+        let snapshot = frame.snapshot();
+        let text = toml::to_string(&snapshot).expect("snapshot should serialize");
+        let loaded = load(&text);
+        assert_eq!(loaded, snapshot);
+
+        let replayed = loaded.to_frame_data();
+        assert_eq!(replayed.delta_time, frame.delta_time);
+        let original = frame.manifolds.get((a, b)).unwrap();
+        let restored = replayed.manifolds.get((a, b)).unwrap();
+        assert_eq!(restored.normal, original.normal);
+        assert_eq!(
+            restored.relative_normal_speed,
+            original.relative_normal_speed
+        );
+        assert_eq!(restored.impact_impulse, original.impact_impulse);
+        assert_eq!(restored.impact_energy, original.impact_energy);
+        assert_eq!(restored.contacts.len(), original.contacts.len());
+        for (restored, original) in restored.contacts.iter().zip(&original.contacts) {
+            assert_eq!(restored.entity_a, original.entity_a);
+            assert_eq!(restored.entity_b, original.entity_b);
+            assert_eq!(restored.normal, original.normal);
+            assert_eq!(restored.penetration, original.penetration);
+            assert_eq!(restored.contact_point, original.contact_point);
+        }
+    }
+}
@@ -1,44 +1,94 @@
-use bevy_ecs::prelude::*;
+use bevy_ecs::{prelude::*, system::SystemParam};
 use glam::{Mat3, Vec3};
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     components::{
-        physics_component::PhysicsComponent, sleep_component::SleepComponent,
-        transform_component::TransformComponent, velocity_component::VelocityComponent,
+        collider_component::{CollisionLayer, ConvexCollider, MeshCollider},
+        locked_axes_component::LockedAxes,
+        physics_component::PhysicsComponent,
+        sleep_component::SleepComponent,
+        surface_velocity_component::SurfaceVelocityComponent,
+        transform_component::TransformComponent,
+        velocity_component::VelocityComponent,
     },
     physics::{
         gravity_resource::Gravity,
         movement_system::MovementSystem,
-        physics_resource::{CollisionFrameData, ContactManifold, PhysicsFrameData},
+        physics_resource::{
+            CollisionFrameData, ContactManifold, ManifoldEntry, PhysicsFrameData, PhysicsResource,
+            PreSolveContact, PreSolveHooks,
+        },
     },
     time_resource::TimeResource,
 };
 pub struct PhysicsSystem {}
 
+/// Everything `PhysicsSystem::physics_solver` only reads: the contacts to solve, the
+/// components that adjust them and the solver settings.
+#[derive(SystemParam)]
+pub struct SolverContext<'w, 's> {
+    sleepers: Query<'w, 's, &'static SleepComponent>,
+    colliders: Query<
+        'w,
+        's,
+        (
+            Option<&'static ConvexCollider>,
+            Option<&'static MeshCollider>,
+        ),
+    >,
+    surfaces: Query<'w, 's, &'static SurfaceVelocityComponent>,
+    collision_frame_data: Res<'w, CollisionFrameData>,
+    physics_world: Res<'w, PhysicsResource>,
+    pre_solve_hooks: Res<'w, PreSolveHooks>,
+    gravity: Res<'w, Gravity>,
+    time: Res<'w, TimeResource>,
+}
+
 pub struct ContactConstraint {
     entity_a: Entity,
     entity_b: Entity,
     normal: Vec3,
     penetration: f32,
-    accumulated_tangent_lambda: f32,
+    accumulated_tangent_impulse: Vec3,
     accumulated_normal_lambda: f32,
     contact_point: Vec3, // world-space contact
+    warm_started: bool,
+    /// Surface velocity of B relative to A's, which friction drives the tangential relative
+    /// velocity towards instead of zero.
+    surface_velocity: Vec3,
+}
+
+/// A contact's accumulated impulse at the end of a solver step, used to warm start the
+/// nearest contact of the same pair in the next step.
+#[derive(Debug, Clone, Copy)]
+pub struct CachedImpulse {
+    contact_point: Vec3,
+    normal_lambda: f32,
+    tangent_impulse: Vec3,
 }
 
+type IntegratedBodyQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut TransformComponent,
+        &'static mut VelocityComponent,
+        &'static PhysicsComponent,
+        Option<&'static mut SleepComponent>,
+        Option<&'static LockedAxes>,
+    ),
+>;
+
 impl PhysicsSystem {
     pub fn integrate_motion(
-        mut query: Query<(
-            &mut TransformComponent,
-            &mut VelocityComponent,
-            &PhysicsComponent,
-            Option<&mut SleepComponent>,
-        )>,
+        mut query: IntegratedBodyQuery,
         time: Res<TimeResource>,
         gravity: Res<Gravity>,
     ) {
         let delta_time = time.simulation_fixed_dt().as_secs_f32();
         let g = gravity.gravity_vector();
-        for (mut transform, mut velocity, physics, mut sleep) in query.iter_mut() {
+        for (mut transform, mut velocity, physics, mut sleep, locks) in query.iter_mut() {
             if !matches!(
                 physics.physics_type,
                 crate::components::physics_component::PhysicsType::Dynamic
@@ -46,15 +96,36 @@ impl PhysicsSystem {
                 continue;
             }
 
+            // Sleeping bodies are skipped entirely so their transform isn't marked as changed,
+            // which keeps them out of the broadphase and narrowphase until something wakes them.
             if let Some(sleep) = sleep.as_deref_mut()
                 && sleep.is_sleeping
             {
-                velocity.translational = Vec3::ZERO;
-                velocity.angular = Vec3::ZERO;
-                continue;
+                if velocity.translational.length() < sleep.linear_threshold
+                    && velocity.angular.length() < sleep.angular_threshold
+                {
+                    continue;
+                }
+                // Something pushed the body from outside the solver, e.g. a gameplay impulse.
+                sleep.is_sleeping = false;
+                sleep.sleep_timer = 0.0;
             }
 
-            Self::update_body(&mut transform, &mut velocity, physics, delta_time, g);
+            let body_gravity = match locks {
+                Some(locks) => {
+                    locks.apply(&mut velocity);
+                    Vec3::select(locks.translation, Vec3::ZERO, g)
+                }
+                None => g,
+            };
+
+            Self::update_body(
+                &mut transform,
+                &mut velocity,
+                physics,
+                delta_time,
+                body_gravity,
+            );
 
             if let Some(sleep) = sleep.as_deref_mut() {
                 let linear_speed = velocity.translational.length();
@@ -74,6 +145,83 @@ impl PhysicsSystem {
         }
     }
 
+    /// True when every body with a `SleepComponent` is asleep. Handy for headless runs that
+    /// need to know when the simulation has quiesced.
+    pub fn all_bodies_asleep(world: &mut World) -> bool {
+        world
+            .query::<&SleepComponent>()
+            .iter(world)
+            .all(|sleep| sleep.is_sleeping)
+    }
+
+    /// Wakes sleeping bodies that are touched by an actively moving dynamic body.
+    /// Bodies that are themselves settling (sleep timer running) don't wake their neighbours,
+    /// so a resting stack can fall asleep as a whole instead of waking itself back up.
+    pub fn wake_touched_bodies(
+        mut query: Query<(&PhysicsComponent, Option<&mut SleepComponent>)>,
+        collision_frame_data: Res<CollisionFrameData>,
+    ) {
+        for entry in collision_frame_data.manifolds.iter() {
+            for (sleeper, other) in [
+                (entry.entity_a, entry.entity_b),
+                (entry.entity_b, entry.entity_a),
+            ] {
+                let other_is_active = query.get(other).is_ok_and(|(physics, sleep)| {
+                    matches!(
+                        physics.physics_type,
+                        crate::components::physics_component::PhysicsType::Dynamic
+                    ) && sleep.is_none_or(|sleep| !sleep.is_sleeping && sleep.sleep_timer <= 0.0)
+                });
+                if !other_is_active {
+                    continue;
+                }
+
+                if let Ok((_, Some(mut sleep))) = query.get_mut(sleeper)
+                    && sleep.is_sleeping
+                {
+                    sleep.is_sleeping = false;
+                    sleep.sleep_timer = 0.0;
+                }
+            }
+        }
+    }
+
+    /// Damps the spin of dynamic bodies that are resting on several contacts, see
+    /// [`RestingDampingConfig`](crate::physics::physics_resource::RestingDampingConfig).
+    pub fn damp_resting_spin(
+        mut query: Query<(&mut VelocityComponent, &PhysicsComponent)>,
+        collision_frame_data: Res<CollisionFrameData>,
+        physics_world: Res<PhysicsResource>,
+        time: Res<TimeResource>,
+    ) {
+        let config = physics_world.resting_damping;
+        let decay = (-config.angular_damping * time.simulation_fixed_dt().as_secs_f32()).exp();
+
+        let mut contact_counts: HashMap<Entity, usize> = HashMap::new();
+        for entry in collision_frame_data.manifolds.iter() {
+            let count = entry.manifold.contacts.len();
+            *contact_counts.entry(entry.entity_a).or_default() += count;
+            *contact_counts.entry(entry.entity_b).or_default() += count;
+        }
+
+        for (entity, count) in contact_counts {
+            if count < config.min_contacts {
+                continue;
+            }
+            let Ok((mut velocity, physics)) = query.get_mut(entity) else {
+                continue;
+            };
+            if !matches!(
+                physics.physics_type,
+                crate::components::physics_component::PhysicsType::Dynamic
+            ) || velocity.translational.length() >= config.max_linear_speed
+            {
+                continue;
+            }
+            velocity.angular *= decay;
+        }
+    }
+
     fn update_body(
         transform: &mut TransformComponent,
         velocity: &mut VelocityComponent,
@@ -97,7 +245,48 @@ impl PhysicsSystem {
         velocity.angular += (angular_drag_force / physics.mass) * delta_time;
     }
 
-    fn manifold_to_constraints(manifold: &ContactManifold) -> Vec<ContactConstraint> {
+    /// Runs the pre-solve hooks registered for the layers of `entry`'s two colliders.
+    fn pre_solve_allows(
+        entry: &ManifoldEntry,
+        hooks: &PreSolveHooks,
+        colliders: &Query<(Option<&ConvexCollider>, Option<&MeshCollider>)>,
+        query: &Query<(
+            &mut TransformComponent,
+            Option<&mut VelocityComponent>,
+            Option<&PhysicsComponent>,
+        )>,
+    ) -> bool {
+        if hooks.is_empty() {
+            return true;
+        }
+        let layer = |entity: Entity| -> Option<CollisionLayer> {
+            match colliders.get(entity).ok()? {
+                (Some(convex), _) => Some(convex.layer),
+                (None, Some(mesh)) => Some(mesh.layer),
+                (None, None) => None,
+            }
+        };
+        let (Some(layer_a), Some(layer_b)) = (layer(entry.entity_a), layer(entry.entity_b)) else {
+            return true;
+        };
+        let velocity = |entity: Entity| match query.get(entity) {
+            Ok((_, Some(velocity), _)) => velocity.translational,
+            _ => Vec3::ZERO,
+        };
+        let contact = PreSolveContact {
+            entity_a: entry.entity_a,
+            entity_b: entry.entity_b,
+            normal: entry.manifold.normal,
+            velocity_a: velocity(entry.entity_a),
+            velocity_b: velocity(entry.entity_b),
+        };
+        hooks.allows(layer_a, layer_b, &contact)
+    }
+
+    fn manifold_to_constraints(
+        manifold: &ContactManifold,
+        surface_velocity: Vec3,
+    ) -> Vec<ContactConstraint> {
         manifold
             .contacts
             .iter()
@@ -113,26 +302,84 @@ impl PhysicsSystem {
                     entity_b: contact.entity_b,
                     normal,
                     penetration: contact.penetration,
-                    accumulated_tangent_lambda: 0.0,
+                    accumulated_tangent_impulse: Vec3::ZERO,
                     accumulated_normal_lambda: 0.0,
                     contact_point: contact.contact_point,
+                    warm_started: false,
+                    surface_velocity,
                 }
             })
             .collect()
     }
 
-    fn solve_constraint(
+    /// Seeds `constraint` with the impulse of the nearest previous contact of the same pair
+    /// and applies that impulse up front.
+    fn warm_start(
         constraint: &mut ContactConstraint,
+        previous: &HashMap<(Entity, Entity), Vec<CachedImpulse>>,
+        max_distance: f32,
         query: &mut Query<(
             &mut TransformComponent,
             Option<&mut VelocityComponent>,
             Option<&PhysicsComponent>,
         )>,
     ) {
+        let Some(cached) = previous.get(&(constraint.entity_a, constraint.entity_b)) else {
+            return;
+        };
+        let Some(nearest) = cached
+            .iter()
+            .map(|impulse| {
+                let distance = impulse.contact_point.distance(constraint.contact_point);
+                (impulse, distance)
+            })
+            .filter(|(_, distance)| *distance <= max_distance)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(impulse, _)| *impulse)
+        else {
+            return;
+        };
+
+        constraint.accumulated_normal_lambda = nearest.normal_lambda;
+        constraint.accumulated_tangent_impulse = nearest.tangent_impulse;
+        constraint.warm_started = true;
+
         let Ok([mut a, mut b]) = query.get_many_mut([constraint.entity_a, constraint.entity_b])
         else {
             return;
         };
+        let props_a = physics_props(a.2);
+        let props_b = physics_props(b.2);
+        let ra = constraint.contact_point - a.0.position;
+        let rb = constraint.contact_point - b.0.position;
+        let impulse =
+            constraint.normal.normalize_or_zero() * nearest.normal_lambda + nearest.tangent_impulse;
+
+        if let Some(vel_a) = a.1.as_mut() {
+            vel_a.translational -= impulse * props_a.inv_mass;
+            vel_a.angular -= props_a.inv_inertia * ra.cross(impulse);
+        }
+        if let Some(vel_b) = b.1.as_mut() {
+            vel_b.translational += impulse * props_b.inv_mass;
+            vel_b.angular += props_b.inv_inertia * rb.cross(impulse);
+        }
+    }
+
+    /// Runs one solver pass over `constraint` and returns how much its accumulated impulse
+    /// changed.
+    fn solve_constraint(
+        constraint: &mut ContactConstraint,
+        restitution_threshold: f32,
+        query: &mut Query<(
+            &mut TransformComponent,
+            Option<&mut VelocityComponent>,
+            Option<&PhysicsComponent>,
+        )>,
+    ) -> f32 {
+        let Ok([mut a, mut b]) = query.get_many_mut([constraint.entity_a, constraint.entity_b])
+        else {
+            return 0.0;
+        };
 
         let (transform_a, mut vel_a_opt, phys_a_opt) = (&mut a.0, a.1, a.2);
         let (transform_b, mut vel_b_opt, phys_b_opt) = (&mut b.0, b.1, b.2);
@@ -143,14 +390,14 @@ impl PhysicsSystem {
 
         let inv_mass_sum = props_a.inv_mass + props_b.inv_mass;
         if inv_mass_sum <= f32::EPSILON {
-            return;
+            return 0.0;
         }
 
         // --- Normal ---
         let normal = {
             let n2 = constraint.normal.length_squared();
             if n2 <= f32::EPSILON {
-                return;
+                return 0.0;
             }
             constraint.normal / n2.sqrt()
         };
@@ -174,12 +421,12 @@ impl PhysicsSystem {
         // --- Relative velocity at contact ---
         let mut rv = (v_b + omega_b.cross(rb)) - (v_a + omega_a.cross(ra));
         let rvn = rv.dot(normal);
-        if rvn > 0.0 {
-            return;
+        // A warm-started impulse may overshoot, so it can still be pulled back while separating.
+        if rvn > 0.0 && !(constraint.warm_started && constraint.accumulated_normal_lambda > 0.0) {
+            return 0.0;
         }
 
         // --- Restitution ---
-        let restitution_threshold = 0.1;
         let restitution = if rvn < -restitution_threshold {
             // ((restitution_a.sqrt() + restitution_b.sqrt()) * 0.5).powi(2)
             f32::min(props_a.restitution, props_b.restitution)
@@ -194,7 +441,7 @@ impl PhysicsSystem {
             + normal.dot((props_a.inv_inertia * ra_cross_n).cross(ra))
             + normal.dot((props_b.inv_inertia * rb_cross_n).cross(rb));
         if k <= f32::EPSILON {
-            return;
+            return 0.0;
         }
 
         // --- Normal impulse ---
@@ -218,17 +465,18 @@ impl PhysicsSystem {
         }
 
         // --- Friction ---
-        rv = (v_b + omega_b.cross(rb)) - (v_a + omega_a.cross(ra));
+        // Slip relative to the surfaces, so a moving surface drags the other body along.
+        rv = (v_b + omega_b.cross(rb)) - (v_a + omega_a.cross(ra)) - constraint.surface_velocity;
         let mut tangent = rv - normal * rv.dot(normal);
         let tangent_len = tangent.length();
         if tangent_len <= f32::EPSILON {
-            return;
+            return delta_normal.abs();
         }
         tangent /= tangent_len;
 
         let friction = (props_a.friction * props_b.friction).sqrt();
         if friction <= 0.0 {
-            return;
+            return delta_normal.abs();
         }
 
         // Effective mass for friction
@@ -238,16 +486,17 @@ impl PhysicsSystem {
             + tangent.dot((props_a.inv_inertia * ra_cross_t).cross(ra))
             + tangent.dot((props_b.inv_inertia * rb_cross_t).cross(rb));
         if k_t <= f32::EPSILON {
-            return;
+            return delta_normal.abs();
         }
 
+        // Friction is accumulated as a vector so a warm-started impulse stays valid when the
+        // sliding direction changes between steps.
         let jt = -rv.dot(tangent) / k_t;
         let max_friction = friction * constraint.accumulated_normal_lambda;
-        let new_tangent_lambda =
-            (constraint.accumulated_tangent_lambda + jt).clamp(-max_friction, max_friction);
-        let delta_tangent = new_tangent_lambda - constraint.accumulated_tangent_lambda;
-        constraint.accumulated_tangent_lambda = new_tangent_lambda;
-        let friction_impulse = tangent * delta_tangent;
+        let new_tangent_impulse =
+            (constraint.accumulated_tangent_impulse + tangent * jt).clamp_length_max(max_friction);
+        let friction_impulse = new_tangent_impulse - constraint.accumulated_tangent_impulse;
+        constraint.accumulated_tangent_impulse = new_tangent_impulse;
 
         if let Some(vel_a) = vel_a_opt.as_mut() {
             vel_a.translational -= friction_impulse * props_a.inv_mass;
@@ -257,8 +506,65 @@ impl PhysicsSystem {
             vel_b.translational += friction_impulse * props_b.inv_mass;
             vel_b.angular += props_b.inv_inertia * rb.cross(friction_impulse);
         }
+
+        delta_normal.abs() + friction_impulse.length()
     }
 
+    /// Opposes the rolling part of the relative spin at a contact with an angular impulse of
+    /// at most `rolling_friction * normal impulse`, using the larger coefficient of the two
+    /// bodies. Runs once per step, after the velocity iterations have settled the normal
+    /// impulse, and never reverses the spin.
+    fn apply_rolling_friction(
+        constraint: &ContactConstraint,
+        query: &mut Query<(
+            &mut TransformComponent,
+            Option<&mut VelocityComponent>,
+            Option<&PhysicsComponent>,
+        )>,
+    ) {
+        if constraint.accumulated_normal_lambda <= 0.0 {
+            return;
+        }
+        let Ok([mut a, mut b]) = query.get_many_mut([constraint.entity_a, constraint.entity_b])
+        else {
+            return;
+        };
+        let props_a = physics_props(a.2);
+        let props_b = physics_props(b.2);
+        let rolling_friction = props_a.rolling_friction.max(props_b.rolling_friction);
+        if rolling_friction <= 0.0 {
+            return;
+        }
+
+        let normal = constraint.normal.normalize_or_zero();
+        let omega_a = a.1.as_ref().map(|v| v.angular).unwrap_or(Vec3::ZERO);
+        let omega_b = b.1.as_ref().map(|v| v.angular).unwrap_or(Vec3::ZERO);
+        let relative_spin = omega_b - omega_a;
+        // Spin about the normal is twisting, not rolling.
+        let rolling_spin = relative_spin - normal * relative_spin.dot(normal);
+        let speed = rolling_spin.length();
+        if speed <= f32::EPSILON {
+            return;
+        }
+        let axis = rolling_spin / speed;
+
+        let k = axis.dot(props_a.inv_inertia * axis) + axis.dot(props_b.inv_inertia * axis);
+        if k <= f32::EPSILON {
+            return;
+        }
+        let max_impulse = rolling_friction * constraint.accumulated_normal_lambda;
+        let angular_impulse = axis * (speed / k).min(max_impulse);
+
+        if let Some(vel_a) = a.1.as_mut() {
+            vel_a.angular += props_a.inv_inertia * angular_impulse;
+        }
+        if let Some(vel_b) = b.1.as_mut() {
+            vel_b.angular -= props_b.inv_inertia * angular_impulse;
+        }
+    }
+
+    /// Pushes penetrating bodies apart. Sleeping bodies take no share of the correction, like
+    /// static ones, so an awake body settling onto them can't shift them while they sleep.
     fn positional_correction(
         physics_frame_data: &mut PhysicsFrameData,
         query: &mut Query<(
@@ -266,12 +572,21 @@ impl PhysicsSystem {
             Option<&mut VelocityComponent>,
             Option<&PhysicsComponent>,
         )>,
+        sleepers: &Query<&SleepComponent>,
     ) {
         // Parameters
         let slop = 0.025;
         let percent = 0.45;
         let max_correction = 2.0;
 
+        let correction_inv_mass = |entity: Entity, physics: Option<&PhysicsComponent>| {
+            if sleepers.get(entity).is_ok_and(|sleep| sleep.is_sleeping) {
+                0.0
+            } else {
+                physics_props(physics).inv_mass
+            }
+        };
+
         // Track accumulated corrections per entity
 
         for constraint in &physics_frame_data.constraints {
@@ -283,9 +598,9 @@ impl PhysicsSystem {
             let (_, _, phys_a) = (&mut a.0, a.1, a.2);
             let (_, _, phys_b) = (&mut b.0, b.1, b.2);
 
-            let props_a = physics_props(phys_a);
-            let props_b = physics_props(phys_b);
-            let inv_mass_sum = props_a.inv_mass + props_b.inv_mass;
+            let inv_mass_a = correction_inv_mass(constraint.entity_a, phys_a);
+            let inv_mass_b = correction_inv_mass(constraint.entity_b, phys_b);
+            let inv_mass_sum = inv_mass_a + inv_mass_b;
             if inv_mass_sum <= f32::EPSILON {
                 continue;
             }
@@ -303,18 +618,23 @@ impl PhysicsSystem {
             physics_frame_data
                 .corrections
                 .entry(constraint.entity_a)
-                .and_modify(|v| *v += -correction * props_a.inv_mass)
-                .or_insert(-correction * props_a.inv_mass);
+                .and_modify(|v| *v += -correction * inv_mass_a)
+                .or_insert(-correction * inv_mass_a);
 
             physics_frame_data
                 .corrections
                 .entry(constraint.entity_b)
-                .and_modify(|v| *v += correction * props_b.inv_mass)
-                .or_insert(correction * props_b.inv_mass);
+                .and_modify(|v| *v += correction * inv_mass_b)
+                .or_insert(correction * inv_mass_b);
         }
 
         // Apply clamped corrections
         for (entity, delta) in physics_frame_data.corrections.drain() {
+            // Static and sleeping bodies take no share; skipping them keeps their transforms
+            // from being marked as changed.
+            if delta == Vec3::ZERO {
+                continue;
+            }
             let Ok(mut entry) = query.get_mut(entity) else {
                 continue;
             };
@@ -324,14 +644,16 @@ impl PhysicsSystem {
     }
 
     /// Applies extra damping for bodies that are in resting contact on support surfaces
-    /// to reduce micro-jitter and slow spin accumulation.
+    /// to reduce micro-jitter and slow spin accumulation. Pairs in `skipped` are left alone.
     fn stabilize_resting_contacts(
         collision_frame_data: &CollisionFrameData,
+        skipped: &HashSet<(Entity, Entity)>,
         query: &mut Query<(
             &mut TransformComponent,
             Option<&mut VelocityComponent>,
             Option<&PhysicsComponent>,
         )>,
+        sleepers: &Query<&SleepComponent>,
         gravity: Vec3,
     ) {
         use crate::components::physics_component::PhysicsType;
@@ -349,7 +671,9 @@ impl PhysicsSystem {
         let max_supported_downward_speed = 0.02;
 
         for entry in collision_frame_data.manifolds.iter() {
-            if entry.manifold.contacts.is_empty() {
+            if entry.manifold.contacts.is_empty()
+                || skipped.contains(&(entry.entity_a, entry.entity_b))
+            {
                 continue;
             }
 
@@ -372,6 +696,10 @@ impl PhysicsSystem {
                         continue;
                     }
 
+                    if sleepers.get(entity).is_ok_and(|sleep| sleep.is_sleeping) {
+                        continue;
+                    }
+
                     let Ok((_, vel_opt, phys_opt)) = query.get_mut(entity) else {
                         continue;
                     };
@@ -434,30 +762,119 @@ impl PhysicsSystem {
             Option<&mut VelocityComponent>,
             Option<&PhysicsComponent>,
         )>,
-        collision_frame_data: Res<CollisionFrameData>,
         mut physics_frame_data: ResMut<PhysicsFrameData>,
-        gravity: Res<Gravity>,
-        time: Res<TimeResource>,
+        context: SolverContext,
     ) {
+        use crate::components::physics_component::PhysicsType;
+
+        let SolverContext {
+            sleepers,
+            colliders,
+            surfaces,
+            collision_frame_data,
+            physics_world,
+            pre_solve_hooks,
+            gravity,
+            time,
+        } = context;
+        let config = physics_world.solver;
+
+        // Contacts where neither side is an awake dynamic body (sleeping against sleeping or
+        // static) need no solving, and solving them would write to the sleepers' transforms.
+        let is_awake_dynamic = |entity: Entity| {
+            matches!(
+                query.get(entity),
+                Ok((_, _, Some(physics))) if matches!(physics.physics_type, PhysicsType::Dynamic)
+            ) && !sleepers.get(entity).is_ok_and(|sleep| sleep.is_sleeping)
+        };
+
+        let surface_velocity = |entity: Entity| {
+            surfaces
+                .get(entity)
+                .map_or(Vec3::ZERO, |surface| surface.surface_velocity)
+        };
+
+        // Vetoed pairs and bodies carried by a moving surface are not resting, so the resting
+        // stabilization below leaves them alone.
+        let mut unstabilized = HashSet::new();
         for entry in collision_frame_data.manifolds.iter() {
+            if !is_awake_dynamic(entry.entity_a) && !is_awake_dynamic(entry.entity_b) {
+                continue;
+            }
+            let pair = (entry.entity_a, entry.entity_b);
+            if !Self::pre_solve_allows(entry, &pre_solve_hooks, &colliders, &query) {
+                unstabilized.insert(pair);
+                continue;
+            }
+            let relative_surface_velocity =
+                surface_velocity(entry.entity_b) - surface_velocity(entry.entity_a);
+            if relative_surface_velocity != Vec3::ZERO {
+                unstabilized.insert(pair);
+            }
             physics_frame_data
                 .constraints
-                .extend(Self::manifold_to_constraints(&entry.manifold));
+                .extend(Self::manifold_to_constraints(
+                    &entry.manifold,
+                    relative_surface_velocity,
+                ));
+        }
+
+        let previous = std::mem::take(&mut physics_frame_data.warm_start);
+        if config.warm_starting {
+            for constraint in &mut physics_frame_data.constraints {
+                Self::warm_start(
+                    constraint,
+                    &previous,
+                    config.warm_start_distance,
+                    &mut query,
+                );
+            }
         }
 
         // For smaller time steps, we can get away with fewer iterations.
         // For larger steps, we need more iterations to maintain stability.
         let pgs_iterations = time.simulation_fixed_dt().as_millis() as u32;
+        physics_frame_data.solver_iterations = 0;
         for _ in 0..pgs_iterations {
+            physics_frame_data.solver_iterations += 1;
+            let mut largest_change = 0.0f32;
             for constraint in &mut physics_frame_data.constraints {
-                Self::solve_constraint(constraint, &mut query);
+                largest_change = largest_change.max(Self::solve_constraint(
+                    constraint,
+                    physics_world.restitution_velocity_threshold,
+                    &mut query,
+                ));
+            }
+            if largest_change < config.convergence_tolerance {
+                break;
             }
         }
 
-        Self::positional_correction(&mut physics_frame_data, &mut query);
+        for constraint in &physics_frame_data.constraints {
+            Self::apply_rolling_friction(constraint, &mut query);
+        }
+
+        if config.warm_starting {
+            let mut warm_start: HashMap<(Entity, Entity), Vec<CachedImpulse>> = HashMap::new();
+            for constraint in &physics_frame_data.constraints {
+                warm_start
+                    .entry((constraint.entity_a, constraint.entity_b))
+                    .or_default()
+                    .push(CachedImpulse {
+                        contact_point: constraint.contact_point,
+                        normal_lambda: constraint.accumulated_normal_lambda,
+                        tangent_impulse: constraint.accumulated_tangent_impulse,
+                    });
+            }
+            physics_frame_data.warm_start = warm_start;
+        }
+
+        Self::positional_correction(&mut physics_frame_data, &mut query, &sleepers);
         Self::stabilize_resting_contacts(
             &collision_frame_data,
+            &unstabilized,
             &mut query,
+            &sleepers,
             gravity.gravity_vector(),
         );
 
@@ -473,6 +890,7 @@ fn physics_props(physics: Option<&PhysicsComponent>) -> PhysicsProps {
             inv_mass: 0.0,
             restitution: 0.0,
             friction: 0.0,
+            rolling_friction: 0.0,
             inv_inertia: Mat3::ZERO,
         };
     };
@@ -496,6 +914,7 @@ fn physics_props(physics: Option<&PhysicsComponent>) -> PhysicsProps {
         inv_mass,
         restitution: physics.restitution,
         friction: physics.friction,
+        rolling_friction: physics.rolling_friction,
         inv_inertia,
     }
 }
@@ -504,15 +923,28 @@ struct PhysicsProps {
     inv_mass: f32,
     restitution: f32,
     friction: f32,
+    rolling_friction: f32,
     inv_inertia: Mat3,
 }
 
 #[cfg(test)]
 mod tests {
     use approx::assert_relative_eq;
+    use bevy_ecs::system::RunSystemOnce;
     use glam::{Quat, Vec3};
 
-    use crate::components::physics_component::PhysicsType;
+    use crate::{
+        assets::mesh_resource::MeshResource,
+        components::{
+            collider_component::{CollisionLayer, ConvexCollider},
+            physics_component::PhysicsType,
+        },
+        physics::{
+            collision_system::{CollisionSystem, ordered_pair},
+            physics_resource::{Contact, SolverConfig},
+        },
+        render::render_body_resource::RenderBodyResource,
+    };
 
     use super::*;
 
@@ -521,6 +953,7 @@ mod tests {
             physics_type: PhysicsType::Dynamic,
             mass: 1.0,
             friction: 0.0,
+            rolling_friction: 0.0,
             drag_coefficient: 0.1,
             angular_drag_coefficient: 0.2,
             restitution: 0.0,
@@ -528,6 +961,156 @@ mod tests {
         }
     }
 
+    #[test]
+    fn body_under_sideways_gravity_accelerates_along_it() {
+        let mut world = World::new();
+        world.insert_resource(TimeResource::new(60, 120));
+        world.insert_resource(Gravity::from_vector(Vec3::X * 9.81));
+        let body = world
+            .spawn((
+                TransformComponent::default(),
+                VelocityComponent::default(),
+                physics_component(),
+            ))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(PhysicsSystem::integrate_motion);
+        for _ in 0..10 {
+            schedule.run(&mut world);
+        }
+
+        let velocity = world.get::<VelocityComponent>(body).unwrap().translational;
+        let position = world.get::<TransformComponent>(body).unwrap().position;
+        assert!(velocity.x > 0.0, "{velocity:?}");
+        assert_relative_eq!(velocity.y, 0.0);
+        assert_relative_eq!(velocity.z, 0.0);
+        assert!(position.x > 0.0, "{position:?}");
+    }
+
+    #[test]
+    fn resting_body_loses_spin_faster_than_free_body() {
+        let mut world = World::new();
+        world.insert_resource(TimeResource::new(60, 120));
+        world.insert_resource(Gravity::from_vector(Vec3::ZERO));
+        world.insert_resource(PhysicsResource::default());
+        let mut frame = CollisionFrameData::default();
+
+        let spin = Vec3::new(0.0, 0.0, 0.05);
+        let spawn_spinning = |world: &mut World| {
+            world
+                .spawn((
+                    TransformComponent::default(),
+                    VelocityComponent {
+                        translational: Vec3::ZERO,
+                        angular: spin,
+                    },
+                    physics_component(),
+                ))
+                .id()
+        };
+        let resting = spawn_spinning(&mut world);
+        let free = spawn_spinning(&mut world);
+        let ground = world
+            .spawn((
+                TransformComponent::default(),
+                PhysicsComponent {
+                    physics_type: PhysicsType::Static,
+                    ..physics_component()
+                },
+            ))
+            .id();
+
+        let contact = |x: f32| Contact {
+            entity_a: ground,
+            entity_b: resting,
+            normal: Vec3::Z,
+            penetration: 0.0,
+            contact_point: Vec3::new(x, 0.0, 0.0),
+        };
+        frame.manifolds.push(
+            ordered_pair(ground, resting),
+            ContactManifold {
+                contacts: vec![contact(-0.5), contact(0.5)],
+                normal: Vec3::Z,
+                relative_normal_speed: 0.0,
+                impact_impulse: 0.0,
+                impact_energy: 0.0,
+            },
+        );
+        world.insert_resource(frame);
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                PhysicsSystem::damp_resting_spin,
+                PhysicsSystem::integrate_motion,
+            )
+                .chain(),
+        );
+        for _ in 0..30 {
+            schedule.run(&mut world);
+        }
+
+        let resting_spin = world
+            .get::<VelocityComponent>(resting)
+            .unwrap()
+            .angular
+            .length();
+        let free_spin = world
+            .get::<VelocityComponent>(free)
+            .unwrap()
+            .angular
+            .length();
+        assert!(
+            resting_spin < free_spin * 0.5,
+            "resting spin {resting_spin} should decay well below free spin {free_spin}"
+        );
+    }
+
+    #[test]
+    fn locked_rotation_ignores_off_center_impulse() {
+        let mut world = World::new();
+        world.insert_resource(TimeResource::new(60, 120));
+        world.insert_resource(Gravity::default());
+
+        // Impulse applied at the edge of the body, which would normally make it spin.
+        let physics = physics_component();
+        let impulse = Vec3::new(1.0, 0.0, 0.0);
+        let lever = Vec3::new(0.0, 0.0, 0.5);
+        let velocity = VelocityComponent {
+            translational: impulse / physics.mass,
+            angular: physics.local_inertia.inverse() * lever.cross(impulse),
+        };
+        assert!(velocity.angular.length() > 0.0);
+
+        let spawn = |world: &mut World, locks: LockedAxes| {
+            world
+                .spawn((TransformComponent::default(), velocity, physics, locks))
+                .id()
+        };
+        let locked = spawn(&mut world, LockedAxes::ROTATION_LOCKED);
+        let free = spawn(&mut world, LockedAxes::default());
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(PhysicsSystem::integrate_motion);
+        for _ in 0..10 {
+            schedule.run(&mut world);
+        }
+
+        let locked_transform = world.get::<TransformComponent>(locked).unwrap();
+        assert_eq!(locked_transform.rotation, Quat::IDENTITY);
+        assert!(locked_transform.position.x > 0.0);
+        assert_eq!(
+            world.get::<VelocityComponent>(locked).unwrap().angular,
+            Vec3::ZERO
+        );
+        assert_ne!(
+            world.get::<TransformComponent>(free).unwrap().rotation,
+            Quat::IDENTITY
+        );
+    }
+
     #[test]
     fn update_body_applies_gravity_and_drag() {
         let mut transform = TransformComponent::default();
@@ -587,4 +1170,545 @@ mod tests {
         assert_relative_eq!(transform.rotation.z, expected.z, epsilon = 1e-6);
         assert_relative_eq!(transform.rotation.w, expected.w, epsilon = 1e-6);
     }
+
+    /// Rests two boxes on static ground through hand-made manifolds and returns the solver
+    /// passes used by the last of several steps.
+    fn resting_stack_solver_iterations(warm_starting: bool) -> u32 {
+        let mut world = World::new();
+        world.insert_resource(TimeResource::new(60, 120));
+        world.insert_resource(Gravity::default());
+        world.insert_resource(PhysicsFrameData::default());
+        world.insert_resource(PreSolveHooks::default());
+        world.insert_resource(PhysicsResource {
+            solver: SolverConfig {
+                warm_starting,
+                convergence_tolerance: 1e-4,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        let ground = world
+            .spawn((
+                TransformComponent::default(),
+                PhysicsComponent {
+                    physics_type: PhysicsType::Static,
+                    ..physics_component()
+                },
+            ))
+            .id();
+        let spawn_box = |world: &mut World, height: f32| {
+            world
+                .spawn((
+                    TransformComponent {
+                        position: Vec3::new(0.0, 0.0, height),
+                        ..Default::default()
+                    },
+                    VelocityComponent::default(),
+                    physics_component(),
+                ))
+                .id()
+        };
+        let lower = spawn_box(&mut world, 0.5);
+        let upper = spawn_box(&mut world, 1.5);
+
+        let mut frame = CollisionFrameData::default();
+        for (a, b, height) in [(ground, lower, 0.0), (lower, upper, 1.0)] {
+            let contacts = [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)]
+                .map(|(x, y)| Contact {
+                    entity_a: a,
+                    entity_b: b,
+                    normal: Vec3::Z,
+                    penetration: 0.0,
+                    contact_point: Vec3::new(x, y, height),
+                })
+                .to_vec();
+            frame.manifolds.push(
+                ordered_pair(a, b),
+                ContactManifold {
+                    contacts,
+                    normal: Vec3::Z,
+                    relative_normal_speed: 0.0,
+                    impact_impulse: 0.0,
+                    impact_energy: 0.0,
+                },
+            );
+        }
+        world.insert_resource(frame);
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                PhysicsSystem::physics_solver,
+                PhysicsSystem::integrate_motion,
+            )
+                .chain(),
+        );
+        for _ in 0..10 {
+            schedule.run(&mut world);
+        }
+        world.resource::<PhysicsFrameData>().solver_iterations
+    }
+
+    #[test]
+    fn warm_started_stack_converges_in_fewer_iterations() {
+        let cold = resting_stack_solver_iterations(false);
+        let warm = resting_stack_solver_iterations(true);
+        assert!(warm < cold, "warm started {warm} vs cold {cold} iterations");
+    }
+
+    /// Rolls a ball along static ground for a few seconds and returns its final speed.
+    fn rolled_ball_speed(rolling_friction: f32) -> f32 {
+        let mut world = World::new();
+        world.insert_resource(RenderBodyResource::default());
+        world.insert_resource(MeshResource::default());
+        world.insert_resource(PhysicsResource::default());
+        world.insert_resource(CollisionFrameData::default());
+        world.insert_resource(PhysicsFrameData::default());
+        world.insert_resource(PreSolveHooks::default());
+        world.insert_resource(TimeResource::new(60, 120));
+        world.insert_resource(Gravity::default());
+
+        let rough = PhysicsComponent {
+            friction: 0.5,
+            drag_coefficient: 0.0,
+            angular_drag_coefficient: 0.0,
+            ..physics_component()
+        };
+        world.spawn((
+            TransformComponent {
+                position: Vec3::new(0.0, 0.0, -0.5),
+                ..Default::default()
+            },
+            ConvexCollider::cuboid(Vec3::new(100.0, 100.0, 1.0), CollisionLayer::Environment),
+            PhysicsComponent {
+                physics_type: PhysicsType::Static,
+                ..rough
+            },
+        ));
+        // Rolling without slipping along +X: v = omega x (center - contact point).
+        let ball = world
+            .spawn((
+                TransformComponent {
+                    position: Vec3::new(0.0, 0.0, 0.5),
+                    ..Default::default()
+                },
+                VelocityComponent {
+                    translational: Vec3::new(2.0, 0.0, 0.0),
+                    angular: Vec3::new(0.0, 4.0, 0.0),
+                },
+                ConvexCollider::sphere(0.5, CollisionLayer::Default),
+                PhysicsComponent {
+                    rolling_friction,
+                    local_inertia: glam::Mat3::from_diagonal(Vec3::splat(0.1)),
+                    ..rough
+                },
+            ))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                CollisionSystem::update_world_aabb_cache,
+                CollisionSystem::update_world_dynamic_tree,
+                CollisionSystem::generate_manifolds,
+                PhysicsSystem::physics_solver,
+                PhysicsSystem::integrate_motion,
+            )
+                .chain(),
+        );
+        for _ in 0..600 {
+            schedule.run(&mut world);
+        }
+        world
+            .get::<VelocityComponent>(ball)
+            .unwrap()
+            .translational
+            .length()
+    }
+
+    #[test]
+    fn rolling_friction_brings_a_rolling_ball_to_rest() {
+        let braked = rolled_ball_speed(0.1);
+        let free = rolled_ball_speed(0.0);
+        assert!(
+            braked < 0.05,
+            "ball with rolling friction still moves at {braked}"
+        );
+        assert!(free > 1.0, "ball without rolling friction slowed to {free}");
+    }
+
+    #[test]
+    fn box_on_conveyor_is_carried_along_the_surface() {
+        let mut world = World::new();
+        world.insert_resource(RenderBodyResource::default());
+        world.insert_resource(MeshResource::default());
+        world.insert_resource(PhysicsResource::default());
+        world.insert_resource(CollisionFrameData::default());
+        world.insert_resource(PhysicsFrameData::default());
+        world.insert_resource(PreSolveHooks::default());
+        world.insert_resource(TimeResource::new(60, 120));
+        world.insert_resource(Gravity::default());
+
+        let rough = PhysicsComponent {
+            friction: 0.5,
+            ..physics_component()
+        };
+        world.spawn((
+            TransformComponent {
+                position: Vec3::new(0.0, 0.0, -0.5),
+                ..Default::default()
+            },
+            ConvexCollider::cuboid(Vec3::new(100.0, 10.0, 1.0), CollisionLayer::Environment),
+            PhysicsComponent {
+                physics_type: PhysicsType::Static,
+                ..rough
+            },
+            SurfaceVelocityComponent {
+                surface_velocity: Vec3::new(1.0, 0.0, 0.0),
+            },
+        ));
+        let parcel = world
+            .spawn((
+                TransformComponent {
+                    position: Vec3::new(0.0, 0.0, 0.5),
+                    ..Default::default()
+                },
+                VelocityComponent::default(),
+                ConvexCollider::cube(1.0, CollisionLayer::Default),
+                rough,
+            ))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                CollisionSystem::update_world_aabb_cache,
+                CollisionSystem::update_world_dynamic_tree,
+                CollisionSystem::generate_manifolds,
+                PhysicsSystem::physics_solver,
+                PhysicsSystem::integrate_motion,
+            )
+                .chain(),
+        );
+        for _ in 0..120 {
+            schedule.run(&mut world);
+        }
+
+        let velocity = world
+            .get::<VelocityComponent>(parcel)
+            .unwrap()
+            .translational;
+        let position = world.get::<TransformComponent>(parcel).unwrap().position;
+        assert!(
+            velocity.x > 0.8,
+            "parcel should move with the belt, {velocity:?}"
+        );
+        assert!(velocity.y.abs() < 0.05, "{velocity:?}");
+        assert!(position.x > 0.5, "{position:?}");
+    }
+
+    /// Drops a bouncy box from just above the ground and returns its largest upward speed
+    /// after first touching down.
+    fn rebound_speed_after_small_drop(restitution_velocity_threshold: f32) -> f32 {
+        let mut world = World::new();
+        world.insert_resource(RenderBodyResource::default());
+        world.insert_resource(MeshResource::default());
+        world.insert_resource(PhysicsResource {
+            restitution_velocity_threshold,
+            ..Default::default()
+        });
+        world.insert_resource(CollisionFrameData::default());
+        world.insert_resource(PhysicsFrameData::default());
+        world.insert_resource(PreSolveHooks::default());
+        world.insert_resource(TimeResource::new(60, 120));
+        world.insert_resource(Gravity::default());
+
+        let bouncy = PhysicsComponent {
+            restitution: 1.0,
+            ..physics_component()
+        };
+        world.spawn((
+            TransformComponent {
+                position: Vec3::new(0.0, 0.0, -0.5),
+                ..Default::default()
+            },
+            ConvexCollider::cuboid(Vec3::new(10.0, 10.0, 1.0), CollisionLayer::Environment),
+            PhysicsComponent {
+                physics_type: PhysicsType::Static,
+                ..bouncy
+            },
+        ));
+        // Lands at about 0.33 m/s
+        let body = world
+            .spawn((
+                TransformComponent {
+                    position: Vec3::new(0.0, 0.0, 0.506),
+                    ..Default::default()
+                },
+                VelocityComponent::default(),
+                ConvexCollider::cube(1.0, CollisionLayer::Default),
+                bouncy,
+            ))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                CollisionSystem::update_world_aabb_cache,
+                CollisionSystem::update_world_dynamic_tree,
+                CollisionSystem::generate_manifolds,
+                PhysicsSystem::physics_solver,
+                PhysicsSystem::integrate_motion,
+            )
+                .chain(),
+        );
+        let mut landed = false;
+        let mut rebound = 0.0f32;
+        for _ in 0..120 {
+            schedule.run(&mut world);
+            landed |= !world
+                .resource::<CollisionFrameData>()
+                .manifolds
+                .iter()
+                .all(|entry| entry.manifold.contacts.is_empty());
+            if landed {
+                let velocity = world.get::<VelocityComponent>(body).unwrap().translational;
+                rebound = rebound.max(velocity.z);
+            }
+        }
+        assert!(landed, "box never reached the ground");
+        rebound
+    }
+
+    #[test]
+    fn slow_impacts_below_the_restitution_threshold_do_not_bounce() {
+        // The box lands at about 0.33 m/s, between the two thresholds
+        let settled = rebound_speed_after_small_drop(0.5);
+        assert!(settled < 0.05, "box still bounces at {settled}");
+
+        let bouncing = rebound_speed_after_small_drop(0.0);
+        assert!(
+            bouncing > 0.2,
+            "box without a threshold only rebounds at {bouncing}"
+        );
+    }
+
+    /// Throws a ball up from below a thin platform and returns its height once it has come
+    /// back down.
+    fn ball_height_after_jumping_through_platform(one_way: bool) -> f32 {
+        let mut world = World::new();
+        world.insert_resource(RenderBodyResource::default());
+        world.insert_resource(MeshResource::default());
+        world.insert_resource(PhysicsResource::default());
+        world.insert_resource(CollisionFrameData::default());
+        world.insert_resource(PhysicsFrameData::default());
+        world.insert_resource(TimeResource::new(60, 120));
+        world.insert_resource(Gravity::default());
+
+        let mut hooks = PreSolveHooks::default();
+        if one_way {
+            // The normal points from the player to the platform, so it points down only when
+            // the player is on top. Moving up always passes through.
+            hooks.register(
+                CollisionLayer::Player,
+                CollisionLayer::Environment,
+                |contact| contact.normal.z < 0.0 && contact.velocity_a.z <= 0.0,
+            );
+        }
+        world.insert_resource(hooks);
+
+        world.spawn((
+            TransformComponent::default(),
+            ConvexCollider::cuboid(Vec3::new(4.0, 4.0, 0.2), CollisionLayer::Environment),
+            PhysicsComponent {
+                physics_type: PhysicsType::Static,
+                ..physics_component()
+            },
+        ));
+        let ball = world
+            .spawn((
+                TransformComponent {
+                    position: Vec3::new(0.0, 0.0, -1.0),
+                    ..Default::default()
+                },
+                VelocityComponent {
+                    translational: Vec3::new(0.0, 0.0, 6.0),
+                    angular: Vec3::ZERO,
+                },
+                ConvexCollider::sphere(0.25, CollisionLayer::Player),
+                physics_component(),
+            ))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                CollisionSystem::update_world_aabb_cache,
+                CollisionSystem::update_world_dynamic_tree,
+                CollisionSystem::generate_manifolds,
+                PhysicsSystem::physics_solver,
+                PhysicsSystem::integrate_motion,
+            )
+                .chain(),
+        );
+        for _ in 0..240 {
+            schedule.run(&mut world);
+        }
+        world.get::<TransformComponent>(ball).unwrap().position.z
+    }
+
+    #[test]
+    fn one_way_platform_lets_a_body_pass_upward_and_land_on_top() {
+        let one_way = ball_height_after_jumping_through_platform(true);
+        assert!(
+            (one_way - 0.35).abs() < 0.05,
+            "ball should rest on top of the platform, ended at {one_way}"
+        );
+
+        let solid = ball_height_after_jumping_through_platform(false);
+        assert!(
+            solid < -0.35,
+            "solid platform should block the ball, ended at {solid}"
+        );
+    }
+
+    #[derive(Resource, Default)]
+    struct ChangedTransforms(Vec<usize>);
+
+    fn count_changed_transforms(
+        query: Query<(), Changed<TransformComponent>>,
+        mut changed: ResMut<ChangedTransforms>,
+    ) {
+        changed.0.push(query.iter().count());
+    }
+
+    #[test]
+    fn settled_stack_stops_changing_transforms() {
+        let mut world = World::new();
+        world.insert_resource(RenderBodyResource::default());
+        world.insert_resource(MeshResource::default());
+        world.insert_resource(PhysicsResource::default());
+        world.insert_resource(CollisionFrameData::default());
+        world.insert_resource(PhysicsFrameData::default());
+        world.insert_resource(PreSolveHooks::default());
+        world.insert_resource(TimeResource::new(60, 120));
+        world.insert_resource(Gravity::default());
+        world.init_resource::<ChangedTransforms>();
+
+        world.spawn((
+            TransformComponent {
+                position: Vec3::new(0.0, 0.0, -0.5),
+                ..Default::default()
+            },
+            ConvexCollider::cuboid(Vec3::new(10.0, 10.0, 1.0), CollisionLayer::Environment),
+            PhysicsComponent {
+                physics_type: PhysicsType::Static,
+                ..physics_component()
+            },
+        ));
+
+        // Slightly interpenetrating boxes so an awake solver would push them apart.
+        for height in [0.45, 1.4] {
+            world.spawn((
+                TransformComponent {
+                    position: Vec3::new(0.0, 0.0, height),
+                    ..Default::default()
+                },
+                VelocityComponent::default(),
+                ConvexCollider::cube(1.0, CollisionLayer::Default),
+                physics_component(),
+                SleepComponent {
+                    is_sleeping: true,
+                    ..Default::default()
+                },
+            ));
+        }
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                CollisionSystem::update_world_aabb_cache,
+                CollisionSystem::update_world_dynamic_tree,
+                CollisionSystem::generate_manifolds,
+                PhysicsSystem::wake_touched_bodies,
+                PhysicsSystem::physics_solver,
+                PhysicsSystem::integrate_motion,
+                count_changed_transforms,
+            )
+                .chain(),
+        );
+
+        for _ in 0..5 {
+            schedule.run(&mut world);
+        }
+
+        let changed = &world.resource::<ChangedTransforms>().0;
+        // The first step sees the freshly spawned entities.
+        assert_eq!(changed[0], 3);
+        assert!(changed[1..].iter().all(|&count| count == 0), "{changed:?}");
+
+        let mut sleep_query = world.query::<&SleepComponent>();
+        assert!(sleep_query.iter(&world).all(|sleep| sleep.is_sleeping));
+    }
+
+    #[test]
+    fn positional_correction_leaves_sleeping_bodies_in_place() {
+        let mut world = World::new();
+        world.insert_resource(TimeResource::new(60, 120));
+        world.insert_resource(Gravity::default());
+        world.insert_resource(PhysicsFrameData::default());
+        world.insert_resource(PreSolveHooks::default());
+        world.insert_resource(PhysicsResource::default());
+
+        let sleeper = world
+            .spawn((
+                TransformComponent::default(),
+                VelocityComponent::default(),
+                physics_component(),
+                SleepComponent {
+                    is_sleeping: true,
+                    ..Default::default()
+                },
+            ))
+            .id();
+        let faller = world
+            .spawn((
+                TransformComponent {
+                    position: Vec3::new(0.0, 0.0, 0.8),
+                    ..Default::default()
+                },
+                VelocityComponent::default(),
+                physics_component(),
+            ))
+            .id();
+
+        // The awake box sinks 0.2 into the sleeping one
+        let mut frame = CollisionFrameData::default();
+        frame.manifolds.push(
+            ordered_pair(sleeper, faller),
+            ContactManifold {
+                contacts: vec![Contact {
+                    entity_a: sleeper,
+                    entity_b: faller,
+                    normal: Vec3::Z,
+                    penetration: 0.2,
+                    contact_point: Vec3::new(0.0, 0.0, 0.4),
+                }],
+                normal: Vec3::Z,
+                relative_normal_speed: 0.0,
+                impact_impulse: 0.0,
+                impact_energy: 0.0,
+            },
+        );
+        world.insert_resource(frame);
+
+        world
+            .run_system_once(PhysicsSystem::physics_solver)
+            .unwrap();
+
+        let position = |entity| world.get::<TransformComponent>(entity).unwrap().position;
+        assert_eq!(position(sleeper), Vec3::ZERO);
+        assert!(position(faller).z > 0.8);
+    }
 }
@@ -0,0 +1,374 @@
+use std::{collections::HashMap, num::NonZeroUsize};
+
+use bevy_ecs::entity::Entity;
+use glam::{IVec3, Vec3};
+
+use crate::{
+    assets::mesh::Aabb,
+    physics::{
+        broadphase::Broadphase,
+        dynamic_aabb_tree::{DEFAULT_FAT_MARGIN, NodeId},
+    },
+};
+
+pub const DEFAULT_CELL_SIZE: f32 = 2.0;
+
+/// Leaves touching more cells than this skip the grid and are checked by every query, so a
+/// ground plane or level-sized collider doesn't fill millions of cells.
+const MAX_LEAF_CELLS: u64 = 64;
+
+#[derive(Debug)]
+struct Leaf {
+    entity: Entity,
+    /// Fattened AABB.
+    aabb: Aabb,
+    oversized: bool,
+}
+
+/// Uniform grid broadphase. Each leaf is listed in every cell its fattened AABB touches, so
+/// moving and querying cost depends on the cells covered rather than on the body count.
+/// Works best when most bodies are about one cell in size, such as a pile of identical
+/// crates; scenes of very mixed sizes are better served by `DynamicAabbTree`.
+#[derive(Debug)]
+pub struct SpatialHashBroadphase {
+    cells: HashMap<IVec3, Vec<NodeId>>,
+    /// Indexed by `NodeId::get() - 1`, `None` for freed ids.
+    leaves: Vec<Option<Leaf>>,
+    free_list: Vec<NodeId>,
+    oversized: Vec<NodeId>,
+    cell_size: f32,
+    /// Padding added around leaf AABBs. Larger margins mean fewer cell changes for moving
+    /// bodies at the cost of looser broadphase pairs.
+    pub fat_margin: f32,
+}
+
+impl Default for SpatialHashBroadphase {
+    fn default() -> Self {
+        Self::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+impl SpatialHashBroadphase {
+    /// `cell_size` should be close to the size of a typical body.
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cells: HashMap::new(),
+            leaves: Vec::new(),
+            free_list: Vec::new(),
+            oversized: Vec::new(),
+            cell_size: cell_size.max(1e-3),
+            fat_margin: DEFAULT_FAT_MARGIN,
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    fn leaf(&self, id: NodeId) -> &Leaf {
+        self.leaves[id.get() - 1]
+            .as_ref()
+            .expect("broadphase leaf was removed")
+    }
+
+    fn leaf_mut(&mut self, id: NodeId) -> &mut Leaf {
+        self.leaves[id.get() - 1]
+            .as_mut()
+            .expect("broadphase leaf was removed")
+    }
+
+    fn live_leaves(&self) -> usize {
+        self.leaves.len() - self.free_list.len()
+    }
+
+    fn cell_range(&self, aabb: &Aabb) -> (IVec3, IVec3) {
+        (
+            (aabb.min / self.cell_size).floor().as_ivec3(),
+            (aabb.max / self.cell_size).floor().as_ivec3(),
+        )
+    }
+
+    fn cell_count(min: IVec3, max: IVec3) -> u64 {
+        (0..3).fold(1u64, |count, axis| {
+            let span = (max[axis] as i64 - min[axis] as i64 + 1).max(0) as u64;
+            count.saturating_mul(span)
+        })
+    }
+
+    fn cells_in(min: IVec3, max: IVec3) -> impl Iterator<Item = IVec3> {
+        (min.x..=max.x).flat_map(move |x| {
+            (min.y..=max.y).flat_map(move |y| (min.z..=max.z).map(move |z| IVec3::new(x, y, z)))
+        })
+    }
+
+    fn expand_aabb(aabb: Aabb, margin: f32) -> Aabb {
+        Aabb {
+            min: aabb.min - Vec3::splat(margin),
+            max: aabb.max + Vec3::splat(margin),
+        }
+    }
+
+    fn insert_cells(&mut self, id: NodeId) {
+        let (min, max) = self.cell_range(&self.leaf(id).aabb);
+        let oversized = Self::cell_count(min, max) > MAX_LEAF_CELLS;
+        self.leaf_mut(id).oversized = oversized;
+        if oversized {
+            self.oversized.push(id);
+            return;
+        }
+        for cell in Self::cells_in(min, max) {
+            self.cells.entry(cell).or_default().push(id);
+        }
+    }
+
+    fn remove_cells(&mut self, id: NodeId) {
+        let leaf = self.leaf(id);
+        if leaf.oversized {
+            self.oversized.retain(|other| *other != id);
+            return;
+        }
+        let (min, max) = self.cell_range(&leaf.aabb);
+        for cell in Self::cells_in(min, max) {
+            if let Some(ids) = self.cells.get_mut(&cell) {
+                ids.retain(|other| *other != id);
+                if ids.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+}
+
+impl Broadphase for SpatialHashBroadphase {
+    fn allocate_leaf(&mut self, entity: Entity, aabb: Aabb) -> NodeId {
+        let leaf = Leaf {
+            entity,
+            aabb: Self::expand_aabb(aabb, self.fat_margin),
+            oversized: false,
+        };
+        let id = match self.free_list.pop() {
+            Some(id) => {
+                self.leaves[id.get() - 1] = Some(leaf);
+                id
+            }
+            None => {
+                self.leaves.push(Some(leaf));
+                NonZeroUsize::new(self.leaves.len()).unwrap()
+            }
+        };
+        self.insert_cells(id);
+        id
+    }
+
+    fn update(&mut self, leaf: NodeId, aabb: Aabb) {
+        if self.leaf(leaf).aabb.contains(&aabb) {
+            return; // still inside fat AABB, no cell changes needed
+        }
+        self.remove_cells(leaf);
+        self.leaf_mut(leaf).aabb = Self::expand_aabb(aabb, self.fat_margin);
+        self.insert_cells(leaf);
+    }
+
+    fn remove(&mut self, leaf: NodeId) {
+        self.remove_cells(leaf);
+        self.leaves[leaf.get() - 1] = None;
+        self.free_list.push(leaf);
+    }
+
+    fn query(&self, aabb: Aabb, callback: &mut dyn FnMut(Entity)) {
+        let (min, max) = self.cell_range(&aabb);
+        if Self::cell_count(min, max) > self.live_leaves() as u64 {
+            // Visiting every cell would cost more than testing every leaf
+            for leaf in self.leaves.iter().flatten() {
+                if leaf.aabb.intersects(&aabb) {
+                    callback(leaf.entity);
+                }
+            }
+            return;
+        }
+
+        // A leaf spanning several cells must only be reported once
+        let mut candidates: Vec<NodeId> = Self::cells_in(min, max)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .chain(&self.oversized)
+            .copied()
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        for id in candidates {
+            let leaf = self.leaf(id);
+            if leaf.aabb.intersects(&aabb) {
+                callback(leaf.entity);
+            }
+        }
+    }
+
+    fn rebuild_from(&mut self, leaves: Vec<(Entity, Aabb)>) -> Vec<(Entity, NodeId)> {
+        let fat_margin = self.fat_margin;
+        *self = Self::new(self.cell_size);
+        self.fat_margin = fat_margin;
+        leaves
+            .into_iter()
+            .map(|(entity, aabb)| (entity, self.allocate_leaf(entity, aabb)))
+            .collect()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let mut listed = 0u64;
+        for (cell, ids) in &self.cells {
+            if ids.is_empty() {
+                return Err(format!("cell {cell} is empty but still stored"));
+            }
+            for id in ids {
+                let Some(leaf) = self.leaves[id.get() - 1].as_ref() else {
+                    return Err(format!("cell {cell} lists removed leaf {id}"));
+                };
+                let (min, max) = self.cell_range(&leaf.aabb);
+                if leaf.oversized || cell.cmplt(min).any() || cell.cmpgt(max).any() {
+                    return Err(format!("cell {cell} lists leaf {id} outside its bounds"));
+                }
+            }
+            listed += ids.len() as u64;
+        }
+
+        let mut expected = 0u64;
+        for (index, leaf) in self.leaves.iter().enumerate() {
+            let Some(leaf) = leaf else {
+                continue;
+            };
+            let id = NonZeroUsize::new(index + 1).unwrap();
+            if leaf.oversized {
+                if !self.oversized.contains(&id) {
+                    return Err(format!("oversized leaf {id} is not in the oversized list"));
+                }
+                continue;
+            }
+            let (min, max) = self.cell_range(&leaf.aabb);
+            expected += Self::cell_count(min, max);
+        }
+        if listed != expected {
+            return Err(format!(
+                "cells list {listed} leaf entries, leaves cover {expected} cells"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::physics::dynamic_aabb_tree::DynamicAabbTree;
+
+    use super::*;
+
+    fn make_aabb(center: Vec3, half_extent: f32) -> Aabb {
+        Aabb {
+            min: center - Vec3::splat(half_extent),
+            max: center + Vec3::splat(half_extent),
+        }
+    }
+
+    fn sorted_hits(broadphase: &dyn Broadphase, aabb: Aabb) -> Vec<Entity> {
+        let mut hits = Vec::new();
+        broadphase.query(aabb, &mut |entity| hits.push(entity));
+        hits.sort();
+        hits
+    }
+
+    #[test]
+    fn hash_reports_the_same_candidates_as_the_tree() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut tree = DynamicAabbTree::default();
+        let mut hash = SpatialHashBroadphase::default();
+
+        // A ground slab far larger than the cells, plus a crowd of similar crates.
+        let mut bodies = vec![(
+            Entity::from_bits(1),
+            Aabb {
+                min: Vec3::new(-500.0, -500.0, -1.0),
+                max: Vec3::new(500.0, 500.0, 0.0),
+            },
+        )];
+        for i in 0..200u64 {
+            let center = Vec3::new(
+                rng.random_range(-20.0..20.0),
+                rng.random_range(-20.0..20.0),
+                rng.random_range(0.5..10.0),
+            );
+            bodies.push((
+                Entity::from_bits(i + 2),
+                make_aabb(center, rng.random_range(0.3..0.7)),
+            ));
+        }
+        let leaves: Vec<(NodeId, NodeId)> = bodies
+            .iter()
+            .map(|(entity, aabb)| {
+                (
+                    DynamicAabbTree::allocate_leaf(&mut tree, *entity, *aabb),
+                    Broadphase::allocate_leaf(&mut hash, *entity, *aabb),
+                )
+            })
+            .collect();
+        assert_eq!(hash.validate(), Ok(()));
+
+        let queries: Vec<Aabb> = (0..50)
+            .map(|_| {
+                let center = Vec3::new(
+                    rng.random_range(-25.0..25.0),
+                    rng.random_range(-25.0..25.0),
+                    rng.random_range(-2.0..12.0),
+                );
+                make_aabb(center, rng.random_range(0.2..3.0))
+            })
+            .chain([make_aabb(Vec3::ZERO, 100.0)])
+            .collect();
+
+        // Leaves hold identical fat AABBs right after insertion.
+        for query in &queries {
+            assert_eq!(sorted_hits(&hash, *query), sorted_hits(&tree, *query));
+        }
+
+        // After moving, the two may keep different fat AABBs, so both must report every
+        // tight overlap and nothing beyond the fat margin plus the move.
+        let max_step = 0.3;
+        for ((_, aabb), (tree_leaf, hash_leaf)) in bodies.iter_mut().zip(&leaves).skip(1) {
+            let offset = Vec3::new(
+                rng.random_range(-max_step..max_step),
+                rng.random_range(-max_step..max_step),
+                rng.random_range(-max_step..max_step),
+            );
+            *aabb = Aabb {
+                min: aabb.min + offset,
+                max: aabb.max + offset,
+            };
+            DynamicAabbTree::update(&mut tree, *tree_leaf, *aabb);
+            Broadphase::update(&mut hash, *hash_leaf, *aabb);
+        }
+        assert_eq!(hash.validate(), Ok(()));
+
+        let reach = DEFAULT_FAT_MARGIN + max_step;
+        for query in &queries {
+            let overlapping = |margin: f32| -> Vec<Entity> {
+                let mut entities: Vec<Entity> = bodies
+                    .iter()
+                    .filter(|(_, aabb)| {
+                        SpatialHashBroadphase::expand_aabb(*aabb, margin).intersects(query)
+                    })
+                    .map(|(entity, _)| *entity)
+                    .collect();
+                entities.sort();
+                entities
+            };
+            let exact = overlapping(0.0);
+            let loose = overlapping(reach);
+            for hits in [sorted_hits(&hash, *query), sorted_hits(&tree, *query)] {
+                assert!(exact.iter().all(|entity| hits.contains(entity)));
+                assert!(hits.iter().all(|entity| loose.contains(entity)));
+            }
+        }
+    }
+}
@@ -4,5 +4,7 @@ pub mod render_body_resource;
 pub mod render_instance;
 pub mod render_queue;
 pub mod render_system;
+pub mod render_to_texture;
 pub mod renderer;
 pub mod renderer_backends;
+pub mod screenshot;
@@ -1,13 +1,26 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
 
 use bevy_ecs::prelude::*;
 use slotmap::SlotMap;
 
-use crate::{RenderBodyHandle, render::render_body::RenderBody};
+use crate::{
+    RenderBodyHandle,
+    assets::{mesh::FrontFace, ref_counts::RefCounts},
+    render::render_body::RenderBody,
+};
 
 #[derive(Default)]
 pub struct RenderBodyStorage {
     pub render_bodies: SlotMap<RenderBodyHandle, RenderBody>,
+    /// References held by `RenderBodyComponent`s.
+    pub(crate) refs: RefCounts<RenderBodyHandle>,
+    /// Render bodies loaded by `Engine::load_model`, keyed by the path they came from and the
+    /// winding they were loaded with.
+    model_cache: HashMap<(PathBuf, FrontFace), RenderBodyHandle>,
 }
 
 #[derive(Resource, Default, Clone)]
@@ -32,6 +45,22 @@ impl RenderBodyResource {
             }
         }
     }
+
+    /// Frees the render body behind `handle`. Its meshes and materials are left loaded.
+    /// While a `RenderBodyComponent` still uses it, the unload is deferred until it is removed.
+    /// Returns `false` if the handle was already unloaded.
+    pub fn unload(&self, handle: RenderBodyHandle) -> bool {
+        let mut storage = self.write();
+        if !storage.render_bodies.contains_key(handle) {
+            return false;
+        }
+        if storage.refs.is_referenced(handle) {
+            storage.refs.defer_unload(handle);
+        } else {
+            storage.remove_render_body(handle);
+        }
+        true
+    }
 }
 
 impl RenderBodyStorage {
@@ -51,8 +80,35 @@ impl RenderBodyStorage {
         self.render_bodies.get_mut(render_body_id)
     }
 
-    #[allow(dead_code)]
-    pub fn remove_render_body(&mut self, render_body_id: RenderBodyHandle) {
-        self.render_bodies.remove(render_body_id);
+    pub fn remove_render_body(&mut self, render_body_id: RenderBodyHandle) -> bool {
+        self.render_bodies.remove(render_body_id).is_some()
+    }
+
+    /// The render body previously loaded from `path` with `front_face`, if it has not been
+    /// unloaded since.
+    pub fn cached_model(&self, path: &Path, front_face: FrontFace) -> Option<RenderBodyHandle> {
+        self.model_cache
+            .get(&(path.to_path_buf(), front_face))
+            .copied()
+            .filter(|handle| self.render_bodies.contains_key(*handle))
+    }
+
+    pub fn cache_model(
+        &mut self,
+        path: PathBuf,
+        front_face: FrontFace,
+        render_body_id: RenderBodyHandle,
+    ) {
+        self.model_cache.insert((path, front_face), render_body_id);
+    }
+
+    pub(crate) fn acquire_render_body(&mut self, render_body_id: RenderBodyHandle) {
+        self.refs.acquire(render_body_id);
+    }
+
+    pub(crate) fn release_render_body(&mut self, render_body_id: RenderBodyHandle) {
+        if self.refs.release(render_body_id) {
+            self.remove_render_body(render_body_id);
+        }
     }
 }
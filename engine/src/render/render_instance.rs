@@ -1,8 +1,10 @@
 use crate::assets::handles::*;
+use bevy_ecs::entity::Entity;
 use glam::Mat4;
 
 #[derive(Debug, Clone)]
 pub struct RenderInstance {
+    pub entity: Entity,
     pub mesh_id: MeshHandle,
     pub transform: Mat4,
     pub material_id: MaterialHandle,
@@ -1,8 +1,12 @@
-use bevy_ecs::prelude::{Query, Res, ResMut};
+use std::collections::HashSet;
+
+use bevy_ecs::prelude::{Entity, Local, Query, Res, ResMut};
 
 use crate::{
+    RenderBodyHandle,
     components::{
-        render_body_component::RenderBodyComponent, transform_component::TransformComponent,
+        material_component::MaterialComponent, render_body_component::RenderBodyComponent,
+        transform_component::TransformComponent,
     },
     render::{
         render_body_resource::RenderBodyResource, render_instance::RenderInstance,
@@ -14,26 +18,212 @@ pub struct RenderSystem {}
 
 impl RenderSystem {
     pub fn build_render_queue(
-        query: Query<(&TransformComponent, &RenderBodyComponent)>,
+        query: Query<(
+            Entity,
+            &TransformComponent,
+            &RenderBodyComponent,
+            Option<&MaterialComponent>,
+        )>,
         render_body_resource: Res<RenderBodyResource>,
         mut queue: ResMut<RenderQueue>,
+        mut missing_bodies: Local<HashSet<RenderBodyHandle>>,
     ) {
         queue.instances.clear();
 
-        for (transform, render_body) in &query {
-            let guard = render_body_resource.read();
-            let body = guard
-                .get_render_body(render_body.render_body_id)
-                .expect("RenderBody not found");
+        let guard = render_body_resource.read();
+        for (entity, transform, render_body, material_override) in &query {
+            let Some(body) = guard.get_render_body(render_body.render_body_id) else {
+                // Logged once per handle; the entity is skipped every frame until fixed
+                if missing_bodies.insert(render_body.render_body_id) {
+                    log::warn!(
+                        "Skipping {entity:?}: render body {:?} is no longer loaded",
+                        render_body.render_body_id
+                    );
+                }
+                continue;
+            };
 
             let world_transform = transform.to_mat4();
             for part in &body.parts {
                 queue.instances.push(RenderInstance {
+                    entity,
                     mesh_id: part.mesh_id,
                     transform: world_transform * part.local_transform,
-                    material_id: part.material_id,
+                    material_id: material_override
+                        .map_or(part.material_id, |material| material.material_id),
                 });
             }
         }
+
+        // Query iteration order isn't guaranteed, so sort for a stable draw order. The sort is
+        // stable, keeping the part order of a render body for equal keys.
+        queue
+            .instances
+            .sort_by_key(|instance| (instance.mesh_id, instance.material_id, instance.entity));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{system::RunSystemOnce, world::World};
+    use glam::{Mat4, Vec3};
+    use slotmap::SlotMap;
+
+    use super::*;
+    use crate::{
+        assets::handles::{MaterialHandle, MeshHandle},
+        render::render_body::{RenderBody, RenderBodyPart},
+    };
+
+    fn instance_keys(world: &mut World) -> Vec<(MeshHandle, MaterialHandle, Entity)> {
+        world
+            .run_system_once(RenderSystem::build_render_queue)
+            .unwrap();
+        world
+            .resource::<RenderQueue>()
+            .instances
+            .iter()
+            .map(|i| (i.mesh_id, i.material_id, i.entity))
+            .collect()
+    }
+
+    #[test]
+    fn render_queue_order_is_deterministic() {
+        let mut meshes: SlotMap<MeshHandle, ()> = SlotMap::with_key();
+        let mut materials: SlotMap<MaterialHandle, ()> = SlotMap::with_key();
+        let mesh_a = meshes.insert(());
+        let mesh_b = meshes.insert(());
+        let material = materials.insert(());
+
+        let part = |mesh_id| RenderBodyPart {
+            mesh_id,
+            material_id: material,
+            local_transform: Mat4::IDENTITY,
+        };
+        let render_bodies = RenderBodyResource::default();
+        let (body_ab, body_b) = {
+            let mut storage = render_bodies.write();
+            (
+                storage.add_render_body(RenderBody::new(vec![part(mesh_b), part(mesh_a)])),
+                storage.add_render_body(RenderBody::new(vec![part(mesh_b)])),
+            )
+        };
+
+        let mut world = World::new();
+        world.insert_resource(render_bodies);
+        world.insert_resource(RenderQueue::default());
+        for (i, body) in [body_b, body_ab, body_b, body_ab].into_iter().enumerate() {
+            world.spawn((
+                TransformComponent {
+                    position: Vec3::new(i as f32, 0.0, 0.0),
+                    ..Default::default()
+                },
+                RenderBodyComponent {
+                    render_body_id: body,
+                },
+            ));
+        }
+
+        let first = instance_keys(&mut world);
+        let second = instance_keys(&mut world);
+
+        assert_eq!(first.len(), 6);
+        assert_eq!(first, second);
+        assert!(first.is_sorted());
+    }
+
+    #[test]
+    fn material_component_overrides_the_render_body_material() {
+        let mut meshes: SlotMap<MeshHandle, ()> = SlotMap::with_key();
+        let mut materials: SlotMap<MaterialHandle, ()> = SlotMap::with_key();
+        let mesh = meshes.insert(());
+        let baked = materials.insert(());
+        let override_material = materials.insert(());
+
+        let render_bodies = RenderBodyResource::default();
+        let body = render_bodies
+            .write()
+            .add_render_body(RenderBody::new(vec![RenderBodyPart {
+                mesh_id: mesh,
+                material_id: baked,
+                local_transform: Mat4::IDENTITY,
+            }]));
+
+        let mut world = World::new();
+        world.insert_resource(render_bodies);
+        world.insert_resource(RenderQueue::default());
+        let plain = world
+            .spawn((
+                TransformComponent::default(),
+                RenderBodyComponent {
+                    render_body_id: body,
+                },
+            ))
+            .id();
+        let recolored = world
+            .spawn((
+                TransformComponent::default(),
+                RenderBodyComponent {
+                    render_body_id: body,
+                },
+                MaterialComponent {
+                    material_id: override_material,
+                },
+            ))
+            .id();
+
+        let keys = instance_keys(&mut world);
+
+        assert!(keys.contains(&(mesh, baked, plain)));
+        assert!(keys.contains(&(mesh, override_material, recolored)));
+        assert_eq!(keys.len(), 2);
+    }
+
+    #[test]
+    fn entities_whose_render_body_was_removed_are_skipped() {
+        let mut meshes: SlotMap<MeshHandle, ()> = SlotMap::with_key();
+        let mut materials: SlotMap<MaterialHandle, ()> = SlotMap::with_key();
+        let mesh = meshes.insert(());
+        let material = materials.insert(());
+
+        let render_bodies = RenderBodyResource::default();
+        let body = || {
+            RenderBody::new(vec![RenderBodyPart {
+                mesh_id: mesh,
+                material_id: material,
+                local_transform: Mat4::IDENTITY,
+            }])
+        };
+        let (kept, removed) = {
+            let mut storage = render_bodies.write();
+            (
+                storage.add_render_body(body()),
+                storage.add_render_body(body()),
+            )
+        };
+
+        let mut world = World::new();
+        world.insert_resource(render_bodies.clone());
+        world.insert_resource(RenderQueue::default());
+        let visible = world
+            .spawn((
+                TransformComponent::default(),
+                RenderBodyComponent {
+                    render_body_id: kept,
+                },
+            ))
+            .id();
+        world.spawn((
+            TransformComponent::default(),
+            RenderBodyComponent {
+                render_body_id: removed,
+            },
+        ));
+
+        // Bypasses the deferred unload, as a resource swap would
+        assert!(render_bodies.write().remove_render_body(removed));
+
+        assert_eq!(instance_keys(&mut world), vec![(mesh, material, visible)]);
+        assert_eq!(instance_keys(&mut world), vec![(mesh, material, visible)]);
     }
 }
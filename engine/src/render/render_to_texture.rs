@@ -0,0 +1,169 @@
+use bevy_ecs::entity::Entity;
+use glow::HasContext;
+
+use crate::{
+    Engine,
+    assets::{
+        handles::TextureHandle, material_resource::MaterialResource, mesh_resource::MeshResource,
+        shader_resource::ShaderResource, texture_resource::TextureResource,
+    },
+    render::{render_queue::RenderQueue, renderer::RenderParams},
+};
+
+impl Engine {
+    /// Renders the scene from `camera` into a new `width` x `height` texture, e.g. for a
+    /// minimap, a mirror or a security camera screen. Returns `None` if `camera` has no
+    /// `CameraComponent`. Refresh the texture on later frames with [`Self::render_camera_into`]
+    /// rather than creating a new one each frame.
+    pub fn render_to_texture(
+        &mut self,
+        camera: Entity,
+        width: u32,
+        height: u32,
+    ) -> Option<TextureHandle> {
+        Self::camera_render_data(&mut self.scene.world, camera, width, height)?;
+        let target = self
+            .scene
+            .world
+            .resource::<TextureResource>()
+            .write()
+            .create_render_target(width, height);
+        self.render_camera_into(camera, target);
+        Some(target)
+    }
+
+    /// Renders the scene as last queued from `camera` into `target`, allocating the texture's
+    /// GPU storage on first use. A material that samples `target` must not be visible to
+    /// `camera`. Returns `false` if the camera or the texture is missing.
+    pub fn render_camera_into(&mut self, camera: Entity, target: TextureHandle) -> bool {
+        let textures = self.scene.world.resource::<TextureResource>().clone();
+        let (width, height, color) = {
+            let mut storage = textures.write();
+            let Some(texture) = storage.textures.get_mut(target) else {
+                return false;
+            };
+            let color = *texture.gl_tex.get_or_insert_with(|| {
+                allocate_color_texture(&self.gl, texture.width, texture.height)
+            });
+            (texture.width, texture.height, color)
+        };
+        let Some(camera_data) =
+            Self::camera_render_data(&mut self.scene.world, camera, width, height)
+        else {
+            return false;
+        };
+
+        let gl = self.gl.clone();
+        let (framebuffer, depth) = unsafe {
+            let framebuffer = gl
+                .create_framebuffer()
+                .expect("Failed to create framebuffer");
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+            gl.framebuffer_texture_2d(
+                glow::FRAMEBUFFER,
+                glow::COLOR_ATTACHMENT0,
+                glow::TEXTURE_2D,
+                Some(color),
+                0,
+            );
+            let depth = gl
+                .create_renderbuffer()
+                .expect("Failed to create renderbuffer");
+            gl.bind_renderbuffer(glow::RENDERBUFFER, Some(depth));
+            gl.renderbuffer_storage(
+                glow::RENDERBUFFER,
+                glow::DEPTH_COMPONENT24,
+                width as i32,
+                height as i32,
+            );
+            gl.framebuffer_renderbuffer(
+                glow::FRAMEBUFFER,
+                glow::DEPTH_ATTACHMENT,
+                glow::RENDERBUFFER,
+                Some(depth),
+            );
+            gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+            (framebuffer, depth)
+        };
+
+        let complete =
+            unsafe { gl.check_framebuffer_status(glow::FRAMEBUFFER) } == glow::FRAMEBUFFER_COMPLETE;
+        if complete {
+            let world = &self.scene.world;
+            self.renderer.stage_instances(
+                &world
+                    .get_resource::<RenderQueue>()
+                    .expect("RenderQueue resource not found")
+                    .instances,
+            );
+            self.renderer.render(
+                RenderParams { width, height },
+                &world
+                    .get_resource::<MeshResource>()
+                    .expect("MeshResource resource not found")
+                    .read(),
+                &world
+                    .get_resource::<MaterialResource>()
+                    .expect("MaterialResource resource not found")
+                    .read(),
+                &textures.read(),
+                &world
+                    .get_resource::<ShaderResource>()
+                    .expect("ShaderResource resource not found")
+                    .read(),
+                Some(camera_data),
+            );
+        } else {
+            log::error!(target: "gl", "Render target framebuffer for {target:?} is incomplete");
+        }
+
+        unsafe {
+            gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+            gl.delete_framebuffer(framebuffer);
+            gl.delete_renderbuffer(depth);
+        }
+        complete
+    }
+}
+
+/// Allocates uninitialized RGBA storage for a texture that will be rendered into.
+fn allocate_color_texture(gl: &glow::Context, width: u32, height: u32) -> glow::Texture {
+    unsafe {
+        let texture = gl.create_texture().expect("Failed to create texture");
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA8 as i32,
+            width as i32,
+            height as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            glow::PixelUnpackData::Slice(None),
+        );
+        // No mipmaps are generated for render targets
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_MIN_FILTER,
+            glow::LINEAR as i32,
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_MAG_FILTER,
+            glow::LINEAR as i32,
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_WRAP_S,
+            glow::CLAMP_TO_EDGE as i32,
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_WRAP_T,
+            glow::CLAMP_TO_EDGE as i32,
+        );
+        gl.bind_texture(glow::TEXTURE_2D, None);
+        texture
+    }
+}
@@ -1,4 +1,11 @@
-use std::{collections::HashMap, mem::offset_of, ops::Range, rc::Rc};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::Hash,
+    mem::offset_of,
+    ops::Range,
+    rc::Rc,
+};
 
 use glam::{Mat4, Vec3};
 use glow::{Context as GlowContext, HasContext};
@@ -6,9 +13,9 @@ use slotmap::SecondaryMap;
 
 use crate::{
     assets::{
-        handles::{MaterialHandle, MeshHandle, ShaderHandle},
+        handles::{MaterialHandle, MeshHandle, ShaderHandle, TextureHandle},
         material_resource::MaterialStorage,
-        mesh::{Mesh, Vertex},
+        mesh::{FrontFace, Mesh, Vertex},
         mesh_resource::MeshStorage,
         shader::{
             InputRate::{PerInstance, PerVertex},
@@ -27,6 +34,22 @@ pub struct Renderer {
     vao_cache: HashMap<VaoKey, glow::VertexArray>,
     mesh_render_data: SecondaryMap<MeshHandle, MeshRenderData>,
     frame_data: PersistentFrameData,
+    missing_assets: MissingAssetWarnings,
+}
+
+/// Stale handles the renderer has already warned about, so each is logged once rather than
+/// every frame it is skipped.
+#[derive(Default)]
+struct MissingAssetWarnings {
+    meshes: HashSet<MeshHandle>,
+    materials: HashSet<MaterialHandle>,
+    textures: HashSet<TextureHandle>,
+}
+
+fn warn_missing_once<H: Copy + Eq + Hash + Debug>(warned: &mut HashSet<H>, kind: &str, handle: H) {
+    if warned.insert(handle) {
+        log::warn!("Skipping {kind} {handle:?}, which is no longer loaded");
+    }
 }
 
 pub struct MeshRenderData {
@@ -110,6 +133,7 @@ impl Renderer {
                 vao_cache: HashMap::with_capacity(256),
                 frame_data: PersistentFrameData::default(),
                 mesh_render_data: SecondaryMap::with_capacity(256),
+                missing_assets: MissingAssetWarnings::default(),
             }
         }
     }
@@ -194,6 +218,7 @@ impl Renderer {
             &self.frame_data.input_instances,
             mesh_resource,
             &view_proj,
+            &mut self.missing_assets.meshes,
         );
 
         Self::material_batcher(
@@ -209,9 +234,10 @@ impl Renderer {
                 .mesh_batches
                 .clone();
 
-            let material = material_resource
-                .get_material(material_id)
-                .expect("Material not found");
+            let Some(material) = material_resource.get_material(material_id) else {
+                warn_missing_once(&mut self.missing_assets.materials, "material", material_id);
+                continue;
+            };
             let shader = shader_resource
                 .get_shader(material.desc.shader)
                 .expect("Shader not found");
@@ -228,6 +254,7 @@ impl Renderer {
                     &loc,
                     &UniformValue::Mat4(self.frame_data.frame_uniforms.view_proj),
                     texture_resource,
+                    &mut self.missing_assets.textures,
                 );
             }
             if let Some(loc) = shader.get_uniform("u_camera_position") {
@@ -236,6 +263,7 @@ impl Renderer {
                     &loc,
                     &UniformValue::Vec3(self.frame_data.frame_uniforms.camera_position),
                     texture_resource,
+                    &mut self.missing_assets.textures,
                 );
             }
             if let Some(loc) = shader.get_uniform("u_light_direction") {
@@ -244,6 +272,7 @@ impl Renderer {
                     &loc,
                     &UniformValue::Vec3(self.frame_data.frame_uniforms.light_direction),
                     texture_resource,
+                    &mut self.missing_assets.textures,
                 );
             }
             if let Some(loc) = shader.get_uniform("u_light_color") {
@@ -252,6 +281,7 @@ impl Renderer {
                     &loc,
                     &UniformValue::Vec3(self.frame_data.frame_uniforms.light_color),
                     texture_resource,
+                    &mut self.missing_assets.textures,
                 );
             }
 
@@ -260,7 +290,13 @@ impl Renderer {
             // 2. Bind material uniforms
             for (name, value) in &material.desc.params {
                 if let Some(loc) = shader.get_uniform(name) {
-                    textures_bound += Self::bind_uniform(&gl, &loc, value, texture_resource);
+                    textures_bound += Self::bind_uniform(
+                        &gl,
+                        &loc,
+                        value,
+                        texture_resource,
+                        &mut self.missing_assets.textures,
+                    );
                 }
             }
 
@@ -270,13 +306,17 @@ impl Renderer {
                 let matrices_range = self.frame_data.mesh_batch_ranges[mesh_idx].matrices.clone();
                 let matrices_slice = &self.frame_data.instance_matrices[matrices_range];
 
+                let Some(mesh) = mesh_resource.get_mesh(mesh_id) else {
+                    warn_missing_once(&mut self.missing_assets.meshes, "mesh", mesh_id);
+                    continue;
+                };
                 let vao = Self::get_or_create_vao(
                     &mut self.vao_cache,
                     &gl,
                     mesh_id,
+                    mesh,
                     &material.desc.shader,
                     shader_resource,
-                    mesh_resource,
                     &mut self.mesh_render_data,
                 );
                 Self::update_instance_buffer(
@@ -286,15 +326,14 @@ impl Renderer {
                     matrices_slice,
                 );
 
-                let index_count: i32 = {
-                    mesh_resource
-                        .get_mesh(mesh_id)
-                        .unwrap_or_else(|| panic!("Couldn't find mesh: {:?}", mesh_id))
-                        .indices
-                        .len() as i32
+                let index_count = mesh.indices.len() as i32;
+                let front_face = match mesh.front_face {
+                    FrontFace::Cw => glow::CW,
+                    FrontFace::Ccw | FrontFace::Auto => glow::CCW,
                 };
 
                 unsafe {
+                    gl.front_face(front_face);
                     gl.bind_vertex_array(Some(vao));
                     gl.draw_elements_instanced(
                         glow::TRIANGLES,
@@ -318,6 +357,7 @@ impl Renderer {
 
         unsafe {
             gl.bind_vertex_array(None);
+            gl.front_face(glow::CCW);
             gl.viewport(
                 saved_viewport[0],
                 saved_viewport[1],
@@ -396,14 +436,16 @@ impl Renderer {
         instances: &[RenderInstance],
         mesh_resource: &MeshStorage,
         view_proj: &Mat4,
+        missing_meshes: &mut HashSet<MeshHandle>,
     ) {
         visible_instances.clear();
         let frustum = Frustum::from_view_proj(view_proj);
 
         for inst in instances {
-            let mesh = mesh_resource
-                .get_mesh(inst.mesh_id)
-                .expect("Mesh not found");
+            let Some(mesh) = mesh_resource.get_mesh(inst.mesh_id) else {
+                warn_missing_once(missing_meshes, "mesh", inst.mesh_id);
+                continue;
+            };
 
             let scale = Self::max_scale(inst.transform);
             let world_center = inst.transform.transform_point3(mesh.sphere_center);
@@ -472,9 +514,9 @@ impl Renderer {
         mesh_render_data: &mut SecondaryMap<MeshHandle, MeshRenderData>,
         instance_matrices: &[[f32; 16]],
     ) {
-        let mesh_data = mesh_render_data
-            .get_mut(mesh_handle)
-            .expect("Mesh not found");
+        let Some(mesh_data) = mesh_render_data.get_mut(mesh_handle) else {
+            return;
+        };
         if instance_matrices.is_empty() {
             mesh_data.instance_count = 0;
             return;
@@ -494,13 +536,29 @@ impl Renderer {
         }
     }
 
+    /// Byte offset within `Vertex` of the per-vertex shader attribute called `name`, or `None`
+    /// if the vertex format has no such field and the attribute should stay disabled.
+    fn vertex_attrib_offset(name: &str) -> Option<i32> {
+        let offset = match name {
+            "position" => offset_of!(Vertex, position),
+            "normal" => offset_of!(Vertex, normal),
+            "barycentric" => offset_of!(Vertex, barycentric),
+            "uv_albedo" => offset_of!(Vertex, uv_albedo),
+            "uv_normal" => offset_of!(Vertex, uv_normal),
+            "tangent" => offset_of!(Vertex, tangent),
+            "color" => offset_of!(Vertex, color),
+            _ => return None,
+        };
+        Some(offset as i32)
+    }
+
     fn get_or_create_vao(
         vao_cache: &mut HashMap<VaoKey, glow::VertexArray>,
         gl: &glow::Context,
         mesh: MeshHandle,
+        mesh_asset: &Mesh,
         shader: &ShaderHandle,
         shader_resource: &ShaderStorage,
-        mesh_resource: &MeshStorage,
         mesh_render_data: &mut SecondaryMap<MeshHandle, MeshRenderData>,
     ) -> glow::VertexArray {
         let key = VaoKey {
@@ -513,8 +571,7 @@ impl Renderer {
         }
 
         if !mesh_render_data.contains_key(mesh) {
-            let mesh_data = mesh_resource.get_mesh(mesh).expect("Mesh not found");
-            Self::upload_mesh_to_gpu(gl, mesh_data, mesh, mesh_render_data);
+            Self::upload_mesh_to_gpu(gl, mesh_asset, mesh, mesh_render_data);
         }
 
         let mesh_data = mesh_render_data.get(mesh).unwrap();
@@ -564,18 +621,7 @@ impl Renderer {
                         }
                     }
                     PerVertex => {
-                        if let Some(offset) = {
-                            let name: &str = &attrib.name;
-                            match name {
-                                "position" => Some(offset_of!(Vertex, position) as i32),
-                                "normal" => Some(offset_of!(Vertex, normal) as i32),
-                                "barycentric" => Some(offset_of!(Vertex, barycentric) as i32),
-                                "uv_albedo" => Some(offset_of!(Vertex, uv_albedo) as i32),
-                                "uv_normal" => Some(offset_of!(Vertex, uv_normal) as i32),
-                                "tangent" => Some(offset_of!(Vertex, tangent) as i32),
-                                _ => None,
-                            }
-                        } {
+                        if let Some(offset) = Self::vertex_attrib_offset(&attrib.name) {
                             gl.bind_buffer(glow::ARRAY_BUFFER, Some(mesh_data.vbo.unwrap()));
                             gl.enable_vertex_attrib_array(location);
                             let (size, ty) = match attrib.ty {
@@ -604,24 +650,53 @@ impl Renderer {
         }
     }
 
-    /// Deletes a mesh's GPU resources
-    #[allow(dead_code)]
-    pub fn delete_mesh_gpu(&mut self, mesh_handle: MeshHandle) {
-        let mesh_data = self
-            .mesh_render_data
-            .get_mut(mesh_handle)
-            .expect("Mesh not found");
+    /// Deletes the GPU objects of meshes and textures unloaded since the last frame, and of
+    /// meshes modified since then, which are uploaded again when next drawn.
+    pub fn release_unloaded(
+        &mut self,
+        mesh_resource: &mut MeshStorage,
+        texture_resource: &mut TextureStorage,
+    ) {
+        for mesh_handle in mesh_resource.unloaded.drain(..) {
+            self.delete_mesh_gpu(mesh_handle);
+        }
+        for mesh_handle in mesh_resource.modified.drain(..) {
+            self.delete_mesh_gpu(mesh_handle);
+        }
+        for texture in texture_resource.unloaded.drain(..) {
+            unsafe {
+                self.gl.delete_texture(texture);
+            }
+        }
+    }
+
+    /// Deletes a mesh's GPU resources, including every VAO built for it
+    fn delete_mesh_gpu(&mut self, mesh_handle: MeshHandle) {
+        let gl = &self.gl;
+        self.vao_cache.retain(|key, vao| {
+            if key.mesh != mesh_handle {
+                return true;
+            }
+            unsafe {
+                gl.delete_vertex_array(*vao);
+            }
+            false
+        });
+
+        // Meshes that were never drawn have nothing uploaded
+        let Some(mut mesh_data) = self.mesh_render_data.remove(mesh_handle) else {
+            return;
+        };
         unsafe {
             if let Some(vbo) = mesh_data.vbo.take() {
-                self.gl.delete_buffer(vbo);
+                gl.delete_buffer(vbo);
             }
             if let Some(ebo) = mesh_data.ebo.take() {
-                self.gl.delete_buffer(ebo);
+                gl.delete_buffer(ebo);
             }
             if let Some(inst) = mesh_data.instance_vbo.take() {
-                self.gl.delete_buffer(inst);
+                gl.delete_buffer(inst);
             }
-            mesh_data.instance_count = 0;
         }
     }
 
@@ -632,6 +707,7 @@ impl Renderer {
         loc: &glow::UniformLocation,
         value: &UniformValue,
         texture_resource: &TextureStorage,
+        missing_textures: &mut HashSet<TextureHandle>,
     ) -> u32 {
         unsafe {
             match value {
@@ -652,9 +728,10 @@ impl Renderer {
                     0
                 }
                 UniformValue::Texture { handle, unit } => {
-                    let tex = texture_resource
-                        .get_texture(*handle)
-                        .expect("Texture missing");
+                    let Some(tex) = texture_resource.get_texture(*handle) else {
+                        warn_missing_once(missing_textures, "texture", *handle);
+                        return 0;
+                    };
 
                     gl.active_texture(glow::TEXTURE0 + *unit);
                     gl.bind_texture(glow::TEXTURE_2D, tex.gl_tex);
@@ -709,3 +786,48 @@ impl Renderer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vertex_color_attribute_maps_to_the_color_field() {
+        assert_eq!(
+            Renderer::vertex_attrib_offset("color"),
+            Some(offset_of!(Vertex, color) as i32)
+        );
+        assert_eq!(Renderer::vertex_attrib_offset("not_an_attribute"), None);
+    }
+
+    #[test]
+    fn frustum_culling_skips_instances_of_unloaded_meshes() {
+        let mut meshes = MeshStorage::default();
+        let kept = meshes.add_mesh(Mesh {
+            sphere_radius: 1.0,
+            ..Default::default()
+        });
+        let unloaded = meshes.add_mesh(Mesh::default());
+        assert!(meshes.unload_mesh(unloaded));
+
+        let instance = |mesh_id| RenderInstance {
+            entity: bevy_ecs::entity::Entity::PLACEHOLDER,
+            mesh_id,
+            transform: Mat4::IDENTITY,
+            material_id: MaterialHandle::default(),
+        };
+        let instances = vec![instance(unloaded), instance(kept), instance(unloaded)];
+        let view_proj = Mat4::perspective_rh(1.0, 1.0, 0.1, 100.0)
+            * Mat4::look_at_rh(Vec3::new(0.0, 0.0, 5.0), Vec3::ZERO, Vec3::Y);
+        let mut visible = Vec::new();
+        let mut missing = HashSet::new();
+
+        for _ in 0..2 {
+            Renderer::frustum_culling(&mut visible, &instances, &meshes, &view_proj, &mut missing);
+        }
+
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].mesh_id, kept);
+        assert_eq!(missing, HashSet::from([unloaded]));
+    }
+}
@@ -0,0 +1,92 @@
+use std::path::{Path, PathBuf};
+
+use bevy_ecs::prelude::*;
+use glow::HasContext;
+
+use crate::Engine;
+
+/// Lets game systems ask for a screenshot; the engine captures it after the next render.
+#[derive(Resource, Default)]
+pub struct ScreenshotRequestResource {
+    pending_path: Option<PathBuf>,
+}
+
+impl ScreenshotRequestResource {
+    pub fn request(&mut self, path: impl Into<PathBuf>) {
+        self.pending_path = Some(path.into());
+    }
+
+    pub fn take_pending(&mut self) -> Option<PathBuf> {
+        self.pending_path.take()
+    }
+}
+
+impl Engine {
+    /// Reads back the default framebuffer and writes it to `path` as a PNG.
+    /// Call after rendering and before swapping, while the back buffer still holds the frame.
+    pub fn capture_screenshot(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let (width, height) = self.window.drawable_size();
+        let row_bytes = width as usize * 4;
+        let mut pixels = vec![0u8; row_bytes * height as usize];
+
+        unsafe {
+            self.gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+            self.gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+            self.gl.read_pixels(
+                0,
+                0,
+                width as i32,
+                height as i32,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                glow::PixelPackData::Slice(Some(&mut pixels)),
+            );
+        }
+
+        // OpenGL's origin is bottom-left, images expect the first row at the top.
+        flip_rows_vertically(&mut pixels, row_bytes);
+
+        image::save_buffer(
+            path.as_ref(),
+            &pixels,
+            width,
+            height,
+            image::ExtendedColorType::Rgba8,
+        )
+        .map_err(|e| format!("Failed to save screenshot {}: {e}", path.as_ref().display()))
+    }
+}
+
+pub(crate) fn flip_rows_vertically(pixels: &mut [u8], row_bytes: usize) {
+    if row_bytes == 0 {
+        return;
+    }
+    let rows = pixels.len() / row_bytes;
+    for row in 0..rows / 2 {
+        let (top, bottom) = pixels.split_at_mut((rows - 1 - row) * row_bytes);
+        top[row * row_bytes..(row + 1) * row_bytes].swap_with_slice(&mut bottom[..row_bytes]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flip_rows_vertically_reverses_row_order() {
+        let mut pixels: Vec<u8> = (0..12).collect();
+
+        flip_rows_vertically(&mut pixels, 4);
+
+        assert_eq!(pixels, vec![8, 9, 10, 11, 4, 5, 6, 7, 0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn flip_rows_vertically_handles_even_row_count() {
+        let mut pixels = vec![1, 1, 2, 2, 3, 3, 4, 4];
+
+        flip_rows_vertically(&mut pixels, 2);
+
+        assert_eq!(pixels, vec![4, 4, 3, 3, 2, 2, 1, 1]);
+    }
+}
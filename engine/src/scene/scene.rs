@@ -1,11 +1,15 @@
 use bevy_ecs::prelude::*;
 
 use crate::{
-    ActiveCamera, Gravity, TimeResource, WorldBasis,
+    ActiveCamera, DevConsole, FrameStats, Gravity, LogLevel, TimeResource, WorldBasis,
+    assets::material_resource::DefaultMaterial,
     audio::audio_control::AudioControl,
+    components::single_audio_listener_component::ActiveListener,
     input::InputStateResource,
-    physics::physics_resource::{CollisionFrameData, PhysicsFrameData, PhysicsResource},
-    render::render_queue::RenderQueue,
+    physics::physics_resource::{
+        CollisionFrameData, PhysicsFrameData, PhysicsResource, PreSolveHooks,
+    },
+    render::{render_queue::RenderQueue, screenshot::ScreenshotRequestResource},
     scene::{scene_changer_resource::SceneChangerResource, scene_services::SceneServices},
 };
 
@@ -36,10 +40,17 @@ impl Scene {
         world.insert_resource(PhysicsResource::default());
         world.insert_resource(CollisionFrameData::default());
         world.insert_resource(PhysicsFrameData::default());
+        world.insert_resource(PreSolveHooks::default());
         world.insert_resource(TimeResource::new(60, 120));
+        world.insert_resource(FrameStats::default());
         world.insert_resource(Gravity::default());
         world.insert_resource(AudioControl::default());
+        world.insert_resource(ActiveListener::default());
         world.insert_resource(SceneChangerResource::default());
+        world.insert_resource(ScreenshotRequestResource::default());
+        world.insert_resource(DefaultMaterial::default());
+        world.insert_resource(DevConsole::default());
+        world.insert_resource(LogLevel::default());
 
         let game_frame_schedule = Schedule::default();
         let game_simulation_schedule = Schedule::default();
@@ -51,3 +62,16 @@ impl Scene {
         }
     }
 }
+
+impl Drop for Scene {
+    /// Despawns every entity so component hooks release the asset references they hold.
+    /// The asset storages are shared between scenes and would otherwise keep those
+    /// references, and any deferred unloads, forever.
+    fn drop(&mut self) {
+        let entities: Vec<Entity> = self.world.query::<Entity>().iter(&self.world).collect();
+        for entity in entities {
+            // Despawning a parent may already have taken its children with it
+            let _ = self.world.try_despawn(entity);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use bevy_ecs::schedule::SystemSet;
+
+/// Labeled phases of the engine's schedules, in the order they run.
+/// Use these with `Engine::add_system_after` to slot game systems between engine systems.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EngineSet {
+    // Frame schedule, once per rendered frame
+    /// Render queue construction.
+    Render,
+    /// Frame timing.
+    Time,
+    /// Audio command building and spatial audio.
+    Audio,
+
+    // Physics schedule, once per fixed step
+    /// Kinematic movement of bodies without physics.
+    Movement,
+    /// AABB updates, broadphase and manifold generation.
+    Collision,
+    /// Waking, contact solving and resting damping. Velocities are final after this set.
+    Solve,
+    /// Integration of velocities into transforms.
+    Integrate,
+    /// Grounded state and physics event dispatch.
+    Events,
+}
+
+impl EngineSet {
+    pub(crate) const FRAME: [EngineSet; 3] = [EngineSet::Render, EngineSet::Time, EngineSet::Audio];
+    pub(crate) const PHYSICS: [EngineSet; 5] = [
+        EngineSet::Movement,
+        EngineSet::Collision,
+        EngineSet::Solve,
+        EngineSet::Integrate,
+        EngineSet::Events,
+    ];
+
+    pub(crate) fn is_physics(self) -> bool {
+        Self::PHYSICS.contains(&self)
+    }
+
+    /// The set that runs right after this one in the same schedule.
+    pub(crate) fn next(self) -> Option<EngineSet> {
+        let phases: &[EngineSet] = if self.is_physics() {
+            &Self::PHYSICS
+        } else {
+            &Self::FRAME
+        };
+        let index = phases.iter().position(|set| *set == self)?;
+        phases.get(index + 1).copied()
+    }
+}
@@ -1,6 +1,126 @@
-use std::time::{Duration, Instant};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
 
 use bevy_ecs::prelude::*;
+
+/// How `Engine::run` paces presented frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FramePacing {
+    /// Let the driver block on buffer swaps at the display refresh rate.
+    Vsync,
+    /// Sleep at the end of each frame to hold the given frame rate.
+    SleepTo(u32),
+    /// Present frames as fast as they are produced.
+    Uncapped,
+}
+
+impl Default for FramePacing {
+    fn default() -> Self {
+        FramePacing::SleepTo(60)
+    }
+}
+
+impl FramePacing {
+    /// Sleep pacing that holds frames to `frame_duration`, rounded to the nearest whole rate.
+    pub fn sleep_to_frame_duration(frame_duration: Duration) -> Self {
+        let secs = frame_duration.as_secs_f64();
+        if secs > 0.0 {
+            FramePacing::SleepTo((1.0 / secs).round() as u32)
+        } else {
+            FramePacing::Uncapped
+        }
+    }
+
+    /// How long to sleep after a frame that took `frame_time`, if at all.
+    pub fn sleep_duration(&self, frame_time: Duration) -> Option<Duration> {
+        match *self {
+            FramePacing::SleepTo(fps) if fps > 0 => {
+                let frame_target = Duration::from_secs_f64(1.0 / fps as f64);
+                frame_target
+                    .checked_sub(frame_time)
+                    .filter(|d| !d.is_zero())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Rolling window of the most recent frame times, recorded by `Engine::run` before they are
+/// clamped for physics catch-up. Game code can read it for an FPS counter or a frame-time graph.
+#[derive(Resource, Debug, Clone)]
+pub struct FrameStats {
+    frame_times: VecDeque<Duration>,
+    capacity: usize,
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self::with_capacity(120)
+    }
+}
+
+impl FrameStats {
+    /// Keeps the last `capacity` frames, at least one.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            frame_times: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn record(&mut self, frame_time: Duration) {
+        if self.frame_times.len() == self.capacity {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
+
+    /// Recorded frame times, oldest first.
+    pub fn frame_times(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.frame_times.iter().copied()
+    }
+
+    pub fn min(&self) -> Option<Duration> {
+        self.frame_times.iter().min().copied()
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.frame_times.iter().max().copied()
+    }
+
+    pub fn average(&self) -> Option<Duration> {
+        if self.frame_times.is_empty() {
+            return None;
+        }
+        let total: Duration = self.frame_times.iter().sum();
+        Some(total / self.frame_times.len() as u32)
+    }
+
+    /// Frame time that `percent` percent of the window is at or below (nearest rank), e.g.
+    /// `percentile(99.0)` for the 1% worst frames.
+    pub fn percentile(&self, percent: f32) -> Option<Duration> {
+        if self.frame_times.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<Duration> = self.frame_times.iter().copied().collect();
+        sorted.sort();
+        let rank = (percent.clamp(0.0, 100.0) / 100.0 * sorted.len() as f32).ceil() as usize;
+        Some(sorted[rank.saturating_sub(1)])
+    }
+
+    /// Frames per second over the window, from the average frame time.
+    pub fn fps(&self) -> Option<f32> {
+        self.average()
+            .filter(|average| !average.is_zero())
+            .map(|average| 1.0 / average.as_secs_f32())
+    }
+}
+
+const DEFAULT_MAX_FRAME_TIME: Duration = Duration::from_millis(250);
+
 #[derive(Resource)]
 pub struct TimeResource {
     dt: f32,
@@ -9,6 +129,7 @@ pub struct TimeResource {
     frame_count: u64,
     target_frame_duration: Duration,
     last_frame_time: Instant,
+    max_frame_time: Duration,
 }
 
 impl Default for TimeResource {
@@ -20,6 +141,7 @@ impl Default for TimeResource {
             frame_count: 0,
             target_frame_duration: Duration::from_secs_f32(1.0 / 60.0), // Default to 60 FPS max
             last_frame_time: Instant::now(),
+            max_frame_time: DEFAULT_MAX_FRAME_TIME,
         }
     }
 }
@@ -36,6 +158,7 @@ impl TimeResource {
             target_frame_duration: target_frame_time,
             simulation_fixed_dt,
             last_frame_time: Instant::now(),
+            max_frame_time: DEFAULT_MAX_FRAME_TIME,
         }
     }
 
@@ -58,6 +181,22 @@ impl TimeResource {
         self.frame_count += 1;
     }
 
+    /// Most frame time the physics accumulator takes in per frame, guarding against a spiral of
+    /// death after debugger pauses, window drags or long level loads. Larger values let the
+    /// simulation catch up further, at the cost of longer bursts of physics steps.
+    pub fn max_frame_time(&self) -> Duration {
+        self.max_frame_time
+    }
+
+    pub fn set_max_frame_time(&mut self, max_frame_time: Duration) {
+        self.max_frame_time = max_frame_time;
+    }
+
+    /// The part of `frame_time` that the physics accumulator should take in.
+    pub fn catch_up_time(&self, frame_time: Duration) -> Duration {
+        frame_time.min(self.max_frame_time)
+    }
+
     pub fn frame_delta_time(&self) -> f32 {
         self.dt
     }
@@ -102,6 +241,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sleep_to_sleeps_for_the_rest_of_the_frame_budget() {
+        let pacing = FramePacing::SleepTo(50);
+
+        assert_eq!(
+            pacing.sleep_duration(Duration::from_millis(5)),
+            Some(Duration::from_millis(15))
+        );
+        assert_eq!(pacing.sleep_duration(Duration::from_millis(20)), None);
+        assert_eq!(pacing.sleep_duration(Duration::from_millis(30)), None);
+    }
+
+    #[test]
+    fn vsync_and_uncapped_never_sleep() {
+        let frame_time = Duration::from_millis(1);
+
+        assert_eq!(FramePacing::Vsync.sleep_duration(frame_time), None);
+        assert_eq!(FramePacing::Uncapped.sleep_duration(frame_time), None);
+        assert_eq!(FramePacing::SleepTo(0).sleep_duration(frame_time), None);
+    }
+
+    #[test]
+    fn sleep_to_frame_duration_rounds_to_the_nearest_rate() {
+        let time = TimeResource::new(144, 120);
+
+        assert_eq!(
+            FramePacing::sleep_to_frame_duration(time.target_frame_duration()),
+            FramePacing::SleepTo(144)
+        );
+        assert_eq!(
+            FramePacing::sleep_to_frame_duration(Duration::ZERO),
+            FramePacing::Uncapped
+        );
+    }
+
+    #[test]
+    fn frame_stats_report_average_and_99th_percentile() {
+        let mut stats = FrameStats::with_capacity(100);
+        // Twenty frames that fall out of the window first
+        for _ in 0..20 {
+            stats.record(Duration::from_secs(1));
+        }
+        for ms in 1..=100 {
+            stats.record(Duration::from_millis(ms));
+        }
+
+        assert_eq!(stats.frame_times().count(), 100);
+        assert_eq!(stats.min(), Some(Duration::from_millis(1)));
+        assert_eq!(stats.max(), Some(Duration::from_millis(100)));
+        assert_eq!(stats.average(), Some(Duration::from_micros(50_500)));
+        assert_eq!(stats.percentile(99.0), Some(Duration::from_millis(99)));
+        assert_eq!(stats.percentile(100.0), Some(Duration::from_millis(100)));
+        assert_eq!(FrameStats::default().percentile(99.0), None);
+    }
+
     #[test]
     fn default_values_match_expected_60hz_defaults() {
         let time = TimeResource::default();
@@ -149,6 +343,28 @@ mod tests {
         assert_eq!(time.fixed_dt(), updated);
     }
 
+    #[test]
+    fn accumulator_grows_by_at_most_the_max_frame_time_per_frame() {
+        let mut time = TimeResource::default();
+        time.set_max_frame_time(Duration::from_millis(100));
+
+        let mut accumulator = Duration::ZERO;
+        for frame_time in [
+            Duration::from_millis(16),
+            Duration::from_secs(3),
+            Duration::from_millis(100),
+        ] {
+            let before = accumulator;
+            accumulator += time.catch_up_time(frame_time);
+            assert!(accumulator - before <= time.max_frame_time());
+        }
+        assert_eq!(accumulator, Duration::from_millis(216));
+        assert_eq!(
+            TimeResource::default().max_frame_time(),
+            Duration::from_millis(250)
+        );
+    }
+
     #[test]
     fn update_frame_dt_tracks_ladt_total_time_and_frame_count() {
         let mut time = TimeResource::default();
@@ -3,6 +3,7 @@ use engine::assets::sound_resource::SoundResource;
 use engine::audio::audio_control::AudioControl;
 use engine::components::single_audio_listener_component::SingleAudioListenerComponent;
 use engine::input::InputStateResource;
+use engine::render::screenshot::ScreenshotRequestResource;
 use engine::scene::scene::Scene;
 use engine::scene::scene_changer_resource::SceneChangerResource;
 use engine::scene::scene_services::SceneServices;
@@ -26,6 +27,19 @@ pub fn scene_switcher(
     }
 }
 
+pub fn screenshot_on_key(
+    mut screenshots: ResMut<ScreenshotRequestResource>,
+    input_state: Res<InputStateResource>,
+) {
+    if input_state.key_pressed(Keycode::F12) {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        screenshots.request(format!("screenshot_{timestamp}.png"));
+    }
+}
+
 fn make_test_scene(scene: &mut Scene) {
     let aspect_ratio = 1024.0 / 769.0;
     let _flying_camera = scene
@@ -106,6 +120,7 @@ fn make_test_scene(scene: &mut Scene) {
         apply_switch_camera_input,
         sound_control,
         scene_switcher,
+        screenshot_on_key,
         // spatial_audio_popping_demo,
     ));
 }
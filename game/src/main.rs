@@ -20,17 +20,19 @@ use crate::game_controller::{
     // SpatialAudioDemoComponent,
     do_gameplay,
     scene_switcher,
+    screenshot_on_key,
     sound_control,
     spatial_audio_orbit_demo,
     // spatial_audio_popping_demo,
 };
 use bevy_ecs::schedule::IntoScheduleConfigs;
 use engine::{
-    ActiveCamera, CameraComponent, CollisionLayer, ConvexCollider, Engine, RenderBodyComponent,
-    SleepComponent, TransformComponent, VelocityComponent,
+    ActiveCamera, CameraComponent, CollisionLayer, ConvexCollider, Engine, LockedAxes,
+    RenderBodyComponent, SleepComponent, TransformComponent, VelocityComponent,
 };
 
 use engine::components::physics_component::{PhysicsComponent, PhysicsType};
+use engine::input::{InputStateResource, MouseCaptureMode};
 
 use glam::{Quat, Vec3};
 
@@ -42,8 +44,16 @@ fn main() {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
 
-    println!("Welcome to the Game!");
     let mut engine = Engine::new();
+    log::info!("Welcome to the Game!");
+
+    let settings = settings::Settings::load_user_settings();
+    engine.set_frame_pacing(settings.renderer.frame_pacing());
+    engine
+        .scene
+        .world
+        .resource_mut::<InputStateResource>()
+        .stick_dead_zone = settings.input.stick_dead_zone;
 
     // Create an ECS-driven camera entity and mark it active.
     let aspect_ratio = 1024.0 / 769.0;
@@ -109,6 +119,14 @@ fn main() {
         .unwrap()
         .set(_flying_camera);
 
+    // Lock the cursor while dragging to look around so it can't leave the window.
+    engine
+        .scene
+        .world
+        .get_resource_mut::<InputStateResource>()
+        .unwrap()
+        .mouse_capture_mode = MouseCaptureMode::HoldButton(engine::MouseButton::Left);
+
     engine.scene.game_simulation_schedule.add_systems(
         (
             initialize_flying_camera_rotation,
@@ -129,6 +147,7 @@ fn main() {
         apply_switch_camera_input,
         sound_control,
         scene_switcher,
+        screenshot_on_key,
         // spatial_audio_popping_demo,
     ));
 
@@ -188,14 +207,16 @@ fn main() {
             mass: 5.0,
             physics_type: PhysicsType::Dynamic,
             friction: 0.9,
+            rolling_friction: 0.0,
             drag_coefficient: 0.8,
             angular_drag_coefficient: 0.1,
             restitution: 0.5,
             local_inertia: glam::Mat3::IDENTITY,
         },
         // SleepComponent::default(),
+        LockedAxes::ROTATION_LOCKED,
         PlayerComponent { speed: 1.0 },
-        PhysicsEventListenerComponent {},
+        PhysicsEventListenerComponent::default(),
     ));
 
     // Spatial audio testing
@@ -259,6 +280,7 @@ fn main() {
     //                 PhysicsType::Dynamic
     //             },
     //             friction: 0.9,
+    //             rolling_friction: 0.0,
     //             drag_coefficient: 0.1,
     //             angular_drag_coefficient: 0.1,
     //             restitution: 0.5,
@@ -314,6 +336,7 @@ fn main() {
                 mass: 30.0,
                 physics_type: PhysicsType::Dynamic,
                 friction: 0.2,
+                rolling_friction: 0.0,
                 drag_coefficient: 0.1,
                 angular_drag_coefficient: 0.1,
                 restitution: 0.5,
@@ -350,6 +373,7 @@ fn main() {
             mass: f32::INFINITY,
             physics_type: PhysicsType::Static,
             friction: 0.5,
+            rolling_friction: 0.0,
             drag_coefficient: 0.1,
             angular_drag_coefficient: 0.1,
             restitution: 0.3,
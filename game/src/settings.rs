@@ -1,7 +1,10 @@
 use dirs_next::config_dir; // Use dirs-next for better maintenance
+use engine::FramePacing;
+use engine::input::DEFAULT_STICK_DEAD_ZONE;
 use serde::{Deserialize, Serialize};
 use std::{
     fs,
+    io::Write,
     path::{Path, PathBuf},
 };
 use thiserror::Error; // For better error handling
@@ -18,6 +21,28 @@ pub struct RendererSettings {
     pub render_scale: f32,
     pub visualize_edges: bool,
     pub visualize_normals: bool,
+    #[serde(default)]
+    pub vsync: bool,
+    /// Frame rate cap when vsync is off. Zero leaves frames uncapped.
+    #[serde(default = "RendererSettings::default_max_fps")]
+    pub max_fps: u32,
+}
+
+impl RendererSettings {
+    fn default_max_fps() -> u32 {
+        60
+    }
+
+    /// The engine frame pacing mode these settings describe.
+    pub fn frame_pacing(&self) -> FramePacing {
+        if self.vsync {
+            FramePacing::Vsync
+        } else if self.max_fps == 0 {
+            FramePacing::Uncapped
+        } else {
+            FramePacing::SleepTo(self.max_fps)
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -26,16 +51,41 @@ pub struct NetworkSettings {
     pub use_https: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct InputSettings {
+    /// Radial analog stick dead zone, as a fraction of full deflection.
+    pub stick_dead_zone: f32,
+}
+
+impl Default for InputSettings {
+    fn default() -> Self {
+        Self {
+            stick_dead_zone: DEFAULT_STICK_DEAD_ZONE,
+        }
+    }
+}
+
+/// Version written by this build. Bump it whenever a change to `Settings` needs `migrate` to
+/// upgrade older files.
+pub const CURRENT_SETTINGS_VERSION: u32 = 2;
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct Settings {
+    /// Layout version of the file these settings were read from. Files written before versioning
+    /// have none and count as version 1.
+    #[serde(default = "Settings::unversioned")]
+    pub version: u32,
     pub general: GeneralSettings,
     pub renderer: RendererSettings,
     pub network: NetworkSettings,
+    #[serde(default)]
+    pub input: InputSettings,
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
+            version: CURRENT_SETTINGS_VERSION,
             general: GeneralSettings {
                 username: String::from("Egg"),
                 theme: String::from("system"),
@@ -45,11 +95,14 @@ impl Default for Settings {
                 render_scale: 1.0,
                 visualize_edges: true,
                 visualize_normals: false,
+                vsync: false,
+                max_fps: RendererSettings::default_max_fps(),
             },
             network: NetworkSettings {
                 timeout: 30,
                 use_https: true,
             },
+            input: InputSettings::default(),
         }
     }
 }
@@ -65,60 +118,202 @@ pub enum SettingsError {
     #[error("Serialization Error: {0}")]
     SerdeSer(#[from] toml::ser::Error),
 
+    #[error("JSON Error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Unknown Settings Format: {0}")]
+    UnknownFormat(PathBuf),
+
     #[error("Configuration Directory Not Found")]
     ConfigDirNotFound,
 }
 
+/// File formats settings can be stored in, chosen by file extension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SettingsFormat {
+    Toml,
+    Json,
+}
+
+impl SettingsFormat {
+    /// `.toml` and `.json` select their format; paths without an extension use TOML.
+    fn from_path(path: &Path) -> Result<Self, SettingsError> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            None => Ok(SettingsFormat::Toml),
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => Ok(SettingsFormat::Toml),
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Ok(SettingsFormat::Json),
+            Some(_) => Err(SettingsError::UnknownFormat(path.to_path_buf())),
+        }
+    }
+}
+
 impl Settings {
-    /// Retrieves the path to the user settings file.
-    fn user_settings_path() -> Result<PathBuf, SettingsError> {
-        let config_dir = config_dir().ok_or(SettingsError::ConfigDirNotFound)?;
-        Ok(config_dir
-            .join("Ultra")
-            .join("settings")
-            .join("user_settings.toml"))
+    fn unversioned() -> u32 {
+        1
     }
 
-    /// Retrieves the path to the default settings file.
-    #[allow(dead_code)]
-    fn default_settings_path() -> Result<PathBuf, SettingsError> {
-        let config_dir = config_dir().ok_or(SettingsError::ConfigDirNotFound)?;
-        Ok(config_dir
-            .join("Ultra")
-            .join("settings")
-            .join("default_settings.toml"))
+    /// Upgrades settings read from a file of an older layout to the current layout.
+    ///
+    /// Version 2 added frame pacing and the input section. Fields a version 1 file
+    /// lacks already got their serde defaults, and any it has were set by the user, so only the
+    /// version changes.
+    pub fn migrate(&mut self, _from_version: u32) {
+        self.version = CURRENT_SETTINGS_VERSION;
     }
 
-    /// Loads settings from a specified file path.
-    #[allow(dead_code)]
+    /// Loads settings from a specified file path, as TOML or JSON depending on its extension.
     pub fn load_from_file(path: &Path) -> Result<Self, SettingsError> {
+        let format = SettingsFormat::from_path(path)?;
         let content = fs::read_to_string(path)?;
-        let settings: Settings = toml::from_str(&content)?;
+        let settings: Settings = match format {
+            SettingsFormat::Toml => toml::from_str(&content)?,
+            SettingsFormat::Json => serde_json::from_str(&content)?,
+        };
         Ok(settings)
     }
 
-    /// Saves settings to a specified file path, ensuring the directory exists.
+    /// Saves settings to a specified file path, ensuring the directory exists. The format
+    /// follows the extension, as in `load_from_file`.
+    ///
+    /// The file is written next to `path` first and then renamed over it, so a crash mid-write
+    /// leaves the previous file intact instead of a truncated one.
     pub fn save_to_file(&self, path: &Path) -> Result<(), SettingsError> {
+        let format = SettingsFormat::from_path(path)?;
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        let content = toml::to_string_pretty(self)?;
-        fs::write(path, content)?;
+        let content = match format {
+            SettingsFormat::Toml => toml::to_string_pretty(self)?,
+            SettingsFormat::Json => serde_json::to_string_pretty(self)?,
+        };
+        let temp_path = Settings::write_temp_file(path, &content)?;
+        if let Err(e) = fs::rename(&temp_path, path) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e.into());
+        }
         Ok(())
     }
 
+    /// Writes `content` to a temporary file in the same directory as `path`, so the following
+    /// rename stays on one filesystem.
+    fn write_temp_file(path: &Path, content: &str) -> Result<PathBuf, SettingsError> {
+        let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(".tmp");
+        let temp_path = path.with_file_name(file_name);
+
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        Ok(temp_path)
+    }
+
     #[allow(dead_code)]
-    /// Saves settings to the user settings file.
+    /// Saves settings to the user settings file in the platform config directory.
     pub fn save_user_settings(&self) -> Result<(), SettingsError> {
-        let user_path = Settings::user_settings_path()?;
-        self.save_to_file(&user_path)
+        SettingsDir::system().save_user_settings(self)
     }
 
-    /// Loads user settings, handling defaults and creating necessary files.
-    #[allow(dead_code)]
+    /// Loads user settings from the platform config directory, handling defaults and creating
+    /// necessary files.
     pub fn load_user_settings() -> Settings {
-        match Settings::initialize_settings() {
+        SettingsDir::system().load_user_settings()
+    }
+
+    /// Replaces every section with the defaults and saves them as the user settings, after
+    /// backing up the previous user settings file next to it with a `.bak` suffix.
+    #[allow(dead_code)]
+    pub fn reset_to_defaults(&mut self) -> Result<(), SettingsError> {
+        SettingsDir::system().reset_user_settings(self)
+    }
+
+    #[allow(dead_code)]
+    pub fn on_reset_settings(&mut self) {
+        if let Err(e) = self.reset_to_defaults() {
+            eprintln!("Failed to reset settings: {}", e);
+        }
+    }
+
+    /// Loads default settings and saves them as user settings.
+    fn load_defaults(user_path: PathBuf, default_path: PathBuf) -> Result<Self, SettingsError> {
+        if default_path.exists() {
+            let settings = Settings::load_from_file(&default_path)?;
+            // Save default settings as user settings
+            settings.save_to_file(&user_path)?;
+            println!("Default settings loaded and saved as user settings.");
+            Ok(settings)
+        } else {
+            eprintln!("Default settings file not found. Using hardcoded defaults.");
+            let settings = Settings::default();
+            // Save hardcoded defaults as default and user settings
+            settings.save_to_file(&default_path)?;
+            settings.save_to_file(&user_path)?;
+            println!("Hardcoded defaults saved as default and user settings.");
+            Ok(settings)
+        }
+    }
+}
+
+/// The base directory settings files live under, normally the platform config directory.
+/// Pointing it elsewhere keeps tests and tools away from the user's real settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingsDir {
+    base_dir: Option<PathBuf>,
+}
+
+impl SettingsDir {
+    /// The platform config directory, or none if the platform has no such directory.
+    pub fn system() -> Self {
+        Self::new(config_dir())
+    }
+
+    /// Settings under `base_dir`. `None` behaves like a platform without a config directory.
+    pub fn new(base_dir: Option<PathBuf>) -> Self {
+        Self { base_dir }
+    }
+
+    fn settings_dir(&self) -> Result<PathBuf, SettingsError> {
+        let base_dir = self
+            .base_dir
+            .as_ref()
+            .ok_or(SettingsError::ConfigDirNotFound)?;
+        Ok(base_dir.join("Ultra").join("settings"))
+    }
+
+    /// Retrieves the path to the user settings file.
+    pub fn user_settings_path(&self) -> Result<PathBuf, SettingsError> {
+        Ok(self.settings_dir()?.join("user_settings.toml"))
+    }
+
+    /// Retrieves the path to the default settings file.
+    pub fn default_settings_path(&self) -> Result<PathBuf, SettingsError> {
+        Ok(self.settings_dir()?.join("default_settings.toml"))
+    }
+
+    /// Saves settings to the user settings file.
+    pub fn save_user_settings(&self, settings: &Settings) -> Result<(), SettingsError> {
+        settings.save_to_file(&self.user_settings_path()?)
+    }
+
+    /// Path the user settings file is backed up to before a reset.
+    pub fn user_settings_backup_path(&self) -> Result<PathBuf, SettingsError> {
+        Ok(self.settings_dir()?.join("user_settings.toml.bak"))
+    }
+
+    /// Backs up the user settings file, if any, then resets `settings` to the defaults and
+    /// saves them as the user settings.
+    pub fn reset_user_settings(&self, settings: &mut Settings) -> Result<(), SettingsError> {
+        let user_path = self.user_settings_path()?;
+        if user_path.exists() {
+            fs::copy(&user_path, self.user_settings_backup_path()?)?;
+        }
+        *settings = Settings::default();
+        self.save_user_settings(settings)
+    }
+
+    /// Loads user settings, handling defaults and creating necessary files.
+    pub fn load_user_settings(&self) -> Settings {
+        match self.initialize_settings() {
             Ok(settings) => settings,
             Err(e) => {
                 eprintln!("Error initializing settings: {}", e);
@@ -128,15 +323,30 @@ impl Settings {
     }
 
     /// Initializes settings by loading user settings or falling back to defaults.
-    #[allow(dead_code)]
-    fn initialize_settings() -> Result<Self, SettingsError> {
-        let user_settings_path = Settings::user_settings_path()?;
-        let default_settings_path = Settings::default_settings_path()?;
+    fn initialize_settings(&self) -> Result<Settings, SettingsError> {
+        let user_settings_path = self.user_settings_path()?;
+        let default_settings_path = self.default_settings_path()?;
 
         if user_settings_path.exists() {
             // Attempt to load user settings
             match Settings::load_from_file(&user_settings_path) {
-                Ok(settings) => Ok(settings),
+                Ok(mut settings) => {
+                    if settings.version < CURRENT_SETTINGS_VERSION {
+                        let from_version = settings.version;
+                        settings.migrate(from_version);
+                        match settings.save_to_file(&user_settings_path) {
+                            Ok(()) => println!(
+                                "User settings migrated from version {} to {}.",
+                                from_version, CURRENT_SETTINGS_VERSION
+                            ),
+                            Err(e) => eprintln!(
+                                "Failed to save migrated user settings: {}. Using them unsaved.",
+                                e
+                            ),
+                        }
+                    }
+                    Ok(settings)
+                }
                 Err(e) => {
                     eprintln!(
                         "Failed to load user settings: {}. Attempting to load defaults.",
@@ -151,32 +361,11 @@ impl Settings {
             Settings::load_defaults(user_settings_path, default_settings_path)
         }
     }
-
-    /// Loads default settings and saves them as user settings.
-    #[allow(dead_code)]
-    fn load_defaults(user_path: PathBuf, default_path: PathBuf) -> Result<Self, SettingsError> {
-        if default_path.exists() {
-            let settings = Settings::load_from_file(&default_path)?;
-            // Save default settings as user settings
-            settings.save_to_file(&user_path)?;
-            println!("Default settings loaded and saved as user settings.");
-            Ok(settings)
-        } else {
-            eprintln!("Default settings file not found. Using hardcoded defaults.");
-            let settings = Settings::default();
-            // Save hardcoded defaults as default and user settings
-            settings.save_to_file(&default_path)?;
-            settings.save_to_file(&user_path)?;
-            println!("Hardcoded defaults saved as default and user settings.");
-            Ok(settings)
-        }
-    }
 }
 #[cfg(test)]
 mod tests {
-    use std::{env, fs, path::PathBuf};
+    use std::{fs, path::PathBuf};
 
-    use serial_test::serial;
     use tempfile::tempdir;
 
     use super::*;
@@ -188,107 +377,65 @@ mod tests {
         (temp_dir, config_dir)
     }
 
-    /// Helper function to override the config_dir() function from dirs-next
-    /// by temporarily setting the HOME and XDG_CONFIG_HOME environment variables to the temp config directory
-    fn override_config_dir(temp_config_dir: &PathBuf) {
-        // TODO: Audit that the environment access only happens in single-threaded code.
-        unsafe { env::set_var("HOME", temp_config_dir) };
-        // TODO: Audit that the environment access only happens in single-threaded code.
-        unsafe { env::set_var("XDG_CONFIG_HOME", temp_config_dir) };
-    }
-
-    /// Helper function to reset the HOME and XDG_CONFIG_HOME environment variables after tests
-    fn reset_config_dir(original_home: &str, original_xdg_config_home: Option<&str>) {
-        // TODO: Audit that the environment access only happens in single-threaded code.
-        unsafe { env::set_var("HOME", original_home) };
-        match original_xdg_config_home {
-            // TODO: Audit that the environment access only happens in single-threaded code.
-            Some(val) => unsafe { env::set_var("XDG_CONFIG_HOME", val) },
-            // TODO: Audit that the environment access only happens in single-threaded code.
-            None => unsafe { env::remove_var("XDG_CONFIG_HOME") },
-        }
-    }
-
     /// Test Case 1a: Verify that `user_settings_path()` correctly constructs the path
     #[test]
-    #[serial]
     fn user_settings_path_retrieval() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir.clone()));
 
         let expected_path = config_dir
             .join("Ultra")
             .join("settings")
             .join("user_settings.toml");
-        let actual_path = Settings::user_settings_path().expect("Failed to get user settings path");
+        let actual_path = dir
+            .user_settings_path()
+            .expect("Failed to get user settings path");
 
         assert_eq!(actual_path, expected_path);
 
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
         // TempDir is automatically deleted when it goes out of scope
     }
 
     /// Test Case 1b: Verify that `default_settings_path()` correctly constructs the path
     #[test]
-    #[serial]
     fn default_settings_path_retrieval() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir.clone()));
 
         let expected_path = config_dir
             .join("Ultra")
             .join("settings")
             .join("default_settings.toml");
-        let actual_path =
-            Settings::default_settings_path().expect("Failed to get default settings path");
+        let actual_path = dir
+            .default_settings_path()
+            .expect("Failed to get default settings path");
 
         assert_eq!(actual_path, expected_path);
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 1c: Handling Non-Existent Configuration Directory
-    ///
-    /// NOTE: This test is marked as ignored because fully simulating the absence of a configuration directory
-    /// is complex due to dirs-next's fallback mechanisms. Consider refactoring your code to support dependency
-    /// injection for more robust testing.
     #[test]
-    #[serial]
-    #[ignore]
     fn config_dir_not_found() {
-        // Temporarily unset the HOME and XDG_CONFIG_HOME environment variables
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        // TODO: Audit that the environment access only happens in single-threaded code.
-        unsafe { env::remove_var("HOME") };
-        // TODO: Audit that the environment access only happens in single-threaded code.
-        unsafe { env::remove_var("XDG_CONFIG_HOME") };
-
-        let result = Settings::user_settings_path();
+        let dir = SettingsDir::new(None);
+
+        let result = dir.user_settings_path();
         assert!(matches!(result, Err(SettingsError::ConfigDirNotFound)));
 
-        let result = Settings::default_settings_path();
+        let result = dir.default_settings_path();
         assert!(matches!(result, Err(SettingsError::ConfigDirNotFound)));
 
-        // Restore the HOME and XDG_CONFIG_HOME environment variables
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
+        // Loading falls back to the hardcoded defaults without touching the disk
+        assert_eq!(dir.load_user_settings(), Settings::default());
     }
 
     /// Test Case 2a: Loading from a Valid User Settings File
     #[test]
-    #[serial]
     fn load_from_valid_user_settings_file() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir));
 
         // Create user_settings.toml with specific content
-        let user_settings_path = Settings::user_settings_path().unwrap();
+        let user_settings_path = dir.user_settings_path().unwrap();
         fs::create_dir_all(user_settings_path.parent().unwrap()).unwrap();
         let user_settings_content = r#"
             [general]
@@ -321,21 +468,16 @@ mod tests {
 
         assert_eq!(settings.network.timeout, 60);
         assert_eq!(settings.network.use_https, false);
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 2b: Loading from a Valid Default Settings File
     #[test]
-    #[serial]
     fn load_from_valid_default_settings_file() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir));
 
         // Create default_settings.toml with specific content
-        let default_settings_path = Settings::default_settings_path().unwrap();
+        let default_settings_path = dir.default_settings_path().unwrap();
         fs::create_dir_all(default_settings_path.parent().unwrap()).unwrap();
         let default_settings_content = r#"
             [general]
@@ -368,21 +510,16 @@ mod tests {
 
         assert_eq!(settings.network.timeout, 45);
         assert_eq!(settings.network.use_https, true);
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 2c: Loading When User Settings File is Missing but Default Exists
     #[test]
-    #[serial]
     fn load_user_settings_missing_user_but_default_exists() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir));
 
         // Create default_settings.toml
-        let default_settings_path = Settings::default_settings_path().unwrap();
+        let default_settings_path = dir.default_settings_path().unwrap();
         fs::create_dir_all(default_settings_path.parent().unwrap()).unwrap();
         let default_settings_content = r#"
             [general]
@@ -402,11 +539,11 @@ mod tests {
         fs::write(&default_settings_path, default_settings_content).unwrap();
 
         // Ensure user_settings.toml does not exist
-        let user_settings_path = Settings::user_settings_path().unwrap();
+        let user_settings_path = dir.user_settings_path().unwrap();
         assert!(!user_settings_path.exists());
 
         // Initialize settings
-        let settings = Settings::initialize_settings().unwrap();
+        let settings = dir.initialize_settings().unwrap();
 
         // Assert loaded settings match default
         assert_eq!(settings.general.username, "DefaultUser");
@@ -422,27 +559,22 @@ mod tests {
 
         // Assert that user_settings.toml is created with default settings
         assert!(user_settings_path.exists());
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 2d: Loading When Both User and Default Settings Files are Missing
     #[test]
-    #[serial]
     fn load_user_settings_missing_both_files() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir));
 
         // Ensure both user_settings.toml and default_settings.toml do not exist
-        let user_settings_path = Settings::user_settings_path().unwrap();
-        let default_settings_path = Settings::default_settings_path().unwrap();
+        let user_settings_path = dir.user_settings_path().unwrap();
+        let default_settings_path = dir.default_settings_path().unwrap();
         assert!(!user_settings_path.exists());
         assert!(!default_settings_path.exists());
 
         // Initialize settings
-        let settings = Settings::initialize_settings().unwrap();
+        let settings = dir.initialize_settings().unwrap();
 
         // Assert loaded settings match hardcoded defaults
         assert_eq!(settings.general.username, "Egg");
@@ -459,27 +591,22 @@ mod tests {
         // Assert that both default_settings.toml and user_settings.toml are created
         assert!(default_settings_path.exists());
         assert!(user_settings_path.exists());
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 2e: Handling Corrupted Settings Files
     #[test]
-    #[serial]
     fn handling_corrupted_settings_files() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir));
 
         // Create a corrupted user_settings.toml
-        let user_settings_path = Settings::user_settings_path().unwrap();
+        let user_settings_path = dir.user_settings_path().unwrap();
         fs::create_dir_all(user_settings_path.parent().unwrap()).unwrap();
         let corrupted_content = "invalid toml content ::::";
         fs::write(&user_settings_path, corrupted_content).unwrap();
 
         // Create a valid default_settings.toml
-        let default_settings_path = Settings::default_settings_path().unwrap();
+        let default_settings_path = dir.default_settings_path().unwrap();
         fs::create_dir_all(default_settings_path.parent().unwrap()).unwrap();
         let default_settings_content = r#"
             [general]
@@ -499,7 +626,7 @@ mod tests {
         fs::write(&default_settings_path, default_settings_content).unwrap();
 
         // Initialize settings, which should fallback to defaults
-        let settings = Settings::initialize_settings().unwrap();
+        let settings = dir.initialize_settings().unwrap();
 
         // Assert loaded settings match default
         assert_eq!(settings.general.username, "DefaultUser");
@@ -516,21 +643,17 @@ mod tests {
         // Assert that user_settings.toml is overwritten with default settings
         let loaded_user_settings = Settings::load_from_file(&user_settings_path).unwrap();
         assert_eq!(loaded_user_settings.general.username, "DefaultUser");
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 3a: Successfully Saving User Settings
     #[test]
-    #[serial]
     fn save_user_settings_success() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir));
 
         // Create a Settings instance with custom values
         let settings = Settings {
+            version: CURRENT_SETTINGS_VERSION,
             general: GeneralSettings {
                 username: "CustomUser".to_string(),
                 theme: "dark".to_string(),
@@ -540,18 +663,21 @@ mod tests {
                 render_scale: 2.0,
                 visualize_edges: false,
                 visualize_normals: true,
+                vsync: true,
+                max_fps: 60,
             },
             network: NetworkSettings {
                 timeout: 50,
                 use_https: false,
             },
+            input: InputSettings::default(),
         };
 
         // Save user settings
-        settings.save_user_settings().unwrap();
+        dir.save_user_settings(&settings).unwrap();
 
         // Read the saved file and verify contents
-        let user_settings_path = Settings::user_settings_path().unwrap();
+        let user_settings_path = dir.user_settings_path().unwrap();
         let saved_content = fs::read_to_string(&user_settings_path).unwrap();
         let loaded_settings: Settings = toml::from_str(&saved_content).unwrap();
 
@@ -562,24 +688,21 @@ mod tests {
         assert_eq!(loaded_settings.renderer.render_scale, 2.0);
         assert_eq!(loaded_settings.renderer.visualize_edges, false);
         assert_eq!(loaded_settings.renderer.visualize_normals, true);
+        assert_eq!(loaded_settings.renderer.vsync, true);
 
         assert_eq!(loaded_settings.network.timeout, 50);
         assert_eq!(loaded_settings.network.use_https, false);
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 3b: Successfully Saving Default Settings
     #[test]
-    #[serial]
     fn save_default_settings_success() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir));
 
         // Create a Settings instance with custom default values
         let settings = Settings {
+            version: CURRENT_SETTINGS_VERSION,
             general: GeneralSettings {
                 username: "DefaultUser".to_string(),
                 theme: "light".to_string(),
@@ -589,15 +712,18 @@ mod tests {
                 render_scale: 1.2,
                 visualize_edges: true,
                 visualize_normals: false,
+                vsync: false,
+                max_fps: 144,
             },
             network: NetworkSettings {
                 timeout: 40,
                 use_https: true,
             },
+            input: InputSettings::default(),
         };
 
         // Save default settings
-        let default_settings_path = Settings::default_settings_path().unwrap();
+        let default_settings_path = dir.default_settings_path().unwrap();
         settings.save_to_file(&default_settings_path).unwrap();
 
         // Read the saved default file and verify contents
@@ -611,21 +737,16 @@ mod tests {
         assert_eq!(loaded_settings.renderer.render_scale, 1.2);
         assert_eq!(loaded_settings.renderer.visualize_edges, true);
         assert_eq!(loaded_settings.renderer.visualize_normals, false);
+        assert_eq!(loaded_settings.renderer.max_fps, 144);
 
         assert_eq!(loaded_settings.network.timeout, 40);
         assert_eq!(loaded_settings.network.use_https, true);
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 3c: Handling Directory Creation During Save
     #[test]
-    #[serial]
     fn save_creates_directories() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
 
         // Define a non-existent directory path within the config directory
         let custom_settings_path = config_dir
@@ -647,13 +768,10 @@ mod tests {
         let loaded_settings: Settings = toml::from_str(&saved_content).unwrap();
 
         assert_eq!(loaded_settings, Settings::default());
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 3d: Handling File Write Permissions
     #[test]
-    #[serial]
     fn save_file_write_permissions() {
         use std::os::unix::fs::PermissionsExt;
 
@@ -661,9 +779,6 @@ mod tests {
         #[cfg(unix)]
         {
             let (_temp_dir, config_dir) = setup_temp_config_dir();
-            let original_home = env::var("HOME").unwrap_or_default();
-            let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-            override_config_dir(&config_dir);
 
             // Create a directory with no write permissions
             let no_write_dir = config_dir.join("Ultra").join("no_write_dir");
@@ -678,8 +793,6 @@ mod tests {
 
             let result = settings.save_to_file(&read_only_settings_path);
             assert!(matches!(result, Err(SettingsError::Io(_))));
-
-            reset_config_dir(&original_home, original_xdg_config_home.as_deref());
         }
 
         // On non-Unix systems, skip the test
@@ -691,15 +804,12 @@ mod tests {
 
     /// Test Case 4a: Successful Initialization with Existing User Settings
     #[test]
-    #[serial]
     fn initialization_with_existing_user_settings() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir));
 
         // Create user_settings.toml with specific content
-        let user_settings_path = Settings::user_settings_path().unwrap();
+        let user_settings_path = dir.user_settings_path().unwrap();
         fs::create_dir_all(user_settings_path.parent().unwrap()).unwrap();
         let user_settings_content = r#"
             [general]
@@ -719,7 +829,7 @@ mod tests {
         fs::write(&user_settings_path, user_settings_content).unwrap();
 
         // Initialize settings
-        let settings = Settings::initialize_settings().unwrap();
+        let settings = dir.initialize_settings().unwrap();
 
         // Assert loaded settings match user settings
         assert_eq!(settings.general.username, "ExistingUser");
@@ -734,28 +844,23 @@ mod tests {
         assert_eq!(settings.network.use_https, false);
 
         // Ensure default_settings.toml is not modified
-        let default_settings_path = Settings::default_settings_path().unwrap();
+        let default_settings_path = dir.default_settings_path().unwrap();
         assert!(!default_settings_path.exists());
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 4b: Initialization with Corrupted User Settings Falling Back to Defaults
     #[test]
-    #[serial]
     fn initialization_with_corrupted_user_settings_fallback_to_defaults() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir));
 
         // Create corrupted user_settings.toml
-        let user_settings_path = Settings::user_settings_path().unwrap();
+        let user_settings_path = dir.user_settings_path().unwrap();
         fs::create_dir_all(user_settings_path.parent().unwrap()).unwrap();
         fs::write(&user_settings_path, "corrupted content").unwrap();
 
         // Create a valid default_settings.toml
-        let default_settings_path = Settings::default_settings_path().unwrap();
+        let default_settings_path = dir.default_settings_path().unwrap();
         fs::create_dir_all(default_settings_path.parent().unwrap()).unwrap();
         let default_settings_content = r#"
             [general]
@@ -775,7 +880,7 @@ mod tests {
         fs::write(&default_settings_path, default_settings_content).unwrap();
 
         // Initialize settings, which should fallback to defaults
-        let settings = Settings::initialize_settings().unwrap();
+        let settings = dir.initialize_settings().unwrap();
 
         // Assert loaded settings match default
         assert_eq!(settings.general.username, "DefaultUser");
@@ -792,27 +897,22 @@ mod tests {
         // Assert that user_settings.toml is overwritten with default settings
         let loaded_user_settings = Settings::load_from_file(&user_settings_path).unwrap();
         assert_eq!(loaded_user_settings.general.username, "DefaultUser");
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 4c: Initialization with Missing User and Default Settings
     #[test]
-    #[serial]
     fn initialization_with_missing_user_and_default_settings() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir));
 
         // Ensure both user_settings.toml and default_settings.toml do not exist
-        let user_settings_path = Settings::user_settings_path().unwrap();
-        let default_settings_path = Settings::default_settings_path().unwrap();
+        let user_settings_path = dir.user_settings_path().unwrap();
+        let default_settings_path = dir.default_settings_path().unwrap();
         assert!(!user_settings_path.exists());
         assert!(!default_settings_path.exists());
 
         // Initialize settings
-        let settings = Settings::initialize_settings().unwrap();
+        let settings = dir.initialize_settings().unwrap();
 
         // Assert loaded settings match hardcoded defaults
         assert_eq!(settings.general.username, "Egg");
@@ -829,14 +929,13 @@ mod tests {
         // Assert that both default_settings.toml and user_settings.toml are created
         assert!(default_settings_path.exists());
         assert!(user_settings_path.exists());
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 5a: Correct Serialization of Settings
     #[test]
     fn correct_serialization_of_settings() {
         let settings = Settings {
+            version: CURRENT_SETTINGS_VERSION,
             general: GeneralSettings {
                 username: "SerializeUser".to_string(),
                 theme: "blue".to_string(),
@@ -846,15 +945,22 @@ mod tests {
                 render_scale: 3.0,
                 visualize_edges: true,
                 visualize_normals: true,
+                vsync: false,
+                max_fps: 0,
             },
             network: NetworkSettings {
                 timeout: 100,
                 use_https: false,
             },
+            input: InputSettings {
+                stick_dead_zone: 0.25,
+            },
         };
 
         let serialized = toml::to_string_pretty(&settings).unwrap();
         let expected = r#"
+version = 2
+
 [general]
 username = "SerializeUser"
 theme = "blue"
@@ -864,10 +970,15 @@ auto_save = false
 render_scale = 3.0
 visualize_edges = true
 visualize_normals = true
+vsync = false
+max_fps = 0
 
 [network]
 timeout = 100
 use_https = false
+
+[input]
+stick_dead_zone = 0.25
         "#
         .trim();
 
@@ -939,22 +1050,26 @@ use_https = false
         assert_eq!(default_settings.renderer.render_scale, 1.0);
         assert_eq!(default_settings.renderer.visualize_edges, true);
         assert_eq!(default_settings.renderer.visualize_normals, false);
+        assert_eq!(default_settings.renderer.vsync, false);
+        assert_eq!(default_settings.renderer.max_fps, 60);
 
         assert_eq!(default_settings.network.timeout, 30);
         assert_eq!(default_settings.network.use_https, true);
+
+        assert_eq!(
+            default_settings.input.stick_dead_zone,
+            DEFAULT_STICK_DEAD_ZONE
+        );
     }
 
     /// Test Case 6b: Overriding Defaults When Loading from Files
     #[test]
-    #[serial]
     fn overriding_defaults_when_loading_from_files() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir));
 
         // Create default_settings.toml with specific values
-        let default_settings_path = Settings::default_settings_path().unwrap();
+        let default_settings_path = dir.default_settings_path().unwrap();
         fs::create_dir_all(default_settings_path.parent().unwrap()).unwrap();
         let default_settings_content = r#"
             [general]
@@ -974,7 +1089,7 @@ use_https = false
         fs::write(&default_settings_path, default_settings_content).unwrap();
 
         // Create user_settings.toml with different values
-        let user_settings_path = Settings::user_settings_path().unwrap();
+        let user_settings_path = dir.user_settings_path().unwrap();
         let user_settings_content = r#"
             [general]
             username = "UserOverride"
@@ -993,7 +1108,7 @@ use_https = false
         fs::write(&user_settings_path, user_settings_content).unwrap();
 
         // Initialize settings
-        let settings = Settings::initialize_settings().unwrap();
+        let settings = dir.initialize_settings().unwrap();
 
         // Assert that settings match user_settings.toml, not default_settings.toml
         assert_eq!(settings.general.username, "UserOverride");
@@ -1006,18 +1121,12 @@ use_https = false
 
         assert_eq!(settings.network.timeout, 75);
         assert_eq!(settings.network.use_https, true);
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 7a: Proper Error Propagation
     #[test]
-    #[serial]
     fn proper_error_propagation() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
 
         // Attempt to load settings from a non-existent path
         let non_existent_path = config_dir.join("Ultra").join("non_existent.toml");
@@ -1038,13 +1147,10 @@ use_https = false
         let settings = Settings::default();
         let save_result = settings.save_to_file(&invalid_save_path);
         assert!(matches!(save_result, Err(SettingsError::Io(_))));
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 7b: Logging of Errors and Informational Messages
     #[test]
-    #[serial]
     fn logging_of_errors_and_info_messages() {
         // Note: Capturing printed output in Rust tests is non-trivial and often not recommended.
         // Instead, consider abstracting logging to a separate component that can be mocked or
@@ -1052,17 +1158,15 @@ use_https = false
         // which can be asserted.
 
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir));
 
         // Create a corrupted user_settings.toml
-        let user_settings_path = Settings::user_settings_path().unwrap();
+        let user_settings_path = dir.user_settings_path().unwrap();
         fs::create_dir_all(user_settings_path.parent().unwrap()).unwrap();
         fs::write(&user_settings_path, "corrupted content").unwrap();
 
         // Create a valid default_settings.toml
-        let default_settings_path = Settings::default_settings_path().unwrap();
+        let default_settings_path = dir.default_settings_path().unwrap();
         fs::create_dir_all(default_settings_path.parent().unwrap()).unwrap();
         let default_settings_content = r#"
             [general]
@@ -1082,7 +1186,7 @@ use_https = false
         fs::write(&default_settings_path, default_settings_content).unwrap();
 
         // Initialize settings, expecting fallback to defaults
-        let settings_result = Settings::initialize_settings();
+        let settings_result = dir.initialize_settings();
         assert!(settings_result.is_ok());
         let settings = settings_result.unwrap();
 
@@ -1097,21 +1201,16 @@ use_https = false
 
         assert_eq!(settings.network.timeout, 45);
         assert_eq!(settings.network.use_https, true);
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 10a: Full Load and Save Cycle (Integration Test)
     #[test]
-    #[serial]
     fn full_load_and_save_cycle() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir));
 
         // Initialize settings (should create defaults)
-        let mut settings = Settings::initialize_settings().unwrap();
+        let mut settings = dir.initialize_settings().unwrap();
 
         // Modify a setting
 
@@ -1119,35 +1218,30 @@ use_https = false
         settings.network.timeout = 90;
 
         // Save settings
-        settings.save_user_settings().unwrap();
+        dir.save_user_settings(&settings).unwrap();
 
         // Reload settings
         let reloaded_settings =
-            Settings::load_from_file(&Settings::user_settings_path().unwrap()).unwrap();
+            Settings::load_from_file(&dir.user_settings_path().unwrap()).unwrap();
 
         // Assert that changes persist
         assert_eq!(reloaded_settings.general.username, "CycleUser");
         assert_eq!(reloaded_settings.network.timeout, 90);
-
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
     }
 
     /// Test Case 10b: Recovery from Corrupted User Settings
     #[test]
-    #[serial]
     fn recovery_from_corrupted_user_settings() {
         let (_temp_dir, config_dir) = setup_temp_config_dir();
-        let original_home = env::var("HOME").unwrap_or_default();
-        let original_xdg_config_home = env::var("XDG_CONFIG_HOME").ok();
-        override_config_dir(&config_dir);
+        let dir = SettingsDir::new(Some(config_dir));
 
         // Create corrupted user_settings.toml
-        let user_settings_path = Settings::user_settings_path().unwrap();
+        let user_settings_path = dir.user_settings_path().unwrap();
         fs::create_dir_all(user_settings_path.parent().unwrap()).unwrap();
         fs::write(&user_settings_path, "corrupted content").unwrap();
 
         // Create a valid default_settings.toml
-        let default_settings_path = Settings::default_settings_path().unwrap();
+        let default_settings_path = dir.default_settings_path().unwrap();
         fs::create_dir_all(default_settings_path.parent().unwrap()).unwrap();
         let default_settings_content = r#"
             [general]
@@ -1167,7 +1261,7 @@ use_https = false
         fs::write(&default_settings_path, default_settings_content).unwrap();
 
         // Initialize settings, expecting fallback to defaults
-        let settings = Settings::initialize_settings().unwrap();
+        let settings = dir.initialize_settings().unwrap();
 
         // Assert that settings match defaults
         assert_eq!(settings.general.username, "DefaultUser");
@@ -1184,7 +1278,183 @@ use_https = false
         // Assert that user_settings.toml is overwritten with default settings
         let loaded_user_settings = Settings::load_from_file(&user_settings_path).unwrap();
         assert_eq!(loaded_user_settings.general.username, "DefaultUser");
+    }
+
+    /// Test Case 9a: Frame Pacing From Renderer Settings
+    #[test]
+    fn renderer_settings_map_to_frame_pacing() {
+        let toml_content = r#"
+            render_scale = 1.0
+            visualize_edges = true
+            visualize_normals = false
+        "#;
+        let mut renderer: RendererSettings = toml::from_str(toml_content).unwrap();
+
+        // Older files without pacing fields keep the 60 FPS sleep cap
+        assert_eq!(renderer.frame_pacing(), FramePacing::SleepTo(60));
+
+        renderer.max_fps = 0;
+        assert_eq!(renderer.frame_pacing(), FramePacing::Uncapped);
+
+        renderer.vsync = true;
+        assert_eq!(renderer.frame_pacing(), FramePacing::Vsync);
+    }
+
+    /// Test Case 11a: JSON Round-Trip Matches TOML Round-Trip
+    #[test]
+    fn json_round_trip_matches_toml_round_trip() {
+        let (_temp_dir, dir) = setup_temp_config_dir();
+        let mut settings = Settings::default();
+        settings.general.username = String::from("JsonUser");
+        settings.renderer.render_scale = 1.5;
+        settings.input.stick_dead_zone = 0.25;
+
+        let toml_path = dir.join("settings.toml");
+        let json_path = dir.join("settings.json");
+        settings.save_to_file(&toml_path).unwrap();
+        settings.save_to_file(&json_path).unwrap();
+
+        let from_toml = Settings::load_from_file(&toml_path).unwrap();
+        let from_json = Settings::load_from_file(&json_path).unwrap();
+        assert_eq!(from_json, from_toml);
+        assert_eq!(from_json, settings);
+        assert!(
+            fs::read_to_string(&json_path)
+                .unwrap()
+                .trim_start()
+                .starts_with('{')
+        );
+    }
 
-        reset_config_dir(&original_home, original_xdg_config_home.as_deref());
+    /// Test Case 11b: Unknown Extensions Are Rejected
+    #[test]
+    fn unknown_settings_extension_is_rejected() {
+        let (_temp_dir, dir) = setup_temp_config_dir();
+        let path = dir.join("settings.yaml");
+
+        let result = Settings::default().save_to_file(&path);
+        assert!(matches!(result, Err(SettingsError::UnknownFormat(p)) if p == path));
+        assert!(!path.exists());
+    }
+
+    /// Test Case 12a: Migrating an Unversioned (v1) User Settings File
+    #[test]
+    fn v1_user_settings_are_migrated_and_written_back() {
+        let (_temp_dir, config_dir) = setup_temp_config_dir();
+        let dir = SettingsDir::new(Some(config_dir));
+
+        let user_settings_path = dir.user_settings_path().unwrap();
+        fs::create_dir_all(user_settings_path.parent().unwrap()).unwrap();
+        let v1_content = r#"
+            [general]
+            username = "OldUser"
+            theme = "dark"
+            auto_save = false
+
+            [renderer]
+            render_scale = 2.0
+            visualize_edges = false
+            visualize_normals = true
+
+            [network]
+            timeout = 60
+            use_https = false
+        "#;
+        fs::write(&user_settings_path, v1_content).unwrap();
+
+        let settings = dir.initialize_settings().unwrap();
+
+        assert_eq!(settings.version, CURRENT_SETTINGS_VERSION);
+        assert_eq!(settings.general.username, "OldUser");
+        assert_eq!(settings.renderer.render_scale, 2.0);
+        assert_eq!(settings.renderer.max_fps, 60);
+        assert!(!settings.renderer.vsync);
+        assert_eq!(settings.input, InputSettings::default());
+
+        let on_disk = Settings::load_from_file(&user_settings_path).unwrap();
+        assert_eq!(on_disk, settings);
+    }
+
+    /// Test Case 12b: Migration Keeps Values the User Already Set
+    #[test]
+    fn migration_keeps_values_present_in_the_file() {
+        let (_temp_dir, config_dir) = setup_temp_config_dir();
+        let dir = SettingsDir::new(Some(config_dir));
+
+        let user_settings_path = dir.user_settings_path().unwrap();
+        fs::create_dir_all(user_settings_path.parent().unwrap()).unwrap();
+        let v1_content = r#"
+            [general]
+            username = "OldUser"
+            theme = "dark"
+            auto_save = false
+
+            [renderer]
+            render_scale = 1.0
+            visualize_edges = false
+            visualize_normals = false
+            vsync = true
+            max_fps = 144
+
+            [network]
+            timeout = 60
+            use_https = false
+        "#;
+        fs::write(&user_settings_path, v1_content).unwrap();
+
+        let settings = dir.initialize_settings().unwrap();
+
+        assert_eq!(settings.version, CURRENT_SETTINGS_VERSION);
+        assert!(settings.renderer.vsync);
+        assert_eq!(settings.renderer.max_fps, 144);
+    }
+
+    /// Test Case 13a: Interrupted Save Leaves the Previous File Intact
+    #[test]
+    fn interrupted_save_leaves_previous_file_intact() {
+        let (_temp_dir, config_dir) = setup_temp_config_dir();
+        let path = config_dir.join("user_settings.toml");
+        let original = Settings::default();
+        original.save_to_file(&path).unwrap();
+
+        // A crash after writing the temporary file but before the rename
+        let temp_path = Settings::write_temp_file(&path, "[general]\nusername = \"Half").unwrap();
+
+        assert!(temp_path.exists());
+        assert_ne!(temp_path, path);
+        assert_eq!(Settings::load_from_file(&path).unwrap(), original);
+
+        // The next successful save replaces both
+        let mut changed = Settings::default();
+        changed.general.username = String::from("AfterCrash");
+        changed.save_to_file(&path).unwrap();
+        assert_eq!(Settings::load_from_file(&path).unwrap(), changed);
+        assert!(!temp_path.exists());
+    }
+
+    /// Test Case 14a: Reverting to Defaults Backs Up the Previous File
+    #[test]
+    fn reset_to_defaults_saves_defaults_and_keeps_a_backup() {
+        let (_temp_dir, config_dir) = setup_temp_config_dir();
+        let dir = SettingsDir::new(Some(config_dir));
+
+        let mut settings = Settings::default();
+        settings.general.username = String::from("BrokenUser");
+        settings.renderer.render_scale = 0.0;
+        dir.save_user_settings(&settings).unwrap();
+
+        dir.reset_user_settings(&mut settings).unwrap();
+
+        assert_eq!(settings, Settings::default());
+        let user_path = dir.user_settings_path().unwrap();
+        assert_eq!(
+            Settings::load_from_file(&user_path).unwrap(),
+            Settings::default()
+        );
+
+        let backup_path = dir.user_settings_backup_path().unwrap();
+        assert!(backup_path.exists());
+        let backup: Settings = toml::from_str(&fs::read_to_string(&backup_path).unwrap()).unwrap();
+        assert_eq!(backup.general.username, "BrokenUser");
     }
 }
@@ -6,11 +6,13 @@ in vec3 v_barycentric;
 in vec3 v_camera_position;
 in vec2 v_uv_albedo;
 in vec2 v_uv_normal;
+in vec3 v_color;
 in mat3 v_tbn;
 
 out vec4 fragColor;
 
 uniform sampler2D u_albedo;
+uniform vec3 u_base_color_factor;
 uniform sampler2D u_normal;
 uniform vec3 u_light_direction;
 uniform vec3 u_light_color;
@@ -47,7 +49,7 @@ vec3 F(vec3 F0, vec3 V, vec3 H) {
 
 void main() {
     // Albedo
-    vec3 albedo = texture(u_albedo, v_uv_albedo).rgb;
+    vec3 albedo = texture(u_albedo, v_uv_albedo).rgb * u_base_color_factor * v_color;
 
     // Normal mapping (tangent → world)
     vec3 N_tangent = texture(u_normal, v_uv_normal).xyz * 2.0 - 1.0;
@@ -7,6 +7,7 @@ layout(location = 2) in vec3 barycentric;
 layout(location = 3) in vec2 uv_albedo;
 layout(location = 4) in vec2 uv_normal;
 layout(location = 5) in vec4 tangent;
+layout(location = 10) in vec3 color;
 
 // Per-instance model matrix
 layout(location = 6) in vec4 instance_model_col0;
@@ -23,6 +24,7 @@ out vec3 v_barycentric;
 out vec3 v_camera_position;
 out vec2 v_uv_albedo;
 out vec2 v_uv_normal;
+out vec3 v_color;
 out mat3 v_tbn;
 
 void main() {
@@ -65,4 +67,5 @@ void main() {
     v_camera_position = u_camera_position;
     v_uv_albedo = uv_albedo;
     v_uv_normal = uv_normal;
+    v_color = color;
 }
\ No newline at end of file